@@ -1,10 +1,15 @@
 //! `config` contains logic for configuring and running the tournament
 
-use std::{sync::{Arc, Mutex}, cmp::Ordering};
+use std::{sync::{Arc, Mutex}, cmp::{self, Ordering}, fs, path::PathBuf};
 use rand::{prelude::*, distributions};
 use threadpool::ThreadPool;
 use constcat::concat;
-use crate::game::{Player, play};
+use crate::game::{Player, Game, Score};
+use crate::simulation::Limits;
+use crate::rules::Schedule;
+use crate::registry::Entry;
+use crate::solver;
+use crate::strategies::{self, FsmStrategy, genetic::Species, lookup, mlp::{self, Mlp}, qlearning::MAX_MEMORY, zd::Relation};
 
 /// The default value for the minimum amount of rounds
 pub const MIN_ROUNDS: u32 = 70;
@@ -21,53 +26,74 @@ pub struct Config {
     rounds: u32,
     show_games: bool,
     threadpool: ThreadPool,
+    simulation: Option<Limits>,
+    schedule: Schedule,
+    bankroll: Option<Score>,
+    seed: Option<u64>,
+    record: Option<PathBuf>,
+    players: Option<Vec<String>>,
+    strategies: Vec<Entry>,
+    penalty: Option<u64>,
 }
 
+/// A total score, `None` if it has overflowed, and a relative win counter.
+type ScoreTotal = Arc<Mutex<(Option<Score>, i32)>>;
+
 impl Config {
     /// Parse command line arguments
-    fn parse_args(args: &[String]) -> Result<Config, &'static str> {
+    fn parse_args(args: &[String]) -> Result<Config, String> {
         // Command line arguments
         let mut min = 0;        // --min <u32>
         let mut max = 0;        // --max <u32>
         let mut games = false; // --games
         let mut threads = 0;  // --threads <u32>
+        let mut open_source = false; // --open-source
+        let mut depth = 0;          // --depth <u32>
+        let mut simulations = 0;   // --simulations <u32>
+        let mut rules = None;     // --rules <path>
+        let mut bankroll = 0;    // --bankroll <i64>
+        let mut seed = None;    // --seed <u64>
+        let mut record = None; // --record <path>
+        let mut players = None; // --players <ids>
+        let mut strategies = None; // --strategies <path>
+        let mut penalty = None;   // --penalty <u64>
 
         let mut i = 1;
         while i < args.len() {
             match args[i].as_str() {
                 "--min" => {
                     if min == 0 {
-                        if let Some(value) = args.iter().skip(i + 1).next().and_then(|s| s.parse().ok()) {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
                             if value > 0 {
                                 min = value;
                                 i += 2;
                                 continue;
                             }
 
-                            return Err("Value must be greater than 0 for argument: --min");
+                            return Err("Value must be greater than 0 for argument: --min".into());
                         }
 
-                        return Err("Incorrect value for argument: --min");
+                        return Err("Incorrect value for argument: --min".into());
                     }
 
-                    return Err("Duplicate argument: --min");
+                    return Err("Duplicate argument: --min".into());
                 }
                 "--max" => {
                     if max == 0 {
-                        if let Some(value) = args.iter().skip(i + 1).next().and_then(|s| s.parse().ok()) {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
                             if value > 0 {
                                 max = value;
                                 i += 2;
                                 continue;
                             }
 
-                            return Err("Value must be greater than 0 for argument: --max");
+                            return Err("Value must be greater than 0 for argument: --max".into());
                         }
 
-                        return Err("Incorrect value for argument: --max");
+                        return Err("Incorrect value for argument: --max".into());
                     }
 
-                    return Err("Duplicate argument: --max");
+                    return Err("Duplicate argument: --max".into());
                 }
                 "--games" => {
                     if !games {
@@ -76,11 +102,11 @@ impl Config {
                         continue;
                     }
 
-                    return Err("Duplicate argument: --games");
+                    return Err("Duplicate argument: --games".into());
                 }
                 "--threads" => {
                     if threads == 0 {
-                        if let Some(value) = args.iter().skip(i + 1).next().and_then(|s| s.parse().ok()) {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
                             if value > 0 && value <= MAX_THREADS {
                                 threads = value;
                                 i += 2;
@@ -88,19 +114,174 @@ impl Config {
                             }
 
                             if value > MAX_THREADS {
-                                return Err(concat!("Value must be less than or equal to ", MAX_THREADS_STRING, " for argument: --threads"));
+                                return Err(concat!("Value must be less than or equal to ", MAX_THREADS_STRING, " for argument: --threads").into());
+                            }
+
+                            return Err("Value must be greater than 0 for argument: --threads".into());
+                        }
+
+                        return Err("Incorrect value for argument: --threads".into());
+                    }
+
+                    return Err("Duplicate argument: --threads".into());
+                }
+                "--open-source" => {
+                    if !open_source {
+                        open_source = true;
+                        i += 1;
+                        continue;
+                    }
+
+                    return Err("Duplicate argument: --open-source".into());
+                }
+                "--depth" => {
+                    if depth == 0 {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                            if value > 0 {
+                                depth = value;
+                                i += 2;
+                                continue;
+                            }
+
+                            return Err("Value must be greater than 0 for argument: --depth".into());
+                        }
+
+                        return Err("Incorrect value for argument: --depth".into());
+                    }
+
+                    return Err("Duplicate argument: --depth".into());
+                }
+                "--simulations" => {
+                    if simulations == 0 {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                            if value > 0 {
+                                simulations = value;
+                                i += 2;
+                                continue;
+                            }
+
+                            return Err("Value must be greater than 0 for argument: --simulations".into());
+                        }
+
+                        return Err("Incorrect value for argument: --simulations".into());
+                    }
+
+                    return Err("Duplicate argument: --simulations".into());
+                }
+                "--rules" => {
+                    if rules.is_none() {
+                        if let Some(path) = args.get(i + 1) {
+                            rules = Some(read_rules(path)?);
+                            i += 2;
+                            continue;
+                        }
+
+                        return Err("Incorrect value for argument: --rules".into());
+                    }
+
+                    return Err("Duplicate argument: --rules".into());
+                }
+                "--bankroll" => {
+                    if bankroll == 0 {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                            if value > 0 {
+                                bankroll = value;
+                                i += 2;
+                                continue;
+                            }
+
+                            return Err("Value must be greater than 0 for argument: --bankroll".into());
+                        }
+
+                        return Err("Incorrect value for argument: --bankroll".into());
+                    }
+
+                    return Err("Duplicate argument: --bankroll".into());
+                }
+                "--seed" => {
+                    if seed.is_none() {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                            seed = Some(value);
+                            i += 2;
+                            continue;
+                        }
+
+                        return Err("Incorrect value for argument: --seed".into());
+                    }
+
+                    return Err("Duplicate argument: --seed".into());
+                }
+                "--record" => {
+                    if record.is_none() {
+                        if let Some(path) = args.get(i + 1) {
+                            record = Some(PathBuf::from(path));
+                            i += 2;
+                            continue;
+                        }
+
+                        return Err("Incorrect value for argument: --record".into());
+                    }
+
+                    return Err("Duplicate argument: --record".into());
+                }
+                "--players" => {
+                    if players.is_none() {
+                        if let Some(value) = args.get(i + 1) {
+                            let ids = value.split(',')
+                                .map(str::trim)
+                                .filter(|id| !id.is_empty())
+                                .map(String::from)
+                                .collect::<Vec<_>>();
+
+                            if ids.len() > 1 {
+                                players = Some(ids);
+                                i += 2;
+                                continue;
+                            }
+
+                            return Err("Value must name at least 2 programs for argument: --players".into());
+                        }
+
+                        return Err("Incorrect value for argument: --players".into());
+                    }
+
+                    return Err("Duplicate argument: --players".into());
+                }
+                "--strategies" => {
+                    if strategies.is_none() {
+                        if let Some(path) = args.get(i + 1) {
+                            let entries = strategies::load_dir(path.as_ref())
+                                .map_err(|error| format!("{error} for argument: --strategies"))?;
+
+                            strategies = Some(entries);
+                            i += 2;
+                            continue;
+                        }
+
+                        return Err("Incorrect value for argument: --strategies".into());
+                    }
+
+                    return Err("Duplicate argument: --strategies".into());
+                }
+                "--penalty" => {
+                    if penalty.is_none() {
+                        if let Some(value) = args.get(i + 1).and_then(|s| s.parse::<u64>().ok()) {
+                            if value > 0 {
+                                penalty = Some(value);
+                                i += 2;
+                                continue;
                             }
 
-                            return Err("Value must be greater than 0 for argument: --threads");
+                            return Err("Value must be greater than 0 for argument: --penalty".into());
                         }
 
-                        return Err("Incorrect value for argument: --threads");
+                        return Err("Incorrect value for argument: --penalty".into());
                     }
 
-                    return Err("Duplicate argument: --threads");
+                    return Err("Duplicate argument: --penalty".into());
                 }
                 _ => {
-                    return Err("Invalid arguments");
+                    return Err("Invalid arguments".into());
                 }
             };
         }
@@ -118,10 +299,38 @@ impl Config {
             threads = DEFAULT_THREADS;
         }
 
+        if !open_source && (depth != 0 || simulations != 0) {
+            return Err("Arguments --depth and --simulations require argument: --open-source".into());
+        }
+
+        let simulation = if open_source {
+            let defaults = Limits::default();
+            Some(Limits::new(
+                if depth == 0 { defaults.depth() } else { depth },
+                if simulations == 0 { defaults.calls() } else { simulations },
+                defaults.time(),
+            ))
+        } else {
+            None
+        };
+
+        let schedule = rules.unwrap_or_default();
+        if schedule.max_score(max).is_none() {
+            return Err("The amount of rounds and the payoffs could exceed the score range".into());
+        }
+
         Ok(Config {
             rounds: random_rounds(min, max),
             show_games: games,
             threadpool: ThreadPool::with_name("Games".into(), threads),
+            simulation,
+            schedule,
+            bankroll: if bankroll == 0 { None } else { Some(bankroll) },
+            seed,
+            record,
+            players,
+            strategies: strategies.unwrap_or_default(),
+            penalty,
         })
     }
 
@@ -137,6 +346,20 @@ impl Config {
     /// * `--max <u32>` - The maximum amount of rounds
     /// * `--games` - Displays all the games outcomes if this is provided
     /// * `--threads <u32>` - Specify the amount of threads used
+    /// * `--open-source` - Allow open-source programs to simulate their opponent
+    /// * `--depth <u32>` - The maximum depth of nested simulations (requires `--open-source`)
+    /// * `--simulations <u32>` - The maximum amount of simulations per move (requires `--open-source`)
+    /// * `--rules <path>` - A file with the [schedule](Schedule) of rules for every game
+    /// * `--bankroll <i64>` - Play a [survival tournament](run_survival) where every program starts with this bankroll
+    /// * `--seed <u64>` - The seed from which the seed of every game is derived, random by default
+    /// * `--record <path>` - A directory to save a [transcript](crate::replay::Transcript) of every game in, which is
+    ///   created when the games are played
+    /// * `--players <ids>` - A comma separated list of [registered](crate::registry::Registry) programs to play,
+    ///   every registered program by default
+    /// * `--strategies <path>` - A directory of [strategy files](strategies::load) and [plugins](strategies::plugin)
+    ///   to register besides the programs
+    /// * `--penalty <u64>` - Subtract a point from a score for every `<u64>` of compute cost, e.g. fuel used by
+    ///   [WASM strategies](strategies::WasmStrategy)
     /// 
     /// If only `--min` is provided, the config will have `rounds == --min`.
     /// Likewise if only `--max` is provided, the config will have `rounds == --max`.
@@ -149,7 +372,7 @@ impl Config {
     /// 
     /// # Errors
     /// 
-    /// If the provided `args` cannot be parsed.
+    /// If the provided `args` cannot be parsed, or if the scores of a game could exceed the range of a [`Score`].
     /// 
     /// # Examples
     /// 
@@ -162,42 +385,120 @@ impl Config {
     /// assert!(config.rounds() == 10);
     /// assert_eq!(error, "Duplicate argument: --threads");
     /// ```
-    pub fn new(args: &[String]) -> Result<Config, &'static str> {
+    pub fn new(args: &[String]) -> Result<Config, String> {
         if args.len() < 2 {
             return Ok(Config::default());
         }
 
-        Config::parse_args(&args)
+        Config::parse_args(args)
     }
 
-    /// Create a new `Config` with default values.
+    /// Get the configured amount of rounds.
     /// 
-    /// # Examples
+    /// # Example
     /// 
     /// ```
     /// # use tourney::config::*;
     /// let config = Config::default();
+    /// 
+    /// assert!(config.rounds() <= MAX_ROUNDS)
     /// ```
-    pub fn default() -> Config {
-        Config {
-            rounds: random_rounds(MIN_ROUNDS, MAX_ROUNDS),
-            show_games: false,
-            threadpool: ThreadPool::with_name("Games".into(), DEFAULT_THREADS),
-        }
+    pub fn rounds(&self) -> u32 {
+        self.rounds
     }
 
-    /// Get the configured amount of rounds.
+    /// Get the simulation limits if the tournament is open-source.
     /// 
     /// # Example
     /// 
     /// ```
     /// # use tourney::config::*;
-    /// let config = Config::default();
+    /// let config = Config::new(&[String::from("tourney"), String::from("--open-source"), String::from("--depth"), String::from("2")]).unwrap();
     /// 
-    /// assert!(config.rounds() <= MAX_ROUNDS)
+    /// assert_eq!(config.simulation().unwrap().depth(), 2);
+    /// assert!(Config::default().simulation().is_none());
     /// ```
-    pub fn rounds(&self) -> u32 {
-        self.rounds
+    pub fn simulation(&self) -> Option<Limits> {
+        self.simulation
+    }
+
+    /// Get the schedule of rules for every game.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get the starting bankroll if the tournament is a [survival tournament](run_survival).
+    pub fn bankroll(&self) -> Option<Score> {
+        self.bankroll
+    }
+
+    /// Get the identifiers of the selected programs, `None` if every registered program plays.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// # use tourney::config::*;
+    /// let config = Config::new(&[String::from("tourney"), String::from("--players"), String::from("tit_for_tat,evil")]).unwrap();
+    /// 
+    /// assert_eq!(config.players().unwrap(), ["tit_for_tat", "evil"]);
+    /// assert!(Config::default().players().is_none());
+    /// ```
+    pub fn players(&self) -> Option<&[String]> {
+        self.players.as_deref()
+    }
+
+    /// Get the strategies loaded from files, which are registered besides the programs.
+    pub fn strategies(&self) -> &[Entry] {
+        &self.strategies
+    }
+
+    /// Get the seed from which the seed of every game is derived, `None` if it is random.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Get the compute cost per point of [penalty](Game::set_penalty), if there is a penalty.
+    pub fn penalty(&self) -> Option<u64> {
+        self.penalty
+    }
+
+    /// Get the largest absolute total a player could reach over a number of games.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `games` - The amount of games played by the player
+    /// * `start` - The score the player starts with
+    /// 
+    /// # Returns
+    /// 
+    /// The bound, or `None` if it does not fit in a [`Score`].
+    fn max_total(&self, games: usize, start: Score) -> Option<Score> {
+        self.schedule.max_score(self.rounds)?
+            .checked_mul(games.try_into().ok()?)?
+            .checked_add(start.abs())
+    }
+
+    /// Create the [game](Game) played between a pair of players.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - The indices of the players
+    fn game(&self, index: (usize, usize)) -> Game {
+        let mut game = Game::new(self.rounds).set_schedule(self.schedule.clone());
+
+        if let Some(limits) = self.simulation {
+            game = game.set_simulation(limits);
+        }
+
+        if let Some(seed) = self.seed {
+            game = game.set_seed(seed ^ ((index.0 as u64) << 32 | index.1 as u64));
+        }
+
+        if let Some(cost) = self.penalty {
+            game = game.set_penalty(cost);
+        }
+
+        game
     }
 
     /// Add a game to the config threadpool.
@@ -206,136 +507,975 @@ impl Config {
     /// 
     /// * `player_1`- A [player](Player)
     /// * `player_2`- A [player](Player) (may be the same as `player_1`)
+    /// * `index` - The indices of the players in the tournament
     /// * `score_totals` - Total score values for the players in the tournament
-    fn add_game(&self, player_1: Player, player_2: Player, score_totals: (Arc<Mutex<(i32, i32)>>, Arc<Mutex<(i32, i32)>>)) {
-        let rounds = self.rounds;
+    fn add_game(&self, player_1: Player, player_2: Player, index: (usize, usize), score_totals: (ScoreTotal, ScoreTotal)) {
+        let game = self.game(index);
         let show_games = self.show_games;
+        let record = self.record.clone();
         self.threadpool.execute(move || {
             let name = format!("{0:>20}  vs.  {1:<20}", player_1.get_name(), player_2.get_name());
-            let scores = play(player_1, player_2, rounds);
+            let scores = match record {
+                Some(path) => game.record(&player_1, &player_2).ok().map(|transcript| {
+                    let path = path.join(format!("{0}_{1}.txt", index.0, index.1));
+                    let saved = fs::create_dir_all(path.parent().unwrap())
+                        .and_then(|_| fs::write(&path, transcript.to_string()));
+                    if let Err(error) = saved {
+                        eprintln!("Could not save {0} ({1})", path.display(), error);
+                    }
+
+                    transcript.scores()
+                }),
+                None => game.play(&player_1, &player_2).ok(),
+            };
 
             if show_games {
-                let output = format!("{0}   {1:>3} - {2:<3}", name, scores.0, scores.1);
+                let output = match scores {
+                    Some(scores) => format!("{0}   {1:>3} - {2:<3}", name, scores.0, scores.1),
+                    None => format!("{0}   overflow", name),
+                };
                 println!("{output}\n");
             }
     
             if let Ok(mut score) = score_totals.0.lock() {
-                score.0 += scores.0;
-                score.1 += match scores.0.cmp(&scores.1) {
-                    Ordering::Less => -1,
-                    Ordering::Equal => 0,
-                    Ordering::Greater => 1,
+                score.0 = score.0.zip(scores).and_then(|(total, scores)| total.checked_add(scores.0));
+                score.1 += match scores.map(|scores| scores.0.cmp(&scores.1)) {
+                    Some(Ordering::Less) => -1,
+                    Some(Ordering::Greater) => 1,
+                    _ => 0,
                 }
             }
     
             if let Ok(mut score) = score_totals.1.lock() {
-                score.0 += scores.1;
-                score.1 += match scores.1.cmp(&scores.0) {
-                    Ordering::Less => -1,
-                    Ordering::Equal => 0,
-                    Ordering::Greater => 1,
+                score.0 = score.0.zip(scores).and_then(|(total, scores)| total.checked_add(scores.1));
+                score.1 += match scores.map(|scores| scores.1.cmp(&scores.0)) {
+                    Some(Ordering::Less) => -1,
+                    Some(Ordering::Greater) => 1,
+                    _ => 0,
                 }
             }
         });
     }
 }
 
-/// Create a vector of atomically reference counted and mutable score and relative win counters.
-/// 
-/// # Arguments
-/// 
-/// * `length` - The length of the vector to be created (typically `length` is equal to the amount of players)
-/// 
-/// # Panics
-/// 
-/// If `length == 0`.
-fn init_scores(length: usize) -> Vec<Arc<Mutex<(i32, i32)>>> {
-    if length == 0 {
-        panic!("Cannot initialize scores with a length of 0");
-    }
-
-    let mut scores = Vec::new();
-    for _ in 0..length {
-        scores.push(Arc::new(Mutex::new((0, 0))));
-    }
-    
-    scores
+/// A command given on the command line.
+#[derive(Debug)]
+pub enum Command {
+    /// Run a tournament, `tourney [arguments]` (see [`Config::new`]).
+    Tournament(Config),
+    /// List every [registered](crate::registry::Registry) program, `tourney list [--strategies <path>]`.
+    List {
+        /// The strategies loaded from files (see [`Config::new`]).
+        strategies: Vec<Entry>,
+    },
+    /// Replay a recorded game, `tourney replay [--verify] [--strategies <path>] <path>`.
+    Replay {
+        /// The path of the [transcript](crate::replay::Transcript).
+        path: String,
+        /// Whether the programs are re-run against the transcript.
+        verify: bool,
+        /// The strategies loaded from files, among which the programs are looked up as well (see [`Config::new`]).
+        strategies: Vec<Entry>,
+    },
+    /// Generate a [zero-determinant strategy](crate::strategies::zd) and measure it against every program,
+    /// `tourney zd <extortion|generous|equalizer> <value> [--rules <path>] [--rounds <u32>] [--save <path>]`
+    /// or `tourney zd relation <alpha> <beta> <gamma> [...]`.
+    Zd {
+        /// The [relation](Relation) to enforce.
+        relation: Relation,
+        /// The rules of the games, of which the initial payoffs are used for the relation.
+        schedule: Schedule,
+        /// The amount of rounds of every game.
+        rounds: u32,
+        /// The path the strategy is saved to.
+        save: Option<PathBuf>,
+    },
+    /// Train a [Q-learning agent](crate::strategies::qlearning) against every program and itself, and save the
+    /// learned table, `tourney train <path> [--memory <usize>] [--episodes <u32>] [--rules <path>] [--rounds <u32>]
+    /// [--seed <u64>]`.
+    Train {
+        /// The path the table is saved to.
+        path: PathBuf,
+        /// The amount of last moves in a state.
+        memory: usize,
+        /// How many times every game is played.
+        episodes: u32,
+        /// The rules of the games.
+        schedule: Schedule,
+        /// The amount of rounds of every game.
+        rounds: u32,
+        /// The seed from which the seed of every game is derived.
+        seed: u64,
+    },
+    /// Evolve a [neural network](crate::strategies::mlp) by its score in tournaments against the selected programs,
+    /// and save it, `tourney neural <path> [--memory <usize>] [--hidden <sizes>] [--generations <u32>]
+    /// [--population <usize>] [--sigma <f64>] [arguments]` (see [`Config::new`] for the other arguments).
+    Neural {
+        /// The path the network is saved to.
+        path: PathBuf,
+        /// The network to start from, with every weight 0.
+        mlp: Mlp,
+        /// The amount of generations.
+        generations: u32,
+        /// The amount of networks per generation.
+        population: usize,
+        /// The standard deviation of the changes to every weight.
+        sigma: f64,
+        /// The configuration of the tournaments the fitness is measured in.
+        config: Config,
+    },
+    /// Evolve [strategies](crate::strategies::genetic) with a genetic algorithm by their score against the selected
+    /// programs, and save the statistics of every generation and a hall of fame to a directory,
+    /// `tourney evolve <mem|fsm|lut> <path> [--states <usize>] [--window <own>,<opponent>] [--population <usize>]
+    /// [--generations <u32>] [--elites <usize>] [--crossover <f64>] [--mutation <f64>] [--hall-of-fame <usize>]
+    /// [arguments]` (see [`Config::new`] for the other arguments).
+    Evolve {
+        /// The directory the statistics and the hall of fame are saved to.
+        path: PathBuf,
+        /// The kind of strategy evolved.
+        species: Species,
+        /// The amount of generations.
+        generations: u32,
+        /// The amount of strategies per generation.
+        population: usize,
+        /// The amount of best strategies kept unchanged.
+        elites: usize,
+        /// The probability that a child is a crossover of two parents.
+        crossover: f64,
+        /// The probability that a gene of a child is changed.
+        mutation: f64,
+        /// The amount of strategies in the hall of fame.
+        hall_of_fame: usize,
+        /// The configuration of the games the fitness is measured in.
+        config: Config,
+    },
+    /// Find the [best response](crate::solver) to every deterministic program, or to the selected ones,
+    /// `tourney solve [<ids>] [--rules <path>] [--rounds <u32>] [--limit <u64>] [--strategies <path>]`.
+    Solve {
+        /// The ids of the programs, separated by commas.
+        players: Option<Vec<String>>,
+        /// The rules of the games.
+        schedule: Schedule,
+        /// The amount of rounds of every game.
+        rounds: u32,
+        /// The amount of moves the search may simulate per program without a known memory.
+        limit: u64,
+        /// The strategies loaded from files (see [`Config::new`]).
+        strategies: Vec<Entry>,
+    },
+    /// Compile the [best response](crate::solver) to a deterministic program into a finite-state machine that falls
+    /// back to another machine when the program deviates from it, and save it, `tourney exploit <id> [--save <path>]
+    /// [--fallback <path>] [--rules <path>] [--rounds <u32>] [--limit <u64>] [--strategies <path>]`.
+    /// The machine is registered as `exploit_<id>` and plays every program; later tournaments load it with
+    /// `--strategies`.
+    Exploit {
+        /// The id of the program.
+        player: String,
+        /// The path the machine is saved to, `strategies/exploit_<id>.fsm` by default.
+        path: PathBuf,
+        /// The machine played after a deviation, tit for tat by default.
+        fallback: FsmStrategy,
+        /// The rules of the game.
+        schedule: Schedule,
+        /// The amount of rounds of the game.
+        rounds: u32,
+        /// The amount of moves the search may simulate if the program has no known memory.
+        limit: u64,
+        /// The strategies loaded from files (see [`Config::new`]).
+        strategies: Vec<Entry>,
+    },
 }
 
-/// Uniformly randomly select an amount of rounds between `min` and `max` (inclusive).
-/// 
-/// # Arguments
-/// 
-/// * `min` - The minimum amount of rounds
-/// * `max` - The maximum amount of rounds
-/// 
-/// # Panics
-/// 
-/// If `max < min` or if `min == 0`.
-fn random_rounds(min: u32, max: u32) -> u32 {
-    if max < min {
-        panic!("max rounds cannot be less than min rounds");
+impl Command {
+    /// Try to create a new `Command` from command line arguments.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `args` - Command line arguments
+    /// 
+    /// # Errors
+    /// 
+    /// If the provided `args` cannot be parsed.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::config::*;
+    /// 
+    /// let command = Command::new(&[String::from("tourney"), String::from("replay"), String::from("--verify"), String::from("game.txt")]).unwrap();
+    /// 
+    /// assert!(matches!(command, Command::Replay { verify: true, .. }));
+    /// assert!(matches!(Command::new(&[String::from("tourney"), String::from("list")]).unwrap(), Command::List { .. }));
+    /// assert!(matches!(Command::new(&[String::from("tourney")]).unwrap(), Command::Tournament(_)));
+    /// ```
+    pub fn new(args: &[String]) -> Result<Command, String> {
+        match args.get(1).map(String::as_str) {
+            Some("replay") => Command::parse_replay(&args[2..]),
+            Some("list") => Command::parse_list(&args[2..]),
+            Some("zd") => Command::parse_zd(&args[2..]),
+            Some("train") => Command::parse_train(&args[2..]),
+            Some("neural") => Command::parse_neural(&args[2..]),
+            Some("evolve") => Command::parse_evolve(&args[2..]),
+            Some("solve") => Command::parse_solve(&args[2..]),
+            Some("exploit") => Command::parse_exploit(&args[2..]),
+            _ => Config::new(args).map(Command::Tournament),
+        }
     }
 
-    if min == 0 {
-        panic!("min rounds cannot not be 0");
+    /// Parse the arguments of the `list` command.
+    fn parse_list(args: &[String]) -> Result<Command, String> {
+        match args {
+            [] => Ok(Command::List { strategies: Vec::new() }),
+            [flag, path] if flag == "--strategies" => {
+                let strategies = strategies::load_dir(path.as_ref())
+                    .map_err(|error| format!("{error} for argument: --strategies"))?;
+
+                Ok(Command::List { strategies })
+            }
+            _ => Err("Invalid arguments".into()),
+        }
     }
 
-    distributions::Uniform::from(min..max+1).sample(&mut rand::thread_rng())
-}
+    /// Parse the arguments of the `zd` command.
+    fn parse_zd(args: &[String]) -> Result<Command, String> {
+        let mut kind = None;
+        let mut values = Vec::new();
+        let mut schedule = None;
+        let mut rounds = None;
+        let mut save = None;
 
-/// Run a tournament of the [game](crate::game).
-/// 
-/// # Arguments
-/// 
-/// * `config` - A [config](Config) specifying rounds, output, etc.
-/// * `players` - The [players](Player) for this tournament
-/// 
-/// # Returns
-/// 
-/// A list of the program names and associated scores sorted in descending order according to their scores.
-/// 
-/// # Errors
-/// 
-/// If `players.len() < 2` an error is returned.
-/// 
-/// # Examples
-/// 
-/// ```
-/// # use tourney::programs::all::*;
-/// use tourney::config::*;
-/// use tourney::game::Player;
-/// 
-/// let config_1 = Config::new(&[String::from("tourney"), String::from("--min"), String::from("10")]).unwrap();
-///
-/// let players = vec![
-///     Player::with_name("1", greedy_blue_and_evil),
-///     Player::with_name("2", take_back_once_prisoner),
-/// ];
-///
-/// let scores_1 = run(&config_1, &players).unwrap();
-/// 
-/// assert_eq!([(scores_1[0].0, scores_1[0].2), (scores_1[1].0, scores_1[1].2)], [(36, "1"), (5, "2")]);
-/// ```
-pub fn run<'a>(config: &Config, players: &'a Vec<Player>) -> Result<Vec<(i32, f32, &'a str)>, &'static str> {
-    if players.len() < 2 {
-        return Err("Too few players");
-    }
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--rules" => {
+                    if schedule.is_some() {
+                        return Err("Duplicate argument: --rules".into());
+                    }
 
-    let player_count = players.len();
+                    let path = args.get(i + 1).ok_or("Incorrect value for argument: --rules")?;
+                    schedule = Some(read_rules(path)?);
+                    i += 2;
+                }
+                "--rounds" => {
+                    if rounds.is_some() {
+                        return Err("Duplicate argument: --rounds".into());
+                    }
 
-    let scores = init_scores(player_count);
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => rounds = Some(value),
+                        _ => return Err("Incorrect value for argument: --rounds".into()),
+                    }
+                    i += 2;
+                }
+                "--save" => {
+                    if save.is_some() {
+                        return Err("Duplicate argument: --save".into());
+                    }
 
-    for i in 0..player_count {
-        for j in 0..player_count {
-            if i >= j {
-                continue;
+                    let path = args.get(i + 1).ok_or("Incorrect value for argument: --save")?;
+                    save = Some(PathBuf::from(path));
+                    i += 2;
+                }
+                name @ ("extortion" | "generous" | "equalizer" | "relation") if kind.is_none() => {
+                    let count = if name == "relation" { 3 } else { 1 };
+                    values = args.iter()
+                        .skip(i + 1)
+                        .take(count)
+                        .map(|value| value.parse::<f64>().ok().filter(|value| value.is_finite()))
+                        .collect::<Option<Vec<_>>>()
+                        .filter(|values| values.len() == count)
+                        .ok_or_else(|| format!("Expected {count} value(s) for relation: {name}"))?;
+
+                    kind = Some(name);
+                    i += 1 + count;
+                }
+                _ => return Err("Invalid arguments".into()),
             }
+        }
 
-            config.add_game(
-                players[i].clone(),
-                players[j].clone(),
-                (scores[i].clone(), scores[j].clone()),
+        let schedule = schedule.unwrap_or_default();
+        let payoffs = schedule.initial().payoffs();
+        let relation = match kind {
+            Some("extortion") => Relation::extortion(payoffs, values[0]),
+            Some("generous") => Relation::generous(payoffs, values[0]),
+            Some("equalizer") => Relation::equalizer(values[0]),
+            Some(_) => Relation::new(values[0], values[1], values[2]),
+            None => return Err("Missing relation for command: zd".into()),
+        };
+
+        Ok(Command::Zd {
+            relation,
+            schedule,
+            rounds: rounds.unwrap_or(MAX_ROUNDS * 100),
+            save,
+        })
+    }
+
+    /// Parse the arguments of the `train` command.
+    fn parse_train(args: &[String]) -> Result<Command, String> {
+        let mut path = None;
+        let mut memory = None;
+        let mut episodes = None;
+        let mut schedule = None;
+        let mut rounds = None;
+        let mut seed = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--memory" => {
+                    if memory.is_some() {
+                        return Err("Duplicate argument: --memory".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<usize>().ok()) {
+                        Some(value) if value <= MAX_MEMORY => memory = Some(value),
+                        _ => return Err("Incorrect value for argument: --memory".into()),
+                    }
+                    i += 2;
+                }
+                "--episodes" => {
+                    if episodes.is_some() {
+                        return Err("Duplicate argument: --episodes".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => episodes = Some(value),
+                        _ => return Err("Incorrect value for argument: --episodes".into()),
+                    }
+                    i += 2;
+                }
+                "--rules" => {
+                    if schedule.is_some() {
+                        return Err("Duplicate argument: --rules".into());
+                    }
+
+                    let path = args.get(i + 1).ok_or("Incorrect value for argument: --rules")?;
+                    schedule = Some(read_rules(path)?);
+                    i += 2;
+                }
+                "--rounds" => {
+                    if rounds.is_some() {
+                        return Err("Duplicate argument: --rounds".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => rounds = Some(value),
+                        _ => return Err("Incorrect value for argument: --rounds".into()),
+                    }
+                    i += 2;
+                }
+                "--seed" => {
+                    if seed.is_some() {
+                        return Err("Duplicate argument: --seed".into());
+                    }
+
+                    let value = args.get(i + 1).and_then(|s| s.parse::<u64>().ok());
+                    seed = Some(value.ok_or("Incorrect value for argument: --seed")?);
+                    i += 2;
+                }
+                arg if path.is_none() && !arg.starts_with("--") => {
+                    path = Some(PathBuf::from(arg));
+                    i += 1;
+                }
+                _ => return Err("Invalid arguments".into()),
+            }
+        }
+
+        Ok(Command::Train {
+            path: path.ok_or("Missing path for command: train")?,
+            memory: memory.unwrap_or(2),
+            episodes: episodes.unwrap_or(100),
+            schedule: schedule.unwrap_or_default(),
+            rounds: rounds.unwrap_or(MAX_ROUNDS),
+            seed: seed.unwrap_or_else(|| thread_rng().gen()),
+        })
+    }
+
+    /// Parse the arguments of the `neural` command. Arguments it does not know are passed on to [`Config::new`].
+    fn parse_neural(args: &[String]) -> Result<Command, String> {
+        let mut path = None;
+        let mut memory = None;
+        let mut hidden = None;
+        let mut generations = None;
+        let mut population = None;
+        let mut sigma = None;
+        let mut rest = vec![String::from("tourney")];
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--memory" => {
+                    if memory.is_some() {
+                        return Err("Duplicate argument: --memory".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<usize>().ok()) {
+                        Some(value) if value <= mlp::MAX_MEMORY => memory = Some(value),
+                        _ => return Err("Incorrect value for argument: --memory".into()),
+                    }
+                    i += 2;
+                }
+                "--hidden" => {
+                    if hidden.is_some() {
+                        return Err("Duplicate argument: --hidden".into());
+                    }
+
+                    let sizes = args.get(i + 1)
+                        .and_then(|s| s.split(',').map(|size| size.parse::<usize>().ok()).collect::<Option<Vec<_>>>())
+                        .filter(|sizes| sizes.len() <= mlp::MAX_LAYERS && sizes.iter().all(|size| (1..=mlp::MAX_NEURONS).contains(size)));
+                    hidden = Some(sizes.ok_or("Incorrect value for argument: --hidden")?);
+                    i += 2;
+                }
+                "--generations" => {
+                    if generations.is_some() {
+                        return Err("Duplicate argument: --generations".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => generations = Some(value),
+                        _ => return Err("Incorrect value for argument: --generations".into()),
+                    }
+                    i += 2;
+                }
+                "--population" => {
+                    if population.is_some() {
+                        return Err("Duplicate argument: --population".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<usize>().ok()) {
+                        Some(value) if value > 0 => population = Some(value),
+                        _ => return Err("Incorrect value for argument: --population".into()),
+                    }
+                    i += 2;
+                }
+                "--sigma" => {
+                    if sigma.is_some() {
+                        return Err("Duplicate argument: --sigma".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<f64>().ok()) {
+                        Some(value) if value > 0.0 && value.is_finite() => sigma = Some(value),
+                        _ => return Err("Incorrect value for argument: --sigma".into()),
+                    }
+                    i += 2;
+                }
+                arg if path.is_none() && !arg.starts_with("--") => {
+                    path = Some(PathBuf::from(arg));
+                    i += 1;
+                }
+                arg => {
+                    rest.push(String::from(arg));
+                    i += 1;
+                }
+            }
+        }
+
+        let path = path.ok_or("Missing path for command: neural")?;
+        let mlp = Mlp::new(memory.unwrap_or(2), &hidden.unwrap_or_else(|| vec![8]))
+            .ok_or("Network larger than the maximum")?;
+
+        Ok(Command::Neural {
+            path,
+            mlp,
+            generations: generations.unwrap_or(20),
+            population: population.unwrap_or(16),
+            sigma: sigma.unwrap_or(0.5),
+            config: Config::new(&rest)?,
+        })
+    }
+
+    /// Parse the arguments of the `evolve` command. Arguments it does not know are passed on to [`Config::new`].
+    fn parse_evolve(args: &[String]) -> Result<Command, String> {
+        let mut kind = None;
+        let mut path = None;
+        let mut states = None;
+        let mut window = None;
+        let mut generations = None;
+        let mut population = None;
+        let mut elites = None;
+        let mut crossover = None;
+        let mut mutation = None;
+        let mut hall_of_fame = None;
+        let mut rest = vec![String::from("tourney")];
+
+        let probability = |arg: Option<&String>| arg
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| (0.0..=1.0).contains(value));
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--states" => {
+                    if states.is_some() {
+                        return Err("Duplicate argument: --states".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<usize>().ok()) {
+                        Some(value) if value > 0 => states = Some(value),
+                        _ => return Err("Incorrect value for argument: --states".into()),
+                    }
+                    i += 2;
+                }
+                "--window" => {
+                    if window.is_some() {
+                        return Err("Duplicate argument: --window".into());
+                    }
+
+                    let value = args.get(i + 1)
+                        .and_then(|s| s.split_once(','))
+                        .and_then(|(own, opponent)| Some((own.parse::<usize>().ok()?, opponent.parse::<usize>().ok()?)))
+                        .filter(|(own, opponent)| own + opponent <= lookup::MAX_WINDOW);
+                    window = Some(value.ok_or("Incorrect value for argument: --window")?);
+                    i += 2;
+                }
+                "--generations" => {
+                    if generations.is_some() {
+                        return Err("Duplicate argument: --generations".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => generations = Some(value),
+                        _ => return Err("Incorrect value for argument: --generations".into()),
+                    }
+                    i += 2;
+                }
+                "--population" => {
+                    if population.is_some() {
+                        return Err("Duplicate argument: --population".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<usize>().ok()) {
+                        Some(value) if value > 0 => population = Some(value),
+                        _ => return Err("Incorrect value for argument: --population".into()),
+                    }
+                    i += 2;
+                }
+                "--elites" => {
+                    if elites.is_some() {
+                        return Err("Duplicate argument: --elites".into());
+                    }
+
+                    let value = args.get(i + 1).and_then(|s| s.parse::<usize>().ok());
+                    elites = Some(value.ok_or("Incorrect value for argument: --elites")?);
+                    i += 2;
+                }
+                "--crossover" => {
+                    if crossover.is_some() {
+                        return Err("Duplicate argument: --crossover".into());
+                    }
+
+                    crossover = Some(probability(args.get(i + 1)).ok_or("Incorrect value for argument: --crossover")?);
+                    i += 2;
+                }
+                "--mutation" => {
+                    if mutation.is_some() {
+                        return Err("Duplicate argument: --mutation".into());
+                    }
+
+                    mutation = Some(probability(args.get(i + 1)).ok_or("Incorrect value for argument: --mutation")?);
+                    i += 2;
+                }
+                "--hall-of-fame" => {
+                    if hall_of_fame.is_some() {
+                        return Err("Duplicate argument: --hall-of-fame".into());
+                    }
+
+                    let value = args.get(i + 1).and_then(|s| s.parse::<usize>().ok());
+                    hall_of_fame = Some(value.ok_or("Incorrect value for argument: --hall-of-fame")?);
+                    i += 2;
+                }
+                arg if kind.is_none() && !arg.starts_with("--") => {
+                    kind = Some(String::from(arg));
+                    i += 1;
+                }
+                arg if path.is_none() && !arg.starts_with("--") => {
+                    path = Some(PathBuf::from(arg));
+                    i += 1;
+                }
+                arg => {
+                    rest.push(String::from(arg));
+                    i += 1;
+                }
+            }
+        }
+
+        let species = match (kind.as_deref(), states, window) {
+            (Some("mem"), None, None) => Species::MemoryOne,
+            (Some("fsm"), states, None) => Species::Fsm(states.unwrap_or(4)),
+            (Some("lut"), None, window) => {
+                let (own, opponent) = window.unwrap_or((2, 2));
+                Species::Lookup(own, opponent)
+            }
+            (None, ..) => return Err("Missing genome for command: evolve".into()),
+            (Some("mem" | "fsm" | "lut"), ..) => return Err("Invalid arguments".into()),
+            (Some(kind), ..) => return Err(format!("Unknown genome: {kind}")),
+        };
+
+        Ok(Command::Evolve {
+            path: path.ok_or("Missing path for command: evolve")?,
+            species,
+            generations: generations.unwrap_or(30),
+            population: population.unwrap_or(50),
+            elites: elites.unwrap_or(2),
+            crossover: crossover.unwrap_or(0.7),
+            mutation: mutation.unwrap_or(0.05),
+            hall_of_fame: hall_of_fame.unwrap_or(10),
+            config: Config::new(&rest)?,
+        })
+    }
+
+    /// Parse the arguments of the `solve` command.
+    fn parse_solve(args: &[String]) -> Result<Command, String> {
+        let mut players = None;
+        let mut schedule = None;
+        let mut rounds = None;
+        let mut limit = None;
+        let mut strategies = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--rules" => {
+                    if schedule.is_some() {
+                        return Err("Duplicate argument: --rules".into());
+                    }
+
+                    let path = args.get(i + 1).ok_or("Incorrect value for argument: --rules")?;
+                    schedule = Some(read_rules(path)?);
+                    i += 2;
+                }
+                "--rounds" => {
+                    if rounds.is_some() {
+                        return Err("Duplicate argument: --rounds".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => rounds = Some(value),
+                        _ => return Err("Incorrect value for argument: --rounds".into()),
+                    }
+                    i += 2;
+                }
+                "--limit" => {
+                    if limit.is_some() {
+                        return Err("Duplicate argument: --limit".into());
+                    }
+
+                    let value = args.get(i + 1).and_then(|s| s.parse::<u64>().ok());
+                    limit = Some(value.ok_or("Incorrect value for argument: --limit")?);
+                    i += 2;
+                }
+                "--strategies" => {
+                    if strategies.is_some() {
+                        return Err("Duplicate argument: --strategies".into());
+                    }
+
+                    let path = args.get(i + 1).ok_or("Incorrect value for argument: --strategies")?;
+                    let entries = strategies::load_dir(path.as_ref())
+                        .map_err(|error| format!("{error} for argument: --strategies"))?;
+
+                    strategies = Some(entries);
+                    i += 2;
+                }
+                arg if players.is_none() && !arg.starts_with("--") => {
+                    let ids = arg.split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(String::from)
+                        .collect::<Vec<_>>();
+
+                    players = Some(ids);
+                    i += 1;
+                }
+                _ => return Err("Invalid arguments".into()),
+            }
+        }
+
+        Ok(Command::Solve {
+            players,
+            schedule: schedule.unwrap_or_default(),
+            rounds: rounds.unwrap_or(MAX_ROUNDS),
+            limit: limit.unwrap_or(solver::DEFAULT_LIMIT),
+            strategies: strategies.unwrap_or_default(),
+        })
+    }
+
+    /// Parse the arguments of the `exploit` command.
+    fn parse_exploit(args: &[String]) -> Result<Command, String> {
+        let mut player = None;
+        let mut path = None;
+        let mut fallback = None;
+        let mut schedule = None;
+        let mut rounds = None;
+        let mut limit = None;
+        let mut strategies = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--save" => {
+                    if path.is_some() {
+                        return Err("Duplicate argument: --save".into());
+                    }
+
+                    let value = args.get(i + 1).ok_or("Incorrect value for argument: --save")?;
+                    path = Some(PathBuf::from(value));
+                    i += 2;
+                }
+                "--fallback" => {
+                    if fallback.is_some() {
+                        return Err("Duplicate argument: --fallback".into());
+                    }
+
+                    let value = args.get(i + 1).ok_or("Incorrect value for argument: --fallback")?;
+                    let machine = fs::read_to_string(value)
+                        .map_err(|error| format!("Could not read {value} ({error})"))?
+                        .parse::<FsmStrategy>()
+                        .map_err(|error| format!("Invalid strategy in {value} ({error})"))?;
+
+                    fallback = Some(machine);
+                    i += 2;
+                }
+                "--rules" => {
+                    if schedule.is_some() {
+                        return Err("Duplicate argument: --rules".into());
+                    }
+
+                    let value = args.get(i + 1).ok_or("Incorrect value for argument: --rules")?;
+                    schedule = Some(read_rules(value)?);
+                    i += 2;
+                }
+                "--rounds" => {
+                    if rounds.is_some() {
+                        return Err("Duplicate argument: --rounds".into());
+                    }
+
+                    match args.get(i + 1).and_then(|s| s.parse::<u32>().ok()) {
+                        Some(value) if value > 0 => rounds = Some(value),
+                        _ => return Err("Incorrect value for argument: --rounds".into()),
+                    }
+                    i += 2;
+                }
+                "--limit" => {
+                    if limit.is_some() {
+                        return Err("Duplicate argument: --limit".into());
+                    }
+
+                    let value = args.get(i + 1).and_then(|s| s.parse::<u64>().ok());
+                    limit = Some(value.ok_or("Incorrect value for argument: --limit")?);
+                    i += 2;
+                }
+                "--strategies" => {
+                    if strategies.is_some() {
+                        return Err("Duplicate argument: --strategies".into());
+                    }
+
+                    let value = args.get(i + 1).ok_or("Incorrect value for argument: --strategies")?;
+                    let entries = strategies::load_dir(value.as_ref())
+                        .map_err(|error| format!("{error} for argument: --strategies"))?;
+
+                    strategies = Some(entries);
+                    i += 2;
+                }
+                arg if player.is_none() && !arg.starts_with("--") => {
+                    player = Some(String::from(arg));
+                    i += 1;
+                }
+                _ => return Err("Invalid arguments".into()),
+            }
+        }
+
+        let player = player.ok_or("Missing program for command: exploit")?;
+        Ok(Command::Exploit {
+            path: path.unwrap_or_else(|| PathBuf::from("strategies").join(format!("exploit_{player}.fsm"))),
+            player,
+            fallback: fallback.unwrap_or_else(solver::tit_for_tat),
+            schedule: schedule.unwrap_or_default(),
+            rounds: rounds.unwrap_or(MAX_ROUNDS),
+            limit: limit.unwrap_or(solver::DEFAULT_LIMIT),
+            strategies: strategies.unwrap_or_default(),
+        })
+    }
+
+    /// Parse the arguments of the `replay` command.
+    fn parse_replay(args: &[String]) -> Result<Command, String> {
+        let mut path = None;
+        let mut verify = false;
+        let mut strategies = None;
+
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--verify" => {
+                    if verify {
+                        return Err("Duplicate argument: --verify".into());
+                    }
+
+                    verify = true;
+                    i += 1;
+                }
+                "--strategies" => {
+                    if strategies.is_some() {
+                        return Err("Duplicate argument: --strategies".into());
+                    }
+
+                    let value = args.get(i + 1).ok_or("Incorrect value for argument: --strategies")?;
+                    let entries = strategies::load_dir(value.as_ref())
+                        .map_err(|error| format!("{error} for argument: --strategies"))?;
+
+                    strategies = Some(entries);
+                    i += 2;
+                }
+                arg if path.is_none() && !arg.starts_with("--") => {
+                    path = Some(String::from(arg));
+                    i += 1;
+                }
+                _ => return Err("Invalid arguments".into()),
+            }
+        }
+
+        match path {
+            Some(path) => Ok(Command::Replay { path, verify, strategies: strategies.unwrap_or_default() }),
+            None => Err("Missing transcript for command: replay".into()),
+        }
+    }
+}
+
+impl Default for Config {
+    /// Create a new `Config` with default values.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use tourney::config::*;
+    /// let config = Config::default();
+    /// ```
+    fn default() -> Config {
+        Config {
+            rounds: random_rounds(MIN_ROUNDS, MAX_ROUNDS),
+            show_games: false,
+            threadpool: ThreadPool::with_name("Games".into(), DEFAULT_THREADS),
+            simulation: None,
+            schedule: Schedule::default(),
+            bankroll: None,
+            seed: None,
+            record: None,
+            players: None,
+            strategies: Vec::new(),
+            penalty: None,
+        }
+    }
+}
+
+/// Read a [schedule](Schedule) of rules from a file.
+fn read_rules(path: &str) -> Result<Schedule, String> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("Could not read {path} for argument: --rules ({error})"))?
+        .parse::<Schedule>()
+        .map_err(|error| format!("Invalid rules in {path} for argument: --rules ({error})"))
+}
+
+/// Create a vector of atomically reference counted and mutable score and relative win counters.
+/// 
+/// # Arguments
+/// 
+/// * `length` - The length of the vector to be created (typically `length` is equal to the amount of players)
+/// 
+/// # Panics
+/// 
+/// If `length == 0`.
+fn init_scores(length: usize) -> Vec<ScoreTotal> {
+    if length == 0 {
+        panic!("Cannot initialize scores with a length of 0");
+    }
+
+    let mut scores = Vec::new();
+    for _ in 0..length {
+        scores.push(Arc::new(Mutex::new((Some(0), 0))));
+    }
+    
+    scores
+}
+
+/// Uniformly randomly select an amount of rounds between `min` and `max` (inclusive).
+/// 
+/// # Arguments
+/// 
+/// * `min` - The minimum amount of rounds
+/// * `max` - The maximum amount of rounds
+/// 
+/// # Panics
+/// 
+/// If `max < min` or if `min == 0`.
+fn random_rounds(min: u32, max: u32) -> u32 {
+    if max < min {
+        panic!("max rounds cannot be less than min rounds");
+    }
+
+    if min == 0 {
+        panic!("min rounds cannot not be 0");
+    }
+
+    distributions::Uniform::from(min..max+1).sample(&mut rand::thread_rng())
+}
+
+/// Run a tournament of the [game](crate::game).
+/// 
+/// # Arguments
+/// 
+/// * `config` - A [config](Config) specifying rounds, output, etc.
+/// * `players` - The [players](Player) for this tournament
+/// 
+/// # Returns
+/// 
+/// A list of the program names and associated scores sorted in descending order according to their scores.
+/// 
+/// # Errors
+/// 
+/// If `players.len() < 2`, or if the total scores could overflow, an error is returned.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use tourney::programs::all::*;
+/// use tourney::config::*;
+/// use tourney::game::Player;
+/// 
+/// let config_1 = Config::new(&[String::from("tourney"), String::from("--min"), String::from("10")]).unwrap();
+///
+/// let players = vec![
+///     Player::with_name("1", greedy_blue_and_evil),
+///     Player::with_name("2", take_back_once_prisoner),
+/// ];
+///
+/// let scores_1 = run(&config_1, &players).unwrap();
+/// 
+/// assert_eq!([(scores_1[0].0, scores_1[0].2), (scores_1[1].0, scores_1[1].2)], [(36, "1"), (5, "2")]);
+/// ```
+pub fn run<'a>(config: &Config, players: &'a [Player]) -> Result<Vec<(Score, f32, &'a str)>, &'static str> {
+    if players.len() < 2 {
+        return Err("Too few players");
+    }
+
+    if config.max_total(players.len() - 1, 0).is_none() {
+        return Err("Too many players for the score range");
+    }
+
+    let player_count = players.len();
+
+    let scores = init_scores(player_count);
+
+    for i in 0..player_count {
+        for j in 0..player_count {
+            if i >= j {
+                continue;
+            }
+
+            config.add_game(
+                players[i].clone(),
+                players[j].clone(),
+                (i, j),
+                (scores[i].clone(), scores[j].clone()),
             );
         }
     }
@@ -346,25 +1486,260 @@ pub fn run<'a>(config: &Config, players: &'a Vec<Player>) -> Result<Vec<(i32, f3
         .enumerate()
         .map(|(i, v)| {
             let v = v.lock().unwrap();
-            (v.0, v.1 as f32 / (players.len() - 1) as f32, players[i].get_name())
+            Some((v.0?, v.1 as f32 / (players.len() - 1) as f32, players[i].get_name()))
         })
-        .collect::<Vec<_>>();
-    scores.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        .collect::<Option<Vec<_>>>()
+        .ok_or("Score overflow")?;
+    scores.sort_unstable_by_key(|v| v.0);
 
     Ok(scores.iter()
         .rev()
-        .map(|v| *v)
+        .copied()
         .collect()
     )
 }
 
+/// Run every candidate against every player of a roster, without the games between candidates or within the roster.
+///
+/// # Arguments
+///
+/// * `config` - A [config](Config) specifying rounds, output, etc.
+/// * `candidates` - The [players](Player) that are scored
+/// * `roster` - The [players](Player) every candidate plays against
+///
+/// # Returns
+///
+/// The total score of every candidate, in the order of `candidates`.
+///
+/// # Errors
+///
+/// If there are no candidates or no players in the roster, or if the total scores could overflow, an error is
+/// returned.
+///
+/// # Examples
+///
+/// ```
+/// # use tourney::programs::all::*;
+/// use tourney::config::*;
+/// use tourney::game::Player;
+///
+/// let config = Config::new(&[String::from("tourney"), String::from("--min"), String::from("10")]).unwrap();
+///
+/// let candidates = [Player::new(evil), Player::new(friendly)];
+/// let roster = [Player::new(friendly), Player::new(friendly)];
+///
+/// assert_eq!(run_against(&config, &candidates, &roster), Ok(vec![60, 40]));
+/// ```
+pub fn run_against(config: &Config, candidates: &[Player], roster: &[Player]) -> Result<Vec<Score>, &'static str> {
+    if candidates.is_empty() || roster.is_empty() {
+        return Err("Too few players");
+    }
+
+    if config.max_total(roster.len(), 0).is_none() {
+        return Err("Too many players for the score range");
+    }
+
+    let scores = init_scores(candidates.len() + roster.len());
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        for (j, player) in roster.iter().enumerate() {
+            let j = candidates.len() + j;
+            config.add_game(
+                candidate.clone(),
+                player.clone(),
+                (i, j),
+                (scores[i].clone(), scores[j].clone()),
+            );
+        }
+    }
+
+    config.threadpool.join();
+
+    scores[..candidates.len()].iter()
+        .map(|v| v.lock().unwrap().0)
+        .collect::<Option<Vec<_>>>()
+        .ok_or("Score overflow")
+}
+
+/// Schedule a round robin in rounds, such that every player plays at most one game per round.
+/// 
+/// # Arguments
+/// 
+/// * `length` - The amount of players
+/// 
+/// # Returns
+/// 
+/// The pairs of player indices for every round. With an odd amount of players, one player sits out each round.
+fn round_robin(length: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut indices = (0..length).map(Some).collect::<Vec<_>>();
+    if length % 2 == 1 {
+        indices.push(None);
+    }
+
+    let mut rounds = Vec::new();
+    for _ in 1..indices.len() {
+        let pairs = (0..indices.len() / 2)
+            .filter_map(|k| match (indices[k], indices[indices.len() - 1 - k]) {
+                (Some(i), Some(j)) => Some((i.min(j), i.max(j))),
+                _ => None,
+            })
+            .collect();
+        rounds.push(pairs);
+
+        indices[1..].rotate_right(1);
+    }
+
+    rounds
+}
+
+/// A program knocked out of a [survival tournament](run_survival).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elimination<'a> {
+    /// The name of the program.
+    pub name: &'a str,
+    /// The round of the schedule (counting from 1) in which the bankroll of the program hit zero.
+    pub round: usize,
+    /// The bankroll of the program when it was eliminated.
+    pub bankroll: Score,
+}
+
+/// The outcome of a [survival tournament](run_survival).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Survival<'a> {
+    /// The bankrolls and names of the remaining programs, sorted in descending order according to their bankrolls.
+    pub standings: Vec<(Score, &'a str)>,
+    /// The eliminated programs in the order they were eliminated.
+    pub eliminations: Vec<Elimination<'a>>,
+    /// The amount of rounds in the schedule.
+    pub rounds: usize,
+}
+
+/// Run a survival tournament of the [game](crate::game).
+/// 
+/// Every program starts with the [configured](Config::bankroll) bankroll which carries across its games.
+/// Every pair of programs is scheduled to play once, in rounds where each program plays at most one game.
+/// After each round, programs with a bankroll of zero or less are eliminated from the remaining schedule.
+/// 
+/// # Arguments
+/// 
+/// * `config` - A [config](Config) with a bankroll
+/// * `players` - The [players](Player) for this tournament
+/// 
+/// # Returns
+/// 
+/// The remaining programs with their bankrolls, and the eliminated programs in the order they were eliminated.
+/// 
+/// # Errors
+/// 
+/// If `players.len() < 2`, if the config has no bankroll, or if the bankrolls could overflow, an error is returned.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use tourney::programs::all::*;
+/// use tourney::config::*;
+/// use tourney::game::Player;
+/// 
+/// let config = Config::new(&[String::from("tourney"), String::from("--min"), String::from("10"), String::from("--bankroll"), String::from("5")]).unwrap();
+/// 
+/// let players = vec![
+///     Player::with_name("Blue", blue),
+///     Player::with_name("Evil", evil),
+///     Player::with_name("Friendly", friendly),
+/// ];
+/// 
+/// let survival = run_survival(&config, &players).unwrap();
+/// 
+/// assert_eq!(survival.standings, [(35, "Evil"), (15, "Friendly")]);
+/// assert_eq!(survival.eliminations, [Elimination { name: "Blue", round: 2, bankroll: -15 }]);
+/// ```
+pub fn run_survival<'a>(config: &Config, players: &'a [Player]) -> Result<Survival<'a>, &'static str> {
+    let bankroll = config.bankroll.ok_or("No bankroll configured")?;
+
+    if players.len() < 2 {
+        return Err("Too few players");
+    }
+
+    if config.max_total(players.len() - 1, bankroll).is_none() {
+        return Err("Too many players for the score range");
+    }
+
+    let player_count = players.len();
+    let schedule = round_robin(player_count);
+
+    let mut bankrolls = vec![bankroll; player_count];
+    let mut eliminated = vec![false; player_count];
+    let mut eliminations = Vec::new();
+
+    for (round, pairs) in schedule.iter().enumerate() {
+        let scores = init_scores(player_count);
+
+        for &(i, j) in pairs {
+            if eliminated[i] || eliminated[j] {
+                continue;
+            }
+
+            config.add_game(
+                players[i].clone(),
+                players[j].clone(),
+                (i, j),
+                (scores[i].clone(), scores[j].clone()),
+            );
+        }
+
+        config.threadpool.join();
+
+        let mut knocked_out = Vec::new();
+        for i in 0..player_count {
+            if eliminated[i] {
+                continue;
+            }
+
+            bankrolls[i] = scores[i].lock().unwrap().0
+                .and_then(|score| bankrolls[i].checked_add(score))
+                .ok_or("Score overflow")?;
+
+            if bankrolls[i] <= 0 {
+                eliminated[i] = true;
+                knocked_out.push(Elimination {
+                    name: players[i].get_name(),
+                    round: round + 1,
+                    bankroll: bankrolls[i],
+                });
+            }
+        }
+
+        // Within a round, the program with the lowest bankroll is considered eliminated first
+        knocked_out.sort_by_key(|elimination| elimination.bankroll);
+        eliminations.extend(knocked_out);
+    }
+
+    let mut standings = (0..player_count)
+        .filter(|&i| !eliminated[i])
+        .map(|i| (bankrolls[i], players[i].get_name()))
+        .collect::<Vec<_>>();
+    standings.sort_by_key(|v| cmp::Reverse(v.0));
+
+    Ok(Survival {
+        standings,
+        eliminations,
+        rounds: schedule.len(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::programs::{
         prisoners::*,
         greedy::*,
+        simple::*,
+        strategic::*,
+        open_source::*,
     };
+    use crate::programs::simple;
+    use crate::replay::Transcript;
+    use crate::strategies::MemoryOne;
 
     #[test]
     fn config_test() {
@@ -387,6 +1762,181 @@ mod tests {
     fn parsing_test() {
         let error = Config::new(&[String::from("tourney"), String::from("--threads"), String::from("6"), String::from("--threads")]).expect_err("parsing test");
         assert_eq!(error, "Duplicate argument: --threads");
+
+        let error = Config::new(&[String::from("tourney"), String::from("--depth"), String::from("2")]).expect_err("parsing test");
+        assert_eq!(error, "Arguments --depth and --simulations require argument: --open-source");
+
+        let error = Config::new(&[String::from("tourney"), String::from("--players"), String::from("evil,")]).expect_err("parsing test");
+        assert_eq!(error, "Value must name at least 2 programs for argument: --players");
+
+        let error = Config::new(&[String::from("tourney"), String::from("--penalty"), String::from("0")]).expect_err("parsing test");
+        assert_eq!(error, "Value must be greater than 0 for argument: --penalty");
+    }
+
+    #[test]
+    fn score_range_test() {
+        let path = std::env::temp_dir().join("tourney_score_range_test.txt");
+        fs::write(&path, "payoffs 2147483647 0 0 0 0 0 0 0 0\n").unwrap();
+
+        let config = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string(), String::from("--min"), String::from("100")]).unwrap();
+        let players = vec![Player::new(evil); 3];
+        assert_eq!(run(&config, &players).unwrap()[0].0, 2147483647 * 100 * 2);
+
+        let error = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string(), String::from("--min"), String::from("3000000000")]).expect_err("score range test");
+        assert_eq!(error, "The amount of rounds and the payoffs could exceed the score range");
+
+        let config = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string(), String::from("--min"), String::from("2000000000"), String::from("--bankroll"), String::from("9223372036854775807")]).unwrap();
+        assert_eq!(run_survival(&config, &players), Err("Too many players for the score range"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn record_test() {
+        let path = std::env::temp_dir().join("tourney_record_test");
+        let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("10"), String::from("--seed"), String::from("3"), String::from("--record"), path.display().to_string()]).unwrap();
+        assert!(!path.exists());
+
+        let players = vec![
+            Player::with_name("1", simple::random),
+            Player::with_name("2", greed_first_15),
+        ];
+
+        let scores = run(&config, &players).unwrap();
+        let transcript = fs::read_to_string(path.join("0_1.txt")).unwrap().parse::<Transcript>().unwrap();
+
+        assert_eq!(transcript.game().seed(), Some(3 ^ 1));
+        assert_eq!(transcript.verify(&players[0], &players[1]), None);
+        assert_eq!(run(&config, &players).unwrap(), scores);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn command_test() {
+        let error = Command::new(&[String::from("tourney"), String::from("replay")]).expect_err("command test");
+        assert_eq!(error, "Missing transcript for command: replay");
+
+        let error = Command::new(&[String::from("tourney"), String::from("replay"), String::from("a"), String::from("b")]).expect_err("command test");
+        assert_eq!(error, "Invalid arguments");
+
+        let error = Command::new(&[String::from("tourney"), String::from("replay"), String::from("a"), String::from("--strategies")]).expect_err("command test");
+        assert_eq!(error, "Incorrect value for argument: --strategies");
+
+        let error = Command::new(&[String::from("tourney"), String::from("list"), String::from("--games")]).expect_err("command test");
+        assert_eq!(error, "Invalid arguments");
+
+        let command = Command::new(&[String::from("tourney"), String::from("zd"), String::from("relation"), String::from("1"), String::from("1"), String::from("0")]).unwrap();
+        assert!(matches!(command, Command::Zd { relation, rounds: 10000, .. } if relation == Relation::new(1.0, 1.0, 0.0)));
+
+        let error = Command::new(&[String::from("tourney"), String::from("zd"), String::from("extortion"), String::from("--save")]).expect_err("command test");
+        assert_eq!(error, "Expected 1 value(s) for relation: extortion");
+
+        let error = Command::new(&[String::from("tourney"), String::from("zd"), String::from("--rounds"), String::from("10"), String::from("--rounds"), String::from("20")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --rounds");
+
+        let command = Command::new(&[String::from("tourney"), String::from("train"), String::from("agent.qt"), String::from("--seed"), String::from("3")]).unwrap();
+        assert!(matches!(command, Command::Train { memory: 2, episodes: 100, rounds: MAX_ROUNDS, seed: 3, .. }));
+
+        let error = Command::new(&[String::from("tourney"), String::from("train"), String::from("agent.qt"), String::from("--memory"), String::from("5")]).expect_err("command test");
+        assert_eq!(error, "Incorrect value for argument: --memory");
+
+        let error = Command::new(&[String::from("tourney"), String::from("train"), String::from("--episodes"), String::from("3")]).expect_err("command test");
+        assert_eq!(error, "Missing path for command: train");
+
+        let error = Command::new(&[String::from("tourney"), String::from("train"), String::from("agent.qt"), String::from("--seed"), String::from("1"), String::from("--seed"), String::from("2")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --seed");
+
+        let command = Command::new(&[String::from("tourney"), String::from("neural"), String::from("--hidden"), String::from("4,2"), String::from("agent.mlp"), String::from("--max"), String::from("10")]).unwrap();
+        assert!(matches!(command, Command::Neural { mlp, generations: 20, config, .. } if mlp.hidden() == [4, 2] && config.rounds() == 10));
+
+        let error = Command::new(&[String::from("tourney"), String::from("neural"), String::from("agent.mlp"), String::from("--hidden"), String::from("4,0")]).expect_err("command test");
+        assert_eq!(error, "Incorrect value for argument: --hidden");
+
+        let error = Command::new(&[String::from("tourney"), String::from("neural"), String::from("agent.mlp"), String::from("--sigma"), String::from("1"), String::from("--sigma"), String::from("2")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --sigma");
+
+        let command = Command::new(&[String::from("tourney"), String::from("evolve"), String::from("lut"), String::from("evolved"), String::from("--window"), String::from("1,3"), String::from("--max"), String::from("10")]).unwrap();
+        assert!(matches!(command, Command::Evolve { species: Species::Lookup(1, 3), population: 50, config, .. } if config.rounds() == 10));
+
+        let error = Command::new(&[String::from("tourney"), String::from("evolve"), String::from("mem"), String::from("evolved"), String::from("--states"), String::from("3")]).expect_err("command test");
+        assert_eq!(error, "Invalid arguments");
+
+        let error = Command::new(&[String::from("tourney"), String::from("evolve"), String::from("dsl"), String::from("evolved")]).expect_err("command test");
+        assert_eq!(error, "Unknown genome: dsl");
+
+        let error = Command::new(&[String::from("tourney"), String::from("evolve"), String::from("fsm"), String::from("evolved"), String::from("--elites"), String::from("1"), String::from("--elites"), String::from("2")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --elites");
+
+        let command = Command::new(&[String::from("tourney"), String::from("solve"), String::from("tit_for_tat,evil"), String::from("--limit"), String::from("50")]).unwrap();
+        assert!(matches!(command, Command::Solve { players: Some(ids), rounds: MAX_ROUNDS, limit: 50, .. } if ids == ["tit_for_tat", "evil"]));
+
+        let error = Command::new(&[String::from("tourney"), String::from("solve"), String::from("evil"), String::from("friendly")]).expect_err("command test");
+        assert_eq!(error, "Invalid arguments");
+
+        let error = Command::new(&[String::from("tourney"), String::from("solve"), String::from("--limit"), String::from("1"), String::from("--limit"), String::from("2")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --limit");
+
+        let command = Command::new(&[String::from("tourney"), String::from("exploit"), String::from("grim_trigger"), String::from("--rounds"), String::from("50")]).unwrap();
+        assert!(matches!(command, Command::Exploit { player, path, rounds: 50, .. } if player == "grim_trigger" && path.ends_with("exploit_grim_trigger.fsm")));
+
+        let error = Command::new(&[String::from("tourney"), String::from("exploit"), String::from("--limit"), String::from("10")]).expect_err("command test");
+        assert_eq!(error, "Missing program for command: exploit");
+
+        let error = Command::new(&[String::from("tourney"), String::from("exploit"), String::from("evil"), String::from("--save"), String::from("a.fsm"), String::from("--save"), String::from("b.fsm")]).expect_err("command test");
+        assert_eq!(error, "Duplicate argument: --save");
+    }
+
+    #[test]
+    fn rules_test() {
+        let path = std::env::temp_dir().join("tourney_rules_test.txt");
+        fs::write(&path, "last 5 announced\ndoubling off\n").unwrap();
+
+        let config = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string()]).unwrap();
+        assert_eq!(config.schedule().changes().len(), 1);
+
+        fs::write(&path, "doubling maybe\n").unwrap();
+        let error = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string()]).expect_err("rules test");
+        assert!(error.ends_with("(Line 1: Expected on or off for doubling)"));
+
+        fs::remove_file(&path).unwrap();
+        let error = Config::new(&[String::from("tourney"), String::from("--rules"), path.display().to_string()]).expect_err("rules test");
+        assert!(error.starts_with("Could not read"));
+    }
+
+    #[test]
+    fn strategies_test() {
+        let path = std::env::temp_dir().join("tourney_strategies_test");
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("grim.fsm"), "state nice G  mean nice nice\nstate mean R  mean mean mean\n").unwrap();
+        fs::write(path.join("copy.mem"), MemoryOne::preset("copy").unwrap().to_string()).unwrap();
+
+        let config = Config::new(&[String::from("tourney"), String::from("--strategies"), path.display().to_string(), String::from("--max"), String::from("10")]).unwrap();
+        let players = config.strategies().iter().map(Entry::player).collect::<Vec<_>>();
+        assert_eq!(players.iter().map(Player::get_name).collect::<Vec<_>>(), ["copy", "grim"]);
+        assert!(config.strategies()[1].deterministic());
+        assert_eq!(run(&config, &players).unwrap().len(), 2);
+
+        fs::write(path.join("broken.fsm"), "state nice G  mean nice nice\n").unwrap();
+        let error = Config::new(&[String::from("tourney"), String::from("--strategies"), path.display().to_string()]).expect_err("strategies test");
+        assert!(error.ends_with("(Line 1: Unknown state: mean) for argument: --strategies"));
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn open_source_test() {
+        let config = Config::new(&[String::from("tourney"), String::from("--open-source"), String::from("--simulations"), String::from("7"), String::from("--max"), String::from("10")]).unwrap();
+        assert_eq!(config.simulation(), Some(Limits::new(Limits::default().depth(), 7, Limits::default().time())));
+
+        let players = vec![
+            Player::open_with_name("1", fair),
+            Player::with_name("2", evil),
+            Player::with_name("3", friendly),
+        ];
+
+        let scores = run(&config, &players).unwrap();
+        assert_eq!([(scores[0].0, scores[0].2), (scores[1].0, scores[1].2), (scores[2].0, scores[2].2)], [(40, "2"), (30, "1"), (20, "3")]);
     }
 
     #[test]
@@ -395,6 +1945,43 @@ mod tests {
         init_scores(0);
     }
 
+    #[test]
+    fn round_robin_test() {
+        for length in 2..10 {
+            let rounds = round_robin(length);
+            let mut pairs = rounds.iter().flatten().copied().collect::<Vec<_>>();
+            pairs.sort_unstable();
+            pairs.dedup();
+
+            assert_eq!(pairs.len(), length * (length - 1) / 2);
+            assert!(rounds.iter().all(|round| {
+                let mut indices = round.iter().flat_map(|&(i, j)| [i, j]).collect::<Vec<_>>();
+                indices.sort_unstable();
+                indices.dedup();
+                indices.len() == round.len() * 2
+            }));
+        }
+    }
+
+    #[test]
+    fn survival_test() {
+        let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("10"), String::from("--bankroll"), String::from("25")]).unwrap();
+
+        let players = vec![
+            Player::with_name("1", blue),
+            Player::with_name("2", evil),
+            Player::with_name("3", friendly),
+            Player::with_name("4", greedy_blue_and_evil),
+        ];
+
+        let survival = run_survival(&config, &players).unwrap();
+        assert_eq!(survival.rounds, 3);
+        assert_eq!(survival.standings.iter().map(|v| v.1).collect::<Vec<_>>(), ["4", "2", "3"]);
+        assert_eq!(survival.eliminations, [Elimination { name: "1", round: 3, bankroll: -15 }]);
+
+        assert_eq!(run_survival(&Config::default(), &players), Err("No bankroll configured"));
+    }
+
     #[test]
     fn init_scores_test() {
         let scores = init_scores(10);
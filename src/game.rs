@@ -1,7 +1,14 @@
 //! `game` contains all necessities to play the game (described [here](https://github.com/wilzet/tourney)).
 
+use std::{cell::{Cell, RefCell}, fmt, sync::Arc, time::Instant};
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use crate::simulation::{Limits, SimulationError};
+use crate::rules::{Rules, Schedule};
+use crate::replay::{Transcript, Divergence};
+use crate::history;
+
 /// One color option is picked by each [player](Player) every turn. A pair of colors make a [move](Move).
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Color {
     /// Non-cooperative.
     Red,
@@ -11,12 +18,160 @@ pub enum Color {
     Blue,
 }
 
+impl Color {
+    /// Every color, in the order red, green, blue.
+    pub(crate) const ALL: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+
+    /// The index of the color in [`Color::ALL`].
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Color::Red => 0,
+            Color::Green => 1,
+            Color::Blue => 2,
+        }
+    }
+
+    /// The letter of the color in text formats.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::Color;
+    /// 
+    /// assert_eq!(Color::Blue.letter(), 'B');
+    /// ```
+    pub fn letter(self) -> char {
+        match self {
+            Color::Red => 'R',
+            Color::Green => 'G',
+            Color::Blue => 'B',
+        }
+    }
+
+    /// The color of a letter in text formats.
+    pub(crate) fn from_letter(letter: char) -> Option<Color> {
+        match letter {
+            'R' => Some(Color::Red),
+            'G' => Some(Color::Green),
+            'B' => Some(Color::Blue),
+            _ => None,
+        }
+    }
+}
+
 /// A move is a pair of each players' [color option](Color).
 pub type Move = (Color, Color);
 
+/// The type definition for scores, wide enough to aggregate long games between many players.
+pub type Score = i64;
+
+/// The error returned when a score no longer fits in a [`Score`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreOverflow;
+
+impl fmt::Display for ScoreOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Score overflow")
+    }
+}
+
+impl std::error::Error for ScoreOverflow {}
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// The random number generator for player programs.
+/// 
+/// The generator is seeded by the [game](Game) before every move, which makes randomised programs
+/// reproducible when a recorded game is [replayed](crate::replay).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GameRng;
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+/// Get the [random number generator](GameRng) for player programs.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use rand::prelude::*;
+/// use tourney::game::{Color, Move, rng};
+/// 
+/// fn example_random_program(_last_moves: &[Move]) -> Color {
+///     *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
+/// }
+/// ```
+pub fn rng() -> GameRng {
+    GameRng
+}
+
+/// The seed of the [random number generator](GameRng) for a single move.
+/// 
+/// # Arguments
+/// 
+/// * `seed` - The seed of the game
+/// * `round` - The index of the round, counting from 0
+/// * `side` - `0` for the first player and `1` for the second player
+fn move_seed(seed: u64, round: u32, side: u64) -> u64 {
+    seed ^ (u64::from(round) << 1 | side)
+}
+
 /// The type defintion for a [player program](Player).
 pub type Program = fn(&[Move]) -> Color;
 
+/// The type definition for an open-source [player program](Player).
+/// 
+/// Besides the moves, the program is given a [context](Context) through which it may simulate its opponent.
+pub type ContextProgram = fn(&[Move], &mut Context) -> Color;
+
+/// A player program with parameters, e.g. a [memory-one strategy](crate::strategies::MemoryOne).
+/// 
+/// Strategies are shared between the games of a tournament, which may be played on different threads.
+pub trait Strategy: Send + Sync {
+    /// Choose a [color](Color).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `last_moves` - A slice of [moves](Move), with the own color first in every move
+    /// * `context` - The [context](Context) of the move, through which an open-source game may be simulated
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color;
+
+    /// Be told that a game has ended, e.g. to release what was held for it. Does nothing by default.
+    /// 
+    /// A game that ends early because of a [score overflow](ScoreOverflow) is also reported, with the moves played
+    /// until then and scores of 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `moves` - Every [move](Move) of the game, with the own color first in every move
+    /// * `scores` - The final scores, the own score first
+    fn game_over(&self, _moves: &[Move], _scores: (Score, Score)) {}
+}
+
+#[derive(Clone)]
+enum Kind {
+    Program(Program),
+    ContextProgram(ContextProgram),
+    Strategy(Arc<dyn Strategy>),
+}
+
 /// Represents a player program.
 /// 
 /// To create a player that can play the game, a program is needed.
@@ -24,7 +179,7 @@ pub type Program = fn(&[Move]) -> Color;
 #[derive(Clone)]
 pub struct Player {
     name: Option<String>,
-    program: Program,
+    program: Kind,
 }
 
 impl Player {
@@ -56,7 +211,64 @@ impl Player {
     pub fn new(program: Program) -> Player {
         Player {
             name: None,
-            program,
+            program: Kind::Program(program),
+        }
+    }
+
+    /// Create a new open-source player program, which may simulate its opponent.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `program` - A program with type definition according to [`ContextProgram`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::{Player, Color, Move, Context};
+    /// 
+    /// // Cooperate if the opponent would cooperate in our place
+    /// fn example_fair_program(last_moves: &[Move], context: &mut Context) -> Color {
+    ///     match context.simulate(last_moves) {
+    ///         Ok(Color::Red) => Color::Red,
+    ///         _ => Color::Green,
+    ///     }
+    /// }
+    /// 
+    /// let player = Player::open(example_fair_program);
+    /// ```
+    pub fn open(program: ContextProgram) -> Player {
+        Player {
+            name: None,
+            program: Kind::ContextProgram(program),
+        }
+    }
+
+    /// Create a new player from a [strategy](Strategy).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `strategy` - A strategy, e.g. a [memory-one strategy](crate::strategies::MemoryOne)
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::{Player, Color, Move, Context, Strategy};
+    /// 
+    /// // Play the same color every move
+    /// struct Constant(Color);
+    /// 
+    /// impl Strategy for Constant {
+    ///     fn next_move(&self, _last_moves: &[Move], _context: &mut Context) -> Color {
+    ///         self.0
+    ///     }
+    /// }
+    /// 
+    /// let player = Player::from_strategy(Constant(Color::Blue));
+    /// ```
+    pub fn from_strategy<S: Strategy + 'static>(strategy: S) -> Player {
+        Player {
+            name: None,
+            program: Kind::Strategy(Arc::new(strategy)),
         }
     }
 
@@ -83,6 +295,18 @@ impl Player {
         Player::new(program).set_name(name)
     }
 
+    /// Create a new open-source player program with a name.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - An identifying name for the player.
+    /// * `program` - A program with type definition according to [`ContextProgram`].
+    /// 
+    /// View [`open`](Player::open) for a full example of an open-source player program.
+    pub fn open_with_name(name: &str, program: ContextProgram) -> Player {
+        Player::open(program).set_name(name)
+    }
+
     /// Set the name of the player program.
     ///     
     /// # Arguments
@@ -138,8 +362,522 @@ impl Player {
     /// # Arguments
     /// 
     /// * `last_moves` - A slice of [moves](Move)
-    fn make_move(&self, last_moves: &[Move]) -> Color {
-        (self.program)(last_moves)
+    /// * `context` - The [context](Context) given to open-source programs
+    pub(crate) fn make_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        match &self.program {
+            Kind::Program(program) => program(last_moves),
+            Kind::ContextProgram(program) => program(last_moves, context),
+            Kind::Strategy(strategy) => strategy.next_move(last_moves, context),
+        }
+    }
+
+    /// Tell the player program that a game has ended, if it is a [strategy](Strategy).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `moves` - Every [move](Move) of the game
+    /// * `scores` - The final scores, the own score first
+    pub(crate) fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        if let Kind::Strategy(strategy) = &self.program {
+            strategy.game_over(moves, scores);
+        }
+    }
+
+    /// Run the player program on hypothetical moves, outside of a game, e.g. to model an opponent.
+    /// 
+    /// The [random number generator](GameRng) is seeded for the move and restored afterwards, so the caller's
+    /// own draws are unaffected. The program may not simulate its opponent, and its compute cost is not charged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `last_moves` - Hypothetical [moves](Move), with the own color of this player first
+    /// * `rules` - The [rules](Rules) the program is told are in effect
+    /// * `seed` - The seed of the random number generator for the move
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::{Color, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::rules::Rules;
+    /// 
+    /// let player = Player::new(tit_for_tat_prisoner);
+    /// 
+    /// assert_eq!(player.simulate(&[(Color::Green, Color::Red)], &Rules::default(), 0), Color::Red);
+    /// ```
+    pub fn simulate(&self, last_moves: &[Move], rules: &Rules, seed: u64) -> Color {
+        let saved = RNG.with(|rng| rng.replace(StdRng::seed_from_u64(seed)));
+
+        let cost = Cell::new(0);
+        let mut context = Context {
+            player: self,
+            opponent: self,
+            depth: 0,
+            budget: None,
+            rules,
+            cost: &cost,
+        };
+        let color = history::with_game(None, || self.make_move(last_moves, &mut context));
+
+        RNG.with(|rng| rng.replace(saved));
+        color
+    }
+}
+
+/// What remains of the simulation limits for a single move.
+struct Budget {
+    limits: Limits,
+    calls: Cell<u32>,
+    deadline: Instant,
+}
+
+/// The handle given to an open-source [program](ContextProgram) when it makes a move.
+/// 
+/// In an open-source [game](Game) the handle may be used to simulate the opponent on hypothetical moves.
+/// All simulations, including those started by a simulated opponent, are charged against the
+/// [limits](Limits) of the program that is actually making the move.
+/// 
+/// The handle also tells the program which [rules](Rules) are in effect, as far as they have been announced.
+pub struct Context<'a> {
+    player: &'a Player,
+    opponent: &'a Player,
+    depth: u32,
+    budget: Option<&'a Budget>,
+    rules: &'a Rules,
+    cost: &'a Cell<u64>,
+}
+
+impl<'a> Context<'a> {
+    /// Simulate the opponent's next color.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `last_moves` - Hypothetical [moves](Move), from the perspective of the caller
+    /// 
+    /// # Returns
+    /// 
+    /// The [color](Color) the opponent would play after `last_moves`.
+    /// 
+    /// # Errors
+    /// 
+    /// If the game is not open-source, or if the depth, call or time [limits](Limits) would be exceeded.
+    pub fn simulate(&mut self, last_moves: &[Move]) -> Result<Color, SimulationError> {
+        let budget = self.budget.ok_or(SimulationError::Disabled)?;
+
+        if self.depth >= budget.limits.depth() {
+            return Err(SimulationError::DepthExceeded);
+        }
+
+        if budget.calls.get() == 0 {
+            return Err(SimulationError::BudgetExhausted);
+        }
+
+        if Instant::now() >= budget.deadline {
+            return Err(SimulationError::OutOfTime);
+        }
+
+        budget.calls.set(budget.calls.get() - 1);
+
+        let mut context = Context {
+            player: self.opponent,
+            opponent: self.player,
+            depth: self.depth + 1,
+            budget: self.budget,
+            rules: self.rules,
+            cost: self.cost,
+        };
+
+        // The moves are hypothetical, so the simulated program may not use the history of the game
+        Ok(history::with_game(None, || self.opponent.make_move(&swap_moves(last_moves), &mut context)))
+    }
+
+    /// Get the simulation depth of the current move, `0` if the move is actually being played.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Get the amount of simulations left for the current move.
+    pub fn remaining_calls(&self) -> u32 {
+        self.budget.map_or(0, |budget| budget.calls.get())
+    }
+
+    /// Get the rules in effect for the current round. Hidden [changes](crate::rules::Change) are not included.
+    pub fn rules(&self) -> &Rules {
+        self.rules
+    }
+
+    /// Report the compute cost of the current move, e.g. the fuel used by a
+    /// [WASM strategy](crate::strategies::WasmStrategy). The cost of a simulation is charged to the program
+    /// making the actual move, and a [game](Game) may subtract a penalty for it from the score.
+    pub fn charge(&self, cost: u64) {
+        self.cost.set(self.cost.get().saturating_add(cost));
+    }
+}
+
+/// Swap the perspective of a slice of [moves](Move).
+fn swap_moves(last_moves: &[Move]) -> Vec<Move> {
+    last_moves.iter()
+        .map(|moves| (moves.1, moves.0))
+        .collect()
+}
+
+/// The settings of a single game.
+#[derive(Debug, Clone)]
+pub struct Game {
+    rounds: u32,
+    simulation: Option<Limits>,
+    schedule: Schedule,
+    seed: Option<u64>,
+    penalty: Option<u64>,
+}
+
+impl Game {
+    /// Create a new game.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `rounds` - The amount of rounds the game goes on for
+    pub fn new(rounds: u32) -> Game {
+        Game {
+            rounds,
+            simulation: None,
+            schedule: Schedule::default(),
+            seed: None,
+            penalty: None,
+        }
+    }
+
+    /// Make the game open-source, allowing [context programs](ContextProgram) to simulate their opponent.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `limits` - The [limits](Limits) for simulating during a single move
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::*;
+    /// use tourney::simulation::Limits;
+    /// 
+    /// fn example_fair_program(last_moves: &[Move], context: &mut Context) -> Color {
+    ///     match context.simulate(last_moves) {
+    ///         Ok(Color::Red) => Color::Red,
+    ///         _ => Color::Green,
+    ///     }
+    /// }
+    /// 
+    /// fn example_evil_program(_last_moves: &[Move]) -> Color {
+    ///     Color::Red
+    /// }
+    /// 
+    /// let fair = Player::open(example_fair_program);
+    /// let evil = Player::new(example_evil_program);
+    /// 
+    /// let game = Game::new(10).set_simulation(Limits::default());
+    /// 
+    /// assert_eq!(game.play(&fair, &fair), Ok((20, 20)));
+    /// assert_eq!(game.play(&fair, &evil), Ok((10, 10)));
+    /// ```
+    pub fn set_simulation(mut self, limits: Limits) -> Game {
+        self.simulation = Some(limits);
+        self
+    }
+
+    /// Set the [schedule](Schedule) of rules for the game.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `schedule` - The initial rules and the changes scheduled for the game
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::*;
+    /// use tourney::rules::*;
+    /// 
+    /// fn example_blue_program(_last_moves: &[Move]) -> Color {
+    ///     Color::Blue
+    /// }
+    /// 
+    /// fn example_friendly_program(_last_moves: &[Move]) -> Color {
+    ///     Color::Green
+    /// }
+    /// 
+    /// let blue = Player::new(example_blue_program);
+    /// let friendly = Player::new(example_friendly_program);
+    /// 
+    /// // Blue options played in the final 10 rounds do not count towards the doubling
+    /// let schedule = Schedule::default().add_change(Change::new(Start::Last(10), false).set_doubling(false));
+    /// 
+    /// assert_eq!(Game::new(10).play(&blue, &friendly), Ok((-20, 10)));
+    /// assert_eq!(Game::new(10).set_schedule(schedule).play(&blue, &friendly), Ok((-10, 10)));
+    /// ```
+    pub fn set_schedule(mut self, schedule: Schedule) -> Game {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Set the seed of the [random number generator](GameRng) of the programs.
+    /// Without a seed, a random seed is picked every time the game is played.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `seed` - The seed of the game
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use tourney::game::*;
+    /// # use rand::prelude::*;
+    /// # fn example_random_program(_last_moves: &[Move]) -> Color {
+    /// #     *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
+    /// # }
+    /// 
+    /// let random = Player::new(example_random_program);
+    /// let game = Game::new(100).set_seed(7);
+    /// 
+    /// assert_eq!(game.play(&random, &random), game.play(&random, &random));
+    /// ```
+    pub fn set_seed(mut self, seed: u64) -> Game {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Subtract a point from a player's final score for every `cost` of compute cost its moves were
+    /// [charged](Context::charge).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `cost` - The compute cost per point of penalty
+    /// 
+    /// # Panics
+    /// 
+    /// If `cost == 0`.
+    pub fn set_penalty(mut self, cost: u64) -> Game {
+        if cost == 0 {
+            panic!("Cannot charge a point per 0 cost");
+        }
+
+        self.penalty = Some(cost);
+        self
+    }
+
+    /// Get the amount of rounds.
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    /// Get the simulation limits if the game is open-source.
+    pub fn simulation(&self) -> Option<Limits> {
+        self.simulation
+    }
+
+    /// Get the schedule of rules.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get the seed, if one is set.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Get the compute cost per point of penalty, if there is a penalty.
+    pub fn penalty(&self) -> Option<u64> {
+        self.penalty
+    }
+
+    /// Let a player make a move, seeding the [random number generator](GameRng) and starting a new simulation
+    /// budget if the game is open-source.
+    fn make_move(&self, player: &Player, opponent: &Player, last_moves: &[Move], rules: &Rules, cost: &Cell<u64>, seed: u64) -> Color {
+        RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+
+        let budget = self.simulation.map(|limits| Budget {
+            limits,
+            calls: Cell::new(limits.calls()),
+            deadline: Instant::now() + limits.time(),
+        });
+
+        let mut context = Context {
+            player,
+            opponent,
+            depth: 0,
+            budget: budget.as_ref(),
+            rules,
+            cost,
+        };
+
+        player.make_move(last_moves, &mut context)
+    }
+
+    /// Play the game.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `player_1` - A [player](Player)
+    /// * `player_2` - A [player](Player) (may be the same as `player_1`)
+    /// 
+    /// # Returns
+    /// 
+    /// A tuple of scores as [`Score`] in the order the [players](Player) are added as arguments.
+    /// 
+    /// # Errors
+    /// 
+    /// If a score overflows, which cannot happen for the rounds and payoffs accepted by a [config](crate::config::Config).
+    pub fn play(&self, player_1: &Player, player_2: &Player) -> Result<(Score, Score), ScoreOverflow> {
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+        self.play_moves(player_1, player_2, seed).map(|(_, scores)| scores)
+    }
+
+    /// Play the game and record it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `player_1` - A [player](Player)
+    /// * `player_2` - A [player](Player) (may be the same as `player_1`)
+    /// 
+    /// # Returns
+    /// 
+    /// A [transcript](Transcript) of the game, which includes the seed that was used.
+    /// 
+    /// # Errors
+    /// 
+    /// If a score overflows.
+    pub fn record(&self, player_1: &Player, player_2: &Player) -> Result<Transcript, ScoreOverflow> {
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let (moves, scores) = self.play_moves(player_1, player_2, seed)?;
+
+        Ok(Transcript::new(
+            (player_1.get_name(), player_2.get_name()),
+            self.clone().set_seed(seed),
+            moves,
+            scores,
+        ))
+    }
+
+    /// Play the game with a seed.
+    /// 
+    /// # Returns
+    /// 
+    /// The moves and the final scores.
+    fn play_moves(&self, player_1: &Player, player_2: &Player, seed: u64) -> Result<(Vec<Move>, (Score, Score)), ScoreOverflow> {
+        let mut actual = *self.schedule.initial();
+        let mut known = *self.schedule.initial();
+
+        let mut last_moves = Vec::new();
+        let mut scores = Some((0, 0));
+        let mut blue_count = (0, 0);
+        let costs = (Cell::new(0), Cell::new(0));
+        for round in 0..self.rounds {
+            self.schedule.apply(round, self.rounds, &mut actual, &mut known);
+
+            let player1_move = history::with_game(Some((seed, 0)), || {
+                self.make_move(player_1, player_2, &last_moves, &known, &costs.0, move_seed(seed, round, 0))
+            });
+            let player2_move = history::with_game(Some((seed, 1)), || {
+                self.make_move(player_2, player_1, &swap_moves(&last_moves), &known, &costs.1, move_seed(seed, round, 1))
+            });
+            let m = (player1_move, player2_move);
+
+            let round_scores = actual.payoffs().score(m);
+            scores = scores.and_then(|scores: (Score, Score)| Some((
+                scores.0.checked_add(round_scores.0.into())?,
+                scores.1.checked_add(round_scores.1.into())?,
+            )));
+
+            if actual.doubling() {
+                blue_count.0 += (m.0 == Color::Blue) as u32;
+                blue_count.1 += (m.1 == Color::Blue) as u32;
+            }
+
+            last_moves.push(m);
+
+            if scores.is_none() {
+                break;
+            }
+        }
+
+        let scores = scores.ok_or(ScoreOverflow)
+            .and_then(|scores| self.final_scores(scores, blue_count, (costs.0.get(), costs.1.get())));
+
+        // The players are also told when the game ended early, so that they release what they held for it
+        let told = scores.unwrap_or_default();
+        player_1.game_over(&last_moves, told);
+        player_2.game_over(&swap_moves(&last_moves), (told.1, told.0));
+
+        scores.map(|scores| (last_moves, scores))
+    }
+
+    /// Apply the doubling and the penalty to the scores of the moves.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scores` - The scores of the moves
+    /// * `blue_count` - The amount of blue options of each player that count towards the doubling
+    /// * `costs` - The compute cost used by each player
+    fn final_scores(&self, scores: (Score, Score), blue_count: (u32, u32), costs: (u64, u64)) -> Result<(Score, Score), ScoreOverflow> {
+        let scores = if blue_count.0 > blue_count.1 {
+            (scores.0.checked_mul(2).ok_or(ScoreOverflow)?, scores.1)
+        } else if blue_count.0 < blue_count.1 {
+            (scores.0, scores.1.checked_mul(2).ok_or(ScoreOverflow)?)
+        } else {
+            scores
+        };
+
+        match self.penalty {
+            Some(cost) => {
+                let penalty = |cost_used: u64| Score::try_from(cost_used / cost).map_err(|_| ScoreOverflow);
+                Ok((
+                    scores.0.checked_sub(penalty(costs.0)?).ok_or(ScoreOverflow)?,
+                    scores.1.checked_sub(penalty(costs.1)?).ok_or(ScoreOverflow)?,
+                ))
+            }
+            None => Ok(scores),
+        }
+    }
+
+    /// Re-run the programs move by move on recorded moves.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `player_1` - The first [player](Player) of the recorded game
+    /// * `player_2` - The second [player](Player) of the recorded game
+    /// * `moves` - The recorded [moves](Move)
+    /// 
+    /// # Returns
+    /// 
+    /// The first [divergence](Divergence) from the recorded moves, or `None` if the programs play every recorded move.
+    /// 
+    /// # Panics
+    /// 
+    /// If the game has no seed.
+    pub(crate) fn verify(&self, player_1: &Player, player_2: &Player, moves: &[Move]) -> Option<Divergence> {
+        let seed = self.seed.expect("A recorded game has a seed");
+
+        let mut actual = *self.schedule.initial();
+        let mut known = *self.schedule.initial();
+
+        for (round, m) in (0..self.rounds).zip(moves) {
+            self.schedule.apply(round, self.rounds, &mut actual, &mut known);
+
+            let last_moves = &moves[..round as usize];
+            let cost = Cell::new(0);
+            let replayed = (
+                history::with_game(Some((seed, 0)), || {
+                    self.make_move(player_1, player_2, last_moves, &known, &cost, move_seed(seed, round, 0))
+                }),
+                history::with_game(Some((seed, 1)), || {
+                    self.make_move(player_2, player_1, &swap_moves(last_moves), &known, &cost, move_seed(seed, round, 1))
+                }),
+            );
+
+            if replayed != *m {
+                return Some(Divergence::new(round + 1, *m, replayed));
+            }
+        }
+
+        None
     }
 }
 
@@ -153,7 +891,11 @@ impl Player {
 /// 
 /// # Returns
 /// 
-/// A tuple of scores as `i32` in the order the [players](Player) are added as arguments.
+/// A tuple of scores as [`Score`] in the order the [players](Player) are added as arguments.
+/// 
+/// # Panics
+/// 
+/// If a score overflows. Use [`Game::play`] to handle the overflow instead.
 /// 
 /// # Examples
 /// 
@@ -187,43 +929,11 @@ impl Player {
 /// let scores = play(tit_for_tat.clone(), tit_for_tat, 100);
 /// assert_eq!(scores, (200, 200));
 /// ```
-pub fn play(player_1: Player, player_2: Player, rounds: u32) -> (i32, i32) {
-    let mut last_moves = Vec::new();
-    for _ in 0..rounds {
-        let player1_move = player_1.make_move(&last_moves);
-
-        let last_moves_swapped = last_moves.iter()
-            .map(|moves| (moves.1, moves.0))
-            .collect::<Vec<Move>>();
-        let player2_move = player_2.make_move(&last_moves_swapped);
-
-        last_moves.push((player1_move, player2_move));
-    }
-
-
-    let (scores, blue_count) = last_moves.iter()
-        .fold(((0, 0), (0, 0)), |(scores_acc, blue_count_acc), m| {
-            match m {
-                (Color::Red, Color::Red) => ((scores_acc.0 + 1, scores_acc.1 + 1), blue_count_acc),
-                (Color::Red, Color::Green) => ((scores_acc.0 + 3, scores_acc.1), blue_count_acc),
-                (Color::Green, Color::Red) => ((scores_acc.0, scores_acc.1 + 3), blue_count_acc),
-                (Color::Green, Color::Green) => ((scores_acc.0 + 2, scores_acc.1 + 2), blue_count_acc),
-                (Color::Blue, Color::Blue) => (scores_acc, (blue_count_acc.0 + 1, blue_count_acc.1 + 1)),
-                (Color::Blue, _) => ((scores_acc.0 - 1, scores_acc.1 + 1), (blue_count_acc.0 + 1, blue_count_acc.1)),
-                (_, Color::Blue) => ((scores_acc.0 + 1, scores_acc.1 - 1), (blue_count_acc.0, blue_count_acc.1 + 1)),
-            }
-        });
-
-    if blue_count.0 > blue_count.1 {
-        (scores.0 * 2, scores.1)
-    } else if blue_count.0 < blue_count.1 {
-        (scores.0, scores.1 * 2)
-    } else {
-        scores
-    }
+pub fn play(player_1: Player, player_2: Player, rounds: u32) -> (Score, Score) {
+    Game::new(rounds).play(&player_1, &player_2).expect("Score overflow")
 }
 
-pub fn calculate_scores(last_moves: &[Move]) -> (i32, i32) {
+pub fn calculate_scores(last_moves: &[Move]) -> (Score, Score) {
     last_moves.iter()
         .fold((0, 0), |acc, m| {
             match m {
@@ -240,8 +950,10 @@ pub fn calculate_scores(last_moves: &[Move]) -> (i32, i32) {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
     use super::*;
-    use crate::programs::greedy::greedy_blue_and_friendly;
+    use crate::rules::{Change, Payoffs, Start};
+    use crate::programs::{greedy::greedy_blue_and_friendly, simple::friendly};
 
     fn test_strategy(last_moves: &[Move]) -> Color {
         if let Some(last_move) = last_moves.last() {
@@ -255,9 +967,96 @@ mod tests {
         Color::Blue
     }
 
+    fn test_open_strategy(last_moves: &[Move], context: &mut Context) -> Color {
+        match context.simulate(last_moves) {
+            Ok(Color::Red) => Color::Red,
+            Ok(_) => Color::Green,
+            Err(_) => Color::Blue,
+        }
+    }
+
+    fn test_counting_strategy(last_moves: &[Move], context: &mut Context) -> Color {
+        while context.simulate(last_moves).is_ok() {}
+
+        match context.remaining_calls() {
+            0 => Color::Green,
+            _ => Color::Red,
+        }
+    }
+
+    fn test_rules_strategy(_last_moves: &[Move], context: &mut Context) -> Color {
+        if context.rules().doubling() {
+            return Color::Blue;
+        }
+
+        Color::Green
+    }
+
+    fn test_costly_strategy(_last_moves: &[Move], context: &mut Context) -> Color {
+        context.charge(30);
+        Color::Green
+    }
+
     #[test]
     fn make_move_test() {
-        assert!(Player::new(test_strategy).make_move(&[]) == Color::Blue);
+        let p = Player::new(test_strategy);
+        assert!(Game::new(1).make_move(&p, &p, &[], &Rules::default(), &Cell::new(0), 0) == Color::Blue);
+    }
+
+    #[test]
+    fn simulation_disabled_test() {
+        let p_1 = Player::open(test_open_strategy);
+        let p_2 = Player::new(test_strategy);
+
+        assert!(Game::new(1).make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Blue);
+        assert!(Game::new(1).set_simulation(Limits::default()).make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Green);
+    }
+
+    #[test]
+    fn simulation_depth_test() {
+        let p = Player::open(test_open_strategy);
+        let game = Game::new(1).set_simulation(Limits::new(3, 100, Duration::from_secs(10)));
+
+        // The innermost simulation fails and plays blue, which the outer simulations answer with green
+        assert!(game.make_move(&p, &p, &[], &Rules::default(), &Cell::new(0), 0) == Color::Green);
+        assert!(Game::new(1).set_simulation(Limits::new(0, 100, Duration::from_secs(10))).make_move(&p, &p, &[], &Rules::default(), &Cell::new(0), 0) == Color::Blue);
+    }
+
+    #[test]
+    fn simulation_budget_test() {
+        let p_1 = Player::open(test_counting_strategy);
+        let p_2 = Player::new(test_strategy);
+        let game = Game::new(1).set_simulation(Limits::new(1, 5, Duration::from_secs(10)));
+
+        assert!(game.make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Green);
+        assert!(game.make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Green);
+        assert!(Game::new(1).set_simulation(Limits::new(1, 5, Duration::ZERO)).make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Red);
+    }
+
+    #[test]
+    fn simulation_charged_to_caller_test() {
+        let p_1 = Player::open(test_open_strategy);
+        let p_2 = Player::open(test_counting_strategy);
+        let game = Game::new(1).set_simulation(Limits::new(2, 5, Duration::from_secs(10)));
+
+        // The simulated opponent uses up the caller's budget, leaving no calls, and thus plays green
+        assert!(game.make_move(&p_1, &p_2, &[], &Rules::default(), &Cell::new(0), 0) == Color::Green);
+    }
+
+    #[test]
+    fn player_simulate_test() {
+        let p = Player::open(test_open_strategy);
+        let random = Player::new(|_| Color::ALL[rng().gen_range(0..3)]);
+
+        // Simulations are disabled outside of a game
+        assert!(p.simulate(&[], &Rules::default(), 0) == Color::Blue);
+
+        // The same seeds give the same colors, and the caller's generator continues where it was
+        RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(1));
+        let colors = (0..20).map(|i| random.simulate(&[], &Rules::default(), i)).collect::<Vec<_>>();
+        assert_eq!(rng().gen::<u64>(), StdRng::seed_from_u64(1).gen::<u64>());
+        assert_eq!((0..20).map(|i| random.simulate(&[], &Rules::default(), i)).collect::<Vec<_>>(), colors);
+        assert!(colors.contains(&Color::Red) && colors.contains(&Color::Blue));
     }
 
     #[test]
@@ -278,6 +1077,51 @@ mod tests {
         assert_ne!(p_1.get_name(), p_2.get_name());
     }
 
+    #[test]
+    fn schedule_play_test() {
+        let p_1 = Player::open(test_rules_strategy);
+        let p_2 = Player::new(friendly);
+        let payoffs = Payoffs::new([[0; 3], [0, 1, 0], [0; 3]]);
+
+        let hidden = Schedule::default().add_change(Change::new(Start::Round(2), false).set_doubling(false));
+        let announced = Schedule::default().add_change(Change::new(Start::Round(2), true).set_doubling(false).set_payoffs(payoffs));
+
+        assert_eq!(Game::new(4).set_schedule(hidden).play(&p_1, &p_2), Ok((-8, 4)));
+        assert_eq!(Game::new(4).set_schedule(announced).play(&p_1, &p_2), Ok((4, 4)));
+    }
+
+    #[test]
+    fn penalty_play_test() {
+        let p_1 = Player::open(test_costly_strategy);
+        let p_2 = Player::new(friendly);
+
+        assert_eq!(Game::new(10).play(&p_1, &p_2), Ok((20, 20)));
+        assert_eq!(Game::new(10).set_penalty(100).play(&p_1, &p_2), Ok((17, 20)));
+    }
+
+    #[test]
+    fn overflow_game_over_test() {
+        struct Spender(Arc<std::sync::Mutex<Vec<usize>>>);
+
+        impl Strategy for Spender {
+            fn next_move(&self, _last_moves: &[Move], context: &mut Context) -> Color {
+                context.charge(u64::MAX);
+                Color::Green
+            }
+
+            fn game_over(&self, moves: &[Move], _scores: (Score, Score)) {
+                self.0.lock().unwrap().push(moves.len());
+            }
+        }
+
+        let ended = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let player = Player::from_strategy(Spender(ended.clone()));
+
+        // The penalty does not fit in a score, but the player is still told that the game ended
+        assert_eq!(Game::new(3).set_penalty(1).play(&player, &Player::new(friendly)), Err(ScoreOverflow));
+        assert_eq!(*ended.lock().unwrap(), [3]);
+    }
+
     #[test]
     fn score_calculation_test() {
         assert_eq!(calculate_scores(&[]), (0, 0));
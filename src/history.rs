@@ -0,0 +1,345 @@
+//! `history` answers the questions programs ask about the [moves](Move) of a game, such as how often the opponent
+//! played a color, without folding over every move again.
+//!
+//! A [`History`] is updated with the moves that were added since it was last updated. Since programs are given
+//! the moves without any state of their own, [`History::cached`] keeps the histories of the last games played on
+//! the current thread. During a [game](crate::game::Game) the history of a player is found by the game it belongs
+//! to, so a move only costs the moves added since the last one:
+//!
+//! ```
+//! use tourney::game::{Color, Move};
+//! use tourney::history::{History, Side};
+//!
+//! fn mostly_blue(last_moves: &[Move]) -> Color {
+//!     History::cached(last_moves, |history| {
+//!         if history.count(Side::Opponent, Color::Blue) * 2 > history.len() {
+//!             Color::Green
+//!         } else {
+//!             Color::Red
+//!         }
+//!     })
+//! }
+//!
+//! assert_eq!(mostly_blue(&[(Color::Red, Color::Blue)]), Color::Green);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use crate::game::{Color, Move, Score};
+use crate::rules::Payoffs;
+
+/// How many histories [`History::cached`] keeps per thread, enough for both players of a game and a few
+/// simulations.
+const CACHE_SIZE: usize = 8;
+
+/// The seed of a game together with the index of the player, which identifies the moves a player is given during
+/// a game.
+type Key = (u64, usize);
+
+thread_local! {
+    static CACHE: RefCell<Vec<(Option<Key>, History)>> = const { RefCell::new(Vec::new()) };
+    static GAME: Cell<Option<Key>> = const { Cell::new(None) };
+}
+
+/// Run a function while the moves given to programs are those of a player of a game, or hypothetical moves if
+/// `key` is `None`.
+///
+/// # Arguments
+///
+/// * `key` - The seed of the game and the index of the player, `None` for a simulation
+/// * `f` - The function to run
+pub(crate) fn with_game<T>(key: Option<Key>, f: impl FnOnce() -> T) -> T {
+    let saved = GAME.replace(key);
+    let value = f();
+    GAME.set(saved);
+    value
+}
+
+/// One of the players, as seen by the player the moves are given to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The player the moves are given to, whose color is first in every move.
+    Own,
+    /// The opponent, whose color is second in every move.
+    Opponent,
+}
+
+impl Side {
+    /// Get the color of this player in a move.
+    pub fn color(&self, m: Move) -> Color {
+        match self {
+            Side::Own => m.0,
+            Side::Opponent => m.1,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Side::Own => 0,
+            Side::Opponent => 1,
+        }
+    }
+}
+
+/// The moves of a game together with statistics about them, which are updated move by move.
+///
+/// The scores are always calculated with the default [payoffs](Payoffs), so they differ from the scores of the game
+/// when other [rules](crate::rules::Rules) are in effect. Use [`Rules::calculate_scores`](crate::rules::Rules::calculate_scores)
+/// with the [rules of the context](crate::game::Context::rules) for those.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    moves: Vec<Move>,
+    counts: [[usize; 3]; 2],
+    streaks: [usize; 2],
+    responses: [[usize; 3]; 3],
+    scores: Vec<(Score, Score)>,
+}
+
+impl History {
+    /// Create a new history of moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - The moves, with the own color first
+    pub fn new(last_moves: &[Move]) -> History {
+        let mut history = History::default();
+        history.update(last_moves);
+        history
+    }
+
+    /// Get the history of moves from the cache of the current thread, updated with the moves that were added since
+    /// it was cached, and pass it to a function.
+    ///
+    /// During a game the cached history of the player is found by its game, otherwise, e.g. in a simulation, it is
+    /// the longest cached history outside a game. Either way it is only used if its moves start `last_moves`, which is
+    /// still much cheaper than calculating the statistics again, and the statistics are calculated from the start if
+    /// they do not. The function may itself use the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - The moves, with the own color first
+    /// * `f` - The function to pass the history to
+    ///
+    /// # Returns
+    ///
+    /// The value returned by `f`.
+    pub fn cached<T>(last_moves: &[Move], f: impl FnOnce(&History) -> T) -> T {
+        let key = GAME.get();
+
+        // The history is taken out of the cache while it is used, which lets `f` use the cache as well
+        let mut history = CACHE.with_borrow_mut(|cache| {
+            let found = match key {
+                Some(key) => cache.iter().position(|(cached, _)| *cached == Some(key)),
+                None => cache.iter()
+                    .enumerate()
+                    .filter(|(_, (cached, history))| cached.is_none() && last_moves.starts_with(&history.moves))
+                    .max_by_key(|(_, (_, history))| history.len())
+                    .map(|(i, _)| i),
+            };
+
+            found.map(|i| cache.remove(i).1).unwrap_or_default()
+        });
+
+        history.update(last_moves);
+        let value = f(&history);
+
+        CACHE.with_borrow_mut(|cache| {
+            // A game keeps a single history per player
+            if let Some(key) = key {
+                cache.retain(|(cached, _)| *cached != Some(key));
+            }
+
+            cache.truncate(CACHE_SIZE - 1);
+            cache.insert(0, (key, history));
+        });
+
+        value
+    }
+
+    /// Update the history to a longer game. If the moves do not continue the moves of the history, the history
+    /// is calculated from the start.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - Every move of the game, with the own color first
+    pub fn update(&mut self, last_moves: &[Move]) {
+        if !last_moves.starts_with(&self.moves) {
+            *self = History::default();
+        }
+
+        self.extend(last_moves);
+    }
+
+    /// Add the moves after the moves of the history, which are assumed to be the same.
+    fn extend(&mut self, last_moves: &[Move]) {
+        for m in &last_moves[self.moves.len()..] {
+            self.push(*m);
+        }
+    }
+
+    /// Add a move to the history, scored with the default [payoffs](Payoffs).
+    pub fn push(&mut self, m: Move) {
+        let last = self.moves.last().copied();
+
+        if let Some(last) = last {
+            self.responses[last.0.index()][m.1.index()] += 1;
+        }
+
+        for side in [Side::Own, Side::Opponent] {
+            let color = side.color(m);
+            self.counts[side.index()][color.index()] += 1;
+            self.streaks[side.index()] = match last {
+                Some(last) if side.color(last) == color => self.streaks[side.index()] + 1,
+                _ => 1,
+            };
+        }
+
+        let scores = self.scores();
+        let payoffs = Payoffs::default().score(m);
+        self.scores.push((scores.0 + Score::from(payoffs.0), scores.1 + Score::from(payoffs.1)));
+        self.moves.push(m);
+    }
+
+    /// Get the amount of moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Whether there are no moves.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Get every move.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Get the last `n` moves, or every move if there are fewer.
+    pub fn last(&self, n: usize) -> &[Move] {
+        &self.moves[self.moves.len().saturating_sub(n)..]
+    }
+
+    /// Get how many times a player played a color.
+    pub fn count(&self, side: Side, color: Color) -> usize {
+        self.counts[side.index()][color.index()]
+    }
+
+    /// Get how many times in a row a player played its last color, 0 if there are no moves.
+    pub fn streak(&self, side: Side) -> usize {
+        self.streaks[side.index()]
+    }
+
+    /// Get how many times the opponent played `response` in the move after the player played `color`.
+    pub fn responses(&self, color: Color, response: Color) -> usize {
+        self.responses[color.index()][response.index()]
+    }
+
+    /// Get how often the opponent played `response` in the move after the player played `color`, `None` if the
+    /// opponent never responded to `color`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color::*;
+    /// use tourney::history::History;
+    ///
+    /// let history = History::new(&[(Red, Green), (Red, Red), (Green, Green), (Red, Blue)]);
+    ///
+    /// assert_eq!(history.response_frequency(Red, Red), Some(0.5));
+    /// assert_eq!(history.response_frequency(Green, Blue), Some(1.0));
+    /// assert_eq!(history.response_frequency(Blue, Red), None);
+    /// ```
+    pub fn response_frequency(&self, color: Color, response: Color) -> Option<f64> {
+        let total = self.responses[color.index()].iter().sum::<usize>();
+        match total {
+            0 => None,
+            total => Some(self.responses(color, response) as f64 / total as f64),
+        }
+    }
+
+    /// Get the scores so far, calculated like [`calculate_scores`](crate::game::calculate_scores) with the default
+    /// [payoffs](Payoffs).
+    pub fn scores(&self) -> (Score, Score) {
+        self.scores.last().copied().unwrap_or_default()
+    }
+
+    /// Get the scores after every move, calculated like [`calculate_scores`](crate::game::calculate_scores) with the
+    /// default [payoffs](Payoffs).
+    pub fn running_scores(&self) -> &[(Score, Score)] {
+        &self.scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+    use super::*;
+    use crate::game::calculate_scores;
+
+    #[test]
+    fn history_test() {
+        let moves = [(Color::Blue, Color::Red), (Color::Blue, Color::Red), (Color::Green, Color::Red), (Color::Red, Color::Blue)];
+        let history = History::new(&moves);
+
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.last(2), &moves[2..]);
+        assert_eq!(history.last(10), &moves);
+        assert_eq!((history.count(Side::Own, Color::Blue), history.count(Side::Opponent, Color::Red)), (2, 3));
+        assert_eq!((history.streak(Side::Own), history.streak(Side::Opponent)), (1, 1));
+        assert_eq!(History::new(&moves[..3]).streak(Side::Opponent), 3);
+        assert_eq!((history.responses(Color::Blue, Color::Red), history.responses(Color::Green, Color::Blue)), (2, 1));
+        assert_eq!(history.running_scores()[1], calculate_scores(&moves[..2]));
+        assert_eq!(History::default().streak(Side::Own), 0);
+    }
+
+    #[test]
+    fn cached_test() {
+        let mut rng = rand::thread_rng();
+
+        // Two interleaved games, like the two players of a game see it
+        let mut moves = Vec::new();
+        for _ in 0..50 {
+            moves.push((*Color::ALL.choose(&mut rng).unwrap(), *Color::ALL.choose(&mut rng).unwrap()));
+            let swapped = moves.iter().map(|m| (m.1, m.0)).collect::<Vec<_>>();
+
+            assert_eq!(History::cached(&moves, Clone::clone), History::new(&moves));
+            assert_eq!(History::cached(&swapped, |history| history.scores()), calculate_scores(&swapped));
+        }
+
+        // A new game does not continue the cached one
+        let other = [(Color::Green, Color::Green)];
+        assert_eq!(History::cached(&other, |history| History::cached(&moves, |_| history.clone())), History::new(&other));
+    }
+
+    #[test]
+    fn game_cache_test() {
+        let moves = [(Color::Red, Color::Green), (Color::Green, Color::Green)];
+        let changed = [(Color::Blue, Color::Blue), (Color::Green, Color::Green), (Color::Red, Color::Red)];
+
+        // A game continues the history of the player, unless the earlier moves differ
+        let continued = with_game(Some((7, 0)), || {
+            History::cached(&moves, |_| ());
+            History::cached(&[moves[0], moves[1], (Color::Red, Color::Red)], Clone::clone)
+        });
+        assert_eq!(continued, History::new(&[moves[0], moves[1], (Color::Red, Color::Red)]));
+
+        let changed_game = with_game(Some((7, 0)), || History::cached(&changed, Clone::clone));
+        assert_eq!(changed_game, History::new(&changed));
+
+        // The swapped moves after a symmetric last move are not the own view
+        let swapped = changed.map(|m| (m.1, m.0));
+        let first = with_game(Some((7, 0)), || {
+            History::cached(&[(Color::Red, Color::Green), (Color::Red, Color::Red)], |_| ());
+            History::cached(&[(Color::Green, Color::Red), (Color::Red, Color::Red)], |history| history.moves()[0])
+        });
+        assert_eq!(first, (Color::Green, Color::Red));
+        assert_eq!(with_game(Some((7, 0)), || History::cached(&swapped, Clone::clone)), History::new(&swapped));
+
+        // Another player, or a simulation, does not get the history of the game
+        assert_eq!(with_game(Some((7, 1)), || History::cached(&changed, Clone::clone)), History::new(&changed));
+        assert_eq!(History::cached(&changed, Clone::clone), History::new(&changed));
+
+        // A shorter game with the same key starts over
+        assert_eq!(with_game(Some((7, 0)), || History::cached(&changed[..1], Clone::clone)), History::new(&changed[..1]));
+    }
+}
@@ -1,3 +1,10 @@
 pub mod game;
 pub mod programs;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod simulation;
+pub mod rules;
+pub mod replay;
+pub mod registry;
+pub mod strategies;
+pub mod history;
+pub mod solver;
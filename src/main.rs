@@ -1,47 +1,412 @@
-use std::{env, process};
+use std::{env, fs, process};
 use tourney::config::*;
-use tourney::programs::all::*;
-use tourney::game::Player;
+use tourney::programs;
+use tourney::game::{Game, Player};
+use tourney::registry::{Entry, Registry};
+use tourney::replay::Transcript;
+use tourney::rules::Schedule;
+use tourney::solver::{self, Solver};
+use tourney::strategies::FsmStrategy;
+use tourney::strategies::genetic::GeneticAlgorithm;
+use tourney::strategies::mlp::{Evolution, Mlp};
+use tourney::strategies::qlearning::{self, QLearner, QTable};
+use tourney::strategies::zd::{self, Relation};
 
 fn main() {
     let args = env::args().collect::<Vec<_>>();
-    let config = match Config::new(&args) {
-        Ok(config) => config,
+    let command = match Command::new(&args) {
+        Ok(command) => command,
         Err(error) => {
             eprintln!("{error}");
             process::exit(1);
         }
     };
 
-    println!("\nTournament start\n");
+    match command {
+        Command::Tournament(config) => {
+            if let Err(error) = tournament(&config) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::List { strategies } => {
+            if let Err(error) = list(&strategies) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Replay { path, verify, strategies } => {
+            if let Err(error) = replay(&path, verify, &strategies) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Zd { relation, schedule, rounds, save } => {
+            if let Err(error) = zero_determinant(&relation, &schedule, rounds, save.as_deref()) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Neural { path, mlp, generations, population, sigma, config } => {
+            if let Err(error) = neural(&path, mlp, generations, population, sigma, &config) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Evolve { path, species, generations, population, elites, crossover, mutation, hall_of_fame, config } => {
+            let algorithm = GeneticAlgorithm::new(species, population, config.seed().unwrap_or_else(rand::random))
+                .set_elites(elites)
+                .set_crossover(crossover)
+                .set_mutation(mutation)
+                .set_hall_of_fame(hall_of_fame);
+
+            if let Err(error) = evolve(&path, algorithm, generations, &config) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Train { path, memory, episodes, schedule, rounds, seed } => {
+            if let Err(error) = train(&path, memory, episodes, &schedule, rounds, seed) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Solve { players, schedule, rounds, limit, strategies } => {
+            if let Err(error) = solve(players.as_deref(), &schedule, rounds, limit, &strategies) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+        Command::Exploit { player, path, fallback, schedule, rounds, limit, strategies } => {
+            if let Err(error) = exploit(&player, &path, &fallback, &schedule, rounds, limit, &strategies) {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Get the registered programs together with the strategies loaded from files.
+fn registry(strategies: &[Entry]) -> Result<Registry, String> {
+    let mut registry = programs::registry();
+    for entry in strategies {
+        registry.register(entry.clone())?;
+    }
 
-    let players = vec![
-        Player::with_name("Take back 1", take_back_once_prisoner),
-        Player::with_name("Friendly", friendly),
-        Player::with_name("Evil", evil),
-        Player::with_name("Tit for tat", tit_for_tat_prisoner),
-        Player::with_name("Tit for two tats", tit_for_two_tats_prisoner),
-        Player::with_name("Greedy and friendly", greedy_blue_and_friendly),
-        Player::with_name("Greedy and evil", greedy_blue_and_evil),
-        Player::with_name("Greedy blue", blue),
-        Player::with_name("Try to guess", try_to_guess),
-        Player::with_name("Random", random),
-        Player::with_name("ChatGPT adaptive", chat_gpt_adaptive),
-        Player::with_name("ChatGPT proactive", chat_gpt_proactive),
-        Player::with_name("ChatGPT versatile", chat_gpt_versatile),
-        Player::with_name("Cooperate until defection", cooperate_until_defection),
-        Player::with_name("Random, greedy if winning", greedy_if_winning_else_random),
-        Player::with_name("Random, greedy if 2x", greedy_if_2x_score_else_random),
-        Player::with_name("Copy opponent", copy),
-        Player::with_name("Copy opponent, unless blue", smarter_copy),
-        Player::with_name("Match opponent, 15 greedy start", greed_first_15),
-    ];
+    Ok(registry)
+}
+
+fn tournament(config: &Config) -> Result<(), String> {
+    let registry = registry(config.strategies())?;
+    let players = match config.players() {
+        Some(ids) => registry.select(ids)?,
+        None => registry.players(),
+    };
+
+    println!("\nTournament start\n");
 
     println!("Pairing every program... ({0} games)\n", players.len() * (players.len() - 1) / 2);
 
-    let scores = run(&config, &players).unwrap();
+    if config.bankroll().is_some() {
+        print_survival(config, &players)?;
+    } else {
+        print_scores(config, &players)?;
+    }
+
+    println!("\nTournament end\n");
+    Ok(())
+}
+
+fn list(strategies: &[Entry]) -> Result<(), String> {
+    println!("\nid                             program_name                     det. memory  author");
+    println!("-------------------------------------------------------------------------------------");
+
+    for entry in registry(strategies)?.entries() {
+        let memory = entry.memory().map_or(String::from("all"), |memory| memory.to_string());
+        let deterministic = if entry.deterministic() { "yes" } else { "no" };
+        let author = if entry.author().is_empty() { "-" } else { entry.author() };
+        println!("{0:<30} {1:<32} {2:<4} {3:<7} {4}", entry.id(), entry.name(), deterministic, memory, author);
+
+        if !entry.description().is_empty() {
+            println!("    {0}", entry.description());
+        }
+    }
+
+    println!();
+    Ok(())
+}
+
+fn replay(path: &str, verify: bool, strategies: &[Entry]) -> Result<(), String> {
+    let transcript = fs::read_to_string(path)
+        .map_err(|error| format!("Could not read {path} ({error})"))?
+        .parse::<Transcript>()
+        .map_err(|error| format!("Invalid transcript {path} ({error})"))?;
+
+    let (name_1, name_2) = transcript.names();
+    let scores = transcript.scores();
+    println!("\n{0}  vs.  {1}   {2} - {3}", name_1, name_2, scores.0, scores.1);
+    println!("{0} rounds, seed {1}\n", transcript.moves().len(), transcript.game().seed().unwrap_or_default());
+
+    if !verify {
+        return Ok(());
+    }
+
+    let registry = registry(strategies)?;
+    let find = |name: &str| registry.find(name)
+        .map(|entry| entry.player())
+        .ok_or_else(|| format!("Unknown program: {name}"));
+    let (player_1, player_2) = (find(name_1)?, find(name_2)?);
+
+    match transcript.verify(&player_1, &player_2) {
+        Some(divergence) => Err(format!("Verification failed. {divergence}")),
+        None => {
+            println!("Verified every round\n");
+            Ok(())
+        }
+    }
+}
+
+fn zero_determinant(relation: &Relation, schedule: &Schedule, rounds: u32, save: Option<&std::path::Path>) -> Result<(), String> {
+    let payoffs = schedule.initial().payoffs();
+    println!("\nEnforcing {relation}\n");
+
+    let strategy = zd::solve(payoffs, relation)
+        .ok_or_else(|| format!("No memory-one strategy enforces {relation}"))?;
+    print!("{strategy}");
+
+    if let Some(path) = save {
+        fs::write(path, format!("# Enforces {relation}\n{strategy}"))
+            .map_err(|error| format!("Could not write {0} ({1})", path.display(), error))?;
+        println!("\nSaved to {0}", path.display());
+    }
+
+    println!("\n{rounds} rounds, average scores per round before doubling\n");
+    println!("program_name                     s_X      s_Y      residual  final_score");
+    println!("--------------------------------------------------------------------------");
+
+    let player = Player::from_strategy(strategy);
+    let game = Game::new(rounds).set_schedule(schedule.clone());
+    for opponent in programs::registry().players() {
+        let transcript = game.record(&player, &opponent).map_err(|error| error.to_string())?;
+        let totals = transcript.moves().iter().fold((0.0, 0.0), |totals, m| {
+            let score = payoffs.score(*m);
+            (totals.0 + f64::from(score.0), totals.1 + f64::from(score.1))
+        });
+
+        let played = transcript.moves().len().max(1) as f64;
+        let (own, other) = (totals.0 / played, totals.1 / played);
+        let scores = transcript.scores();
+        println!("{0:<32} {1:<8.3} {2:<8.3} {3:<9.3} {4} - {5}", opponent.get_name(), own, other, relation.value(own, other), scores.0, scores.1);
+    }
+
+    println!();
+    Ok(())
+}
+
+fn train(path: &std::path::Path, memory: usize, episodes: u32, schedule: &Schedule, rounds: u32, seed: u64) -> Result<(), String> {
+    let table = QTable::new(memory).ok_or_else(|| format!("Memory larger than {0} moves", qlearning::MAX_MEMORY))?;
+    let learner = QLearner::new(table);
+    let opponents = programs::registry().players();
+    let game = Game::new(rounds).set_schedule(schedule.clone());
+
+    println!("\nTraining against {0} programs and itself for {1} episodes of {2} rounds (seed {3})", opponents.len(), episodes, rounds, seed);
+    qlearning::train(&learner, &opponents, &game, episodes, seed);
+
+    let table = learner.table();
+    fs::write(path, format!("# Q-learning agent, memory {memory}, {episodes} episodes\n{table}"))
+        .map_err(|error| format!("Could not write {0} ({1})", path.display(), error))?;
+    println!("Saved {0} states to {1}", table.len(), path.display());
+
+    println!("\nThe frozen agent against every program\n");
+    println!("program_name                     final_score");
+    println!("--------------------------------------------");
+
+    let player = Player::from_strategy(table);
+    let game = game.set_seed(seed);
+    for opponent in &opponents {
+        let scores = game.play(&player, opponent).map_err(|error| error.to_string())?;
+        println!("{0:<32} {1} - {2}", opponent.get_name(), scores.0, scores.1);
+    }
+
+    println!();
+    Ok(())
+}
+
+fn neural(path: &std::path::Path, mlp: Mlp, generations: u32, population: usize, sigma: f64, config: &Config) -> Result<(), String> {
+    let registry = registry(config.strategies())?;
+    let roster = match config.players() {
+        Some(ids) => registry.select(ids)?,
+        None => registry.players(),
+    };
+
+    let seed = config.seed().unwrap_or_else(rand::random);
+    let mut evolution = Evolution::new(mlp, seed)
+        .set_population(population)
+        .set_sigma(sigma);
+
+    println!("\nEvolving against {0} programs for {1} generations (seed {2})\n", roster.len(), generations, seed);
+    println!("generation  best_score  overall_best");
+    println!("------------------------------------");
+
+    for generation in 1..=generations {
+        let score = evolution.step(config, &roster)?;
+        let best = evolution.best().map(|(_, score)| score).unwrap_or(score);
+        println!("{0:<11} {1:<11} {2}", generation, score, best);
+    }
+
+    let (best, score) = evolution.best().ok_or("No generations")?;
+    fs::write(path, format!("# Evolved network, total score {score} against {0} programs\n{best}", roster.len()))
+        .map_err(|error| format!("Could not write {0} ({1})", path.display(), error))?;
+    println!("\nSaved to {0}\n", path.display());
+
+    Ok(())
+}
+
+fn evolve(path: &std::path::Path, mut algorithm: GeneticAlgorithm, generations: u32, config: &Config) -> Result<(), String> {
+    let registry = registry(config.strategies())?;
+    let roster = match config.players() {
+        Some(ids) => registry.select(ids)?,
+        None => registry.players(),
+    };
+
+    let write = |name: &str, contents: &str| {
+        let file = path.join(name);
+        fs::write(&file, contents).map_err(|error| format!("Could not write {0} ({1})", file.display(), error))
+    };
+
+    fs::create_dir_all(path).map_err(|error| format!("Could not create {0} ({1})", path.display(), error))?;
+
+    println!("\nEvolving {0} strategies against {1} programs for {2} generations\n", algorithm.population().len(), roster.len(), generations);
+    println!("generation  best_score  mean_score  worst_score  distinct");
+    println!("---------------------------------------------------------");
+
+    let mut statistics = String::from("generation,best,mean,worst,distinct\n");
+    for _ in 0..generations {
+        let generation = algorithm.step(config, &roster)?;
+        println!(
+            "{0:<11} {1:<11} {2:<11.2} {3:<12} {4}",
+            generation.number(), generation.best(), generation.mean(), generation.worst(), generation.distinct(),
+        );
+
+        statistics += &format!(
+            "{0},{1},{2:.2},{3},{4}\n",
+            generation.number(), generation.best(), generation.mean(), generation.worst(), generation.distinct(),
+        );
+        write("statistics.csv", &statistics)?;
+    }
+
+    for (i, (genome, score)) in algorithm.hall_of_fame().iter().enumerate() {
+        let name = format!("hall_of_fame_{0}.{1}", i + 1, genome.species().extension());
+        write(&name, &format!("# Hall of fame {0}, total score {1} against {2} programs\n{3}", i + 1, score, roster.len(), genome))?;
+    }
+
+    println!("\nSaved the statistics and {0} strategies to {1}\n", algorithm.hall_of_fame().len(), path.display());
+
+    Ok(())
+}
+
+fn solve(players: Option<&[String]>, schedule: &Schedule, rounds: u32, limit: u64, strategies: &[Entry]) -> Result<(), String> {
+    let registry = registry(strategies)?;
+    let entries = match players {
+        Some(ids) => ids.iter()
+            .map(|id| {
+                let entry = registry.get(id).ok_or_else(|| format!("Unknown program: {id}"))?;
+                if !entry.deterministic() {
+                    return Err(format!("Program is not deterministic: {id}"));
+                }
+
+                Ok(entry)
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        None => registry.entries().iter().filter(|entry| entry.deterministic()).collect(),
+    };
+
+    println!("\nBest responses over {rounds} rounds\n");
+    println!("program_name                     score    opponent  optimal");
+    println!("-----------------------------------------------------------");
+
+    for entry in entries {
+        let memory = entry.memory().filter(|memory| *memory <= solver::MAX_MEMORY);
+        let solution = Solver::new(schedule.clone())
+            .set_memory(memory)
+            .set_limit(limit)
+            .solve(&entry.player(), rounds);
+
+        let scores = solution.scores();
+        let optimal = if solution.optimal() { "yes" } else { "no" };
+        println!("{0:<32} {1:<8} {2:<9} {3}", entry.name(), scores.0, scores.1, optimal);
+
+        let colors = solution.colors().iter().map(|color| color.letter()).collect::<String>();
+        println!("    {colors}");
+    }
+
+    println!();
+    Ok(())
+}
+
+fn exploit(id: &str, path: &std::path::Path, fallback: &FsmStrategy, schedule: &Schedule, rounds: u32, limit: u64, strategies: &[Entry]) -> Result<(), String> {
+    // A machine saved by an earlier run is replaced by the new one
+    let exploit_id = format!("exploit_{id}");
+    let strategies = strategies.iter().filter(|entry| entry.id() != exploit_id).cloned().collect::<Vec<_>>();
+    let mut registry = registry(&strategies)?;
+    let entry = registry.get(id).ok_or_else(|| format!("Unknown program: {id}"))?.clone();
+    if !entry.deterministic() {
+        return Err(format!("Program is not deterministic: {id}"));
+    }
+
+    let solution = Solver::new(schedule.clone())
+        .set_memory(entry.memory().filter(|memory| *memory <= solver::MAX_MEMORY))
+        .set_limit(limit)
+        .solve(&entry.player(), rounds);
+    let scores = solution.scores();
+    let optimal = if solution.optimal() { "optimal" } else { "best found" };
+    println!("\nBest response to {0} over {1} rounds ({2}): {3} - {4}", entry.name(), rounds, optimal, scores.0, scores.1);
 
+    let machine = solution.compile(fallback);
+    let dir = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+    fs::create_dir_all(dir).map_err(|error| format!("Could not create {0} ({1})", dir.display(), error))?;
+    fs::write(path, format!("# Best response to {0} over {1} rounds, {2} - {3}\n{4}", entry.name(), rounds, scores.0, scores.1, machine))
+        .map_err(|error| format!("Could not write {0} ({1})", path.display(), error))?;
+    println!("Saved {0} states to {1}\n", machine.states().len(), path.display());
+
+    // The machine is registered as it is loaded from the file, and plays every other program
+    let loaded = tourney::strategies::load(path)?;
+    let name = format!("Exploit {0}", entry.name());
+    let exploiter = Entry::new(&exploit_id, &name, std::sync::Arc::new(move || loaded.player()))
+        .set_description(&format!("Best response to {0} over {1} rounds", entry.name(), rounds))
+        .set_deterministic(true);
+    registry.register(exploiter)?;
+
+    let player = registry.get(&exploit_id).expect("The machine is registered").player();
+    let game = Game::new(rounds).set_schedule(schedule.clone());
+
+    println!("Registered {exploit_id}\n");
+    println!("program_name                     final_score");
+    println!("--------------------------------------------");
+
+    for other in registry.entries().iter().filter(|other| other.id() != exploit_id) {
+        let scores = game.play(&player, &other.player()).map_err(|error| error.to_string())?;
+        let marker = if other.id() == id { "  (target)" } else { "" };
+        println!("{0:<32} {1} - {2}{3}", other.name(), scores.0, scores.1, marker);
+    }
+
+    println!("\nLoad it in later tournaments with --strategies {0}\n", dir.display());
+    Ok(())
+}
+
+fn print_rules(config: &Config) {
     println!("{0} rounds!\n", config.rounds());
+
+    if !config.schedule().changes().is_empty() {
+        println!("{0} scheduled rule change(s)\n", config.schedule().changes().len());
+    }
+}
+
+fn print_scores(config: &Config, players: &[Player]) -> Result<(), String> {
+    let scores = run(config, players).map_err(String::from)?;
+
+    print_rules(config);
+
     println!("no. program_name                     avg_score   rel_win_ratio");
     println!("--------------------------------------------------------------");
 
@@ -51,5 +416,35 @@ fn main() {
         println!("{0:<3} {2:<32} {1:<11.2} {3:<8}", placement, v.0 as f32 / players.len() as f32, v.2, ratio);
     }
 
-    println!("\nTournament end\n");
-}
\ No newline at end of file
+    Ok(())
+}
+
+fn print_survival(config: &Config, players: &[Player]) -> Result<(), String> {
+    let survival = run_survival(config, players).map_err(String::from)?;
+
+    print_rules(config);
+
+    println!("{0} scheduled rounds, starting bankroll {1}\n", survival.rounds, config.bankroll().unwrap_or_default());
+    println!("no. program_name                     bankroll");
+    println!("--------------------------------------------------------------");
+
+    for (i, v) in survival.standings.iter().enumerate() {
+        let placement = format!("{}.", i + 1);
+        println!("{0:<3} {1:<32} {2:<11}", placement, v.1, v.0);
+    }
+
+    if survival.eliminations.is_empty() {
+        return Ok(());
+    }
+
+    println!("\nEliminated");
+    println!("no. program_name                     round   bankroll");
+    println!("--------------------------------------------------------------");
+
+    for (i, elimination) in survival.eliminations.iter().enumerate() {
+        let placement = format!("{}.", i + 1);
+        println!("{0:<3} {1:<32} {2:<7} {3:<11}", placement, elimination.name, elimination.round, elimination.bankroll);
+    }
+
+    Ok(())
+}
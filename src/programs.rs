@@ -1,10 +1,17 @@
-mod prelude {
+/// `prelude` contains what programs commonly use, `use tourney::programs::prelude::*`.
+pub mod prelude {
     pub use std::cmp;
     pub use rand::prelude::*;
     pub use crate::game::{
         Color,
         Move,
+        Context,
         calculate_scores,
+        rng,
+    };
+    pub use crate::history::{
+        History,
+        Side,
     };
 }
 
@@ -15,19 +22,176 @@ pub mod all {
         greedy::*,
         simple::*,
         strategic::*,
+        open_source::*,
     };
 }
 
+use std::sync::Arc;
+use crate::game::Player;
+use crate::registry::{Entry, Registry};
+use crate::strategies::{BayesianStrategy, MctsStrategy};
+use all::*;
+
+/// Get every program in the tournament, registered by a stable identifier.
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    let entries = [
+        Entry::program("take_back_once", "Take back 1", take_back_once_prisoner)
+            .set_description("Retaliates with red once when its green was met with red")
+            .set_deterministic(true)
+            .set_memory(Some(1)),
+        Entry::program("friendly", "Friendly", friendly)
+            .set_description("Always plays green")
+            .set_deterministic(true)
+            .set_memory(Some(0)),
+        Entry::program("evil", "Evil", evil)
+            .set_description("Always plays red")
+            .set_deterministic(true)
+            .set_memory(Some(0)),
+        Entry::program("tit_for_tat", "Tit for tat", tit_for_tat_prisoner)
+            .set_description("Starts green, then plays red after the opponent played red")
+            .set_deterministic(true)
+            .set_memory(Some(1)),
+        Entry::program("tit_for_two_tats", "Tit for two tats", tit_for_two_tats_prisoner)
+            .set_description("Starts green, then plays red after the opponent played red twice in a row")
+            .set_deterministic(true)
+            .set_memory(Some(2)),
+        Entry::program("grim_trigger", "Grim trigger", grim_trigger)
+            .set_author("James W. Friedman")
+            .set_description("Plays green until the opponent plays red, then red forever")
+            .set_deterministic(true),
+        Entry::program("pavlov", "Pavlov", pavlov)
+            .set_author("Martin Nowak and Karl Sigmund")
+            .set_description("Repeats its color after the opponent played green, otherwise switches")
+            .set_deterministic(true)
+            .set_memory(Some(1)),
+        Entry::program("generous_tit_for_tat", "Generous tit for tat", generous_tit_for_tat)
+            .set_author("Martin Nowak and Karl Sigmund")
+            .set_description("Tit for tat, but forgives red a third of the time")
+            .set_memory(Some(1)),
+        Entry::program("gradual", "Gradual", gradual)
+            .set_author("Bruno Beaufils, Jean-Paul Delahaye and Philippe Mathieu")
+            .set_description("Answers the opponent's n-th red with n reds, then two greens")
+            .set_deterministic(true),
+        Entry::program("prober", "Prober", prober)
+            .set_description("Opens with red, green, green and exploits an opponent that does not retaliate")
+            .set_deterministic(true),
+        Entry::program("tester", "Tester", tester)
+            .set_author("David Gladstein")
+            .set_description("Alternates until the opponent retaliates, then apologises and plays tit for tat")
+            .set_deterministic(true),
+        Entry::program("joss", "Joss", joss)
+            .set_author("Johann Joss")
+            .set_description("Tit for tat, but plays red instead of green a tenth of the time")
+            .set_memory(Some(1)),
+        Entry::program("suspicious_tit_for_tat", "Suspicious tit for tat", suspicious_tit_for_tat)
+            .set_description("Tit for tat, but opens with red")
+            .set_deterministic(true)
+            .set_memory(Some(1)),
+        Entry::program("two_tits_for_tat", "Two tits for tat", two_tits_for_tat)
+            .set_description("Answers red with two reds")
+            .set_deterministic(true)
+            .set_memory(Some(2)),
+        Entry::program("hard_majority", "Hard majority", hard_majority)
+            .set_description("Plays red unless the opponent has played green more often than red")
+            .set_deterministic(true),
+        Entry::program("soft_majority", "Soft majority", soft_majority)
+            .set_description("Plays green unless the opponent has played red more often than green")
+            .set_deterministic(true),
+        Entry::program("adaptive", "Adaptive", adaptive)
+            .set_description("Tries green and red, then plays whichever scored the most per round")
+            .set_deterministic(true),
+        Entry::program("handshake", "Handshake", handshake)
+            .set_description("Opens with red, green and cooperates only with opponents that open the same way")
+            .set_deterministic(true),
+        Entry::program("alternator", "Alternator", alternator)
+            .set_description("Alternates between green and red")
+            .set_deterministic(true),
+        Entry::program("forgiver", "Forgiver", forgiver)
+            .set_description("Plays red once the opponent has played red in more than a tenth of the rounds")
+            .set_deterministic(true),
+        Entry::program("blue_tit_for_tat", "Blue tit for tat", blue_tit_for_tat)
+            .set_description("Tit for tat, but answers blue with blue")
+            .set_deterministic(true)
+            .set_memory(Some(1)),
+        Entry::program("greedy_blue_and_friendly", "Greedy and friendly", greedy_blue_and_friendly)
+            .set_description("Plays blue until it has played more blue than the opponent, then green")
+            .set_deterministic(true),
+        Entry::program("greedy_blue_and_evil", "Greedy and evil", greedy_blue_and_evil)
+            .set_description("Plays blue until it has played more blue than the opponent, then red")
+            .set_deterministic(true),
+        Entry::program("blue", "Greedy blue", blue)
+            .set_description("Always plays blue")
+            .set_deterministic(true)
+            .set_memory(Some(0)),
+        Entry::program("try_to_guess", "Try to guess", try_to_guess)
+            .set_description("Counters the opponent's most frequent color"),
+        Entry::program("random", "Random", random)
+            .set_description("Plays a uniformly random color")
+            .set_memory(Some(0)),
+        Entry::program("chat_gpt_adaptive", "ChatGPT adaptive", chat_gpt_adaptive)
+            .set_author("ChatGPT")
+            .set_description("Responds to the opponent's last color")
+            .set_memory(Some(1)),
+        Entry::program("chat_gpt_proactive", "ChatGPT proactive", chat_gpt_proactive)
+            .set_author("ChatGPT")
+            .set_description("Exploits an opponent that plays green more often than blue, otherwise green"),
+        Entry::program("chat_gpt_versatile", "ChatGPT versatile", chat_gpt_versatile)
+            .set_author("ChatGPT")
+            .set_description("Responds to the opponent's last color, or plays a random color half of the time")
+            .set_memory(Some(1)),
+        Entry::program("cooperate_until_defection", "Cooperate until defection", cooperate_until_defection)
+            .set_description("Plays green until the opponent plays red, then red or blue"),
+        Entry::program("greedy_if_winning_else_random", "Random, greedy if winning", greedy_if_winning_else_random)
+            .set_description("Plays blue while winning, otherwise a random color"),
+        Entry::program("greedy_if_2x_score_else_random", "Random, greedy if 2x", greedy_if_2x_score_else_random)
+            .set_description("Plays blue while at double the opponent's score, otherwise a random color"),
+        Entry::program("copy", "Copy opponent", copy)
+            .set_description("Plays the opponent's last color")
+            .set_memory(Some(1)),
+        Entry::program("smarter_copy", "Copy opponent, unless blue", smarter_copy)
+            .set_description("Plays the opponent's last color, but answers blue with green or blue")
+            .set_memory(Some(1)),
+        Entry::program("greed_first_15", "Match opponent, 15 greedy start", greed_first_15)
+            .set_description("Goes for a blue lead in the first 15 rounds, then matches the opponent's most frequent color"),
+        Entry::open("fair", "Fair", fair)
+            .set_description("Plays red if the opponent would play red against it, otherwise green"),
+        Entry::open("exploit_predictable", "Exploit if predictable", exploit_predictable)
+            .set_description("Plays green if the opponent would play blue against it, otherwise red"),
+    ];
+
+    for entry in entries {
+        registry.register(entry).unwrap();
+    }
+
+    // Models its opponent as one of the programs above
+    let candidates = registry.entries().to_vec();
+    let bayesian = Entry::new("bayesian", "Bayesian", Arc::new(move || Player::from_strategy(BayesianStrategy::new(&candidates))))
+        .set_description("Identifies which of the other programs it is facing, and best-responds to it")
+        .set_deterministic(true);
+    registry.register(bayesian).unwrap();
+
+    // Searches too long per move to be simulated by the Bayesian program
+    let mcts = Entry::new("mcts", "Monte Carlo tree search", Arc::new(|| Player::from_strategy(MctsStrategy::new())))
+        .set_description("Plans to the expected end of the game against a model of the opponent's last colors");
+    registry.register(mcts).unwrap();
+
+    registry
+}
+
 pub mod chat_gpt;
 pub mod prisoners;
 pub mod greedy;
 pub mod simple;
 pub mod strategic;
+pub mod open_source;
 
 #[cfg(test)]
 mod tests {
     use super::prelude::*;
     use super::all::*;
+    use crate::game::{Game, Player};
+    use crate::simulation::Limits;
 
     #[test]
     fn friendly_test() {
@@ -70,6 +234,140 @@ mod tests {
         assert!(tit_for_two_tats_prisoner(&[(Color::Green, Color::Red), (Color::Green, Color::Red)]) == Color::Red);
     }
 
+    #[test]
+    fn grim_trigger_test() {
+        assert!(grim_trigger(&[]) == Color::Green);
+        assert!(grim_trigger(&[(Color::Green, Color::Blue)]) == Color::Green);
+        assert!(grim_trigger(&[(Color::Green, Color::Red), (Color::Red, Color::Green)]) == Color::Red);
+    }
+
+    #[test]
+    fn pavlov_test() {
+        assert!(pavlov(&[]) == Color::Green);
+        assert!(pavlov(&[(Color::Green, Color::Green)]) == Color::Green);
+        assert!(pavlov(&[(Color::Red, Color::Green)]) == Color::Red);
+        assert!(pavlov(&[(Color::Green, Color::Red)]) == Color::Red);
+        assert!(pavlov(&[(Color::Red, Color::Red)]) == Color::Green);
+        assert!(pavlov(&[(Color::Green, Color::Blue)]) == Color::Red);
+        assert!(pavlov(&[(Color::Blue, Color::Green)]) == Color::Green);
+    }
+
+    #[test]
+    fn generous_tit_for_tat_test() {
+        assert!(generous_tit_for_tat(&[]) == Color::Green);
+        assert!(generous_tit_for_tat(&[(Color::Green, Color::Blue)]) == Color::Green);
+
+        let reds = (0..300).filter(|_| generous_tit_for_tat(&[(Color::Green, Color::Red)]) == Color::Red).count();
+        assert!(reds > 100 && reds < 300);
+    }
+
+    #[test]
+    fn gradual_test() {
+        let mut moves = vec![];
+        let opponent = [Color::Red, Color::Green, Color::Green, Color::Green, Color::Red, Color::Blue, Color::Green, Color::Green, Color::Green];
+        for color in opponent {
+            moves.push((gradual(&moves), color));
+        }
+
+        let colors = moves.iter().map(|m| m.0).collect::<Vec<_>>();
+        assert_eq!(colors, [Color::Green, Color::Red, Color::Green, Color::Green, Color::Green, Color::Red, Color::Red, Color::Green, Color::Green]);
+        assert!(gradual(&moves) == Color::Green);
+    }
+
+    #[test]
+    fn prober_test() {
+        assert!(prober(&[]) == Color::Red);
+        assert!(prober(&[(Color::Red, Color::Green)]) == Color::Green);
+        assert!(prober(&[(Color::Red, Color::Green), (Color::Green, Color::Green), (Color::Green, Color::Blue)]) == Color::Red);
+        assert!(prober(&[(Color::Red, Color::Green), (Color::Green, Color::Red), (Color::Green, Color::Green)]) == Color::Green);
+    }
+
+    #[test]
+    fn tester_test() {
+        assert!(tester(&[]) == Color::Red);
+        assert!(tester(&[(Color::Red, Color::Green)]) == Color::Green);
+        assert!(tester(&[(Color::Red, Color::Green), (Color::Green, Color::Blue)]) == Color::Red);
+        assert!(tester(&[(Color::Red, Color::Red)]) == Color::Green);
+        assert!(tester(&[(Color::Red, Color::Red), (Color::Green, Color::Red)]) == Color::Red);
+        assert!(tester(&[(Color::Red, Color::Red), (Color::Green, Color::Green), (Color::Green, Color::Green)]) == Color::Green);
+    }
+
+    #[test]
+    fn joss_test() {
+        assert!(joss(&[(Color::Green, Color::Red)]) == Color::Red);
+
+        let reds = (0..300).filter(|_| joss(&[(Color::Green, Color::Green)]) == Color::Red).count();
+        assert!(reds > 0 && reds < 100);
+    }
+
+    #[test]
+    fn suspicious_tit_for_tat_test() {
+        assert!(suspicious_tit_for_tat(&[]) == Color::Red);
+        assert!(suspicious_tit_for_tat(&[(Color::Red, Color::Green)]) == Color::Green);
+        assert!(suspicious_tit_for_tat(&[(Color::Red, Color::Red)]) == Color::Red);
+    }
+
+    #[test]
+    fn two_tits_for_tat_test() {
+        assert!(two_tits_for_tat(&[]) == Color::Green);
+        assert!(two_tits_for_tat(&[(Color::Green, Color::Red), (Color::Red, Color::Green)]) == Color::Red);
+        assert!(two_tits_for_tat(&[(Color::Green, Color::Red), (Color::Red, Color::Green), (Color::Red, Color::Blue)]) == Color::Green);
+    }
+
+    #[test]
+    fn majority_test() {
+        assert!(hard_majority(&[]) == Color::Red);
+        assert!(soft_majority(&[]) == Color::Green);
+        assert!(hard_majority(&[(Color::Red, Color::Green), (Color::Red, Color::Blue)]) == Color::Green);
+        assert!(soft_majority(&[(Color::Green, Color::Red), (Color::Red, Color::Blue)]) == Color::Red);
+        assert!(hard_majority(&[(Color::Red, Color::Green), (Color::Green, Color::Red)]) == Color::Red);
+        assert!(soft_majority(&[(Color::Red, Color::Green), (Color::Green, Color::Red)]) == Color::Green);
+    }
+
+    #[test]
+    fn adaptive_test() {
+        let opening = (0..11).fold(vec![], |mut moves, _| {
+            moves.push((adaptive(&moves), Color::Green));
+            moves
+        });
+
+        assert_eq!(opening.iter().filter(|m| m.0 == Color::Red).count(), 5);
+        assert!(adaptive(&opening) == Color::Red);
+
+        let punished = opening.iter().map(|m| (m.0, if m.0 == Color::Red { Color::Red } else { Color::Green })).collect::<Vec<_>>();
+        assert!(adaptive(&punished) == Color::Green);
+    }
+
+    #[test]
+    fn handshake_test() {
+        assert!(handshake(&[]) == Color::Red);
+        assert!(handshake(&[(Color::Red, Color::Red)]) == Color::Green);
+        assert!(handshake(&[(Color::Red, Color::Red), (Color::Green, Color::Green)]) == Color::Green);
+        assert!(handshake(&[(Color::Red, Color::Red), (Color::Green, Color::Blue)]) == Color::Red);
+    }
+
+    #[test]
+    fn alternator_test() {
+        assert!(alternator(&[]) == Color::Green);
+        assert!(alternator(&[(Color::Green, Color::Red)]) == Color::Red);
+    }
+
+    #[test]
+    fn forgiver_test() {
+        let mut moves = vec![(Color::Green, Color::Red)];
+        assert!(forgiver(&moves) == Color::Red);
+
+        moves.extend([(Color::Red, Color::Green); 9]);
+        assert!(forgiver(&moves) == Color::Green);
+    }
+
+    #[test]
+    fn blue_tit_for_tat_test() {
+        assert!(blue_tit_for_tat(&[]) == Color::Green);
+        assert!(blue_tit_for_tat(&[(Color::Green, Color::Blue)]) == Color::Blue);
+        assert!(blue_tit_for_tat(&[(Color::Blue, Color::Red)]) == Color::Red);
+    }
+
     #[test]
     fn greedy_blue_and_evil_test() {
         assert!(greedy_blue_and_evil(&[]) == Color::Blue);
@@ -122,6 +420,23 @@ mod tests {
         assert!(copy(&[(Color::Red, Color::Blue)]) == Color::Blue);
     }
 
+    #[test]
+    fn fair_test() {
+        let game = Game::new(1).set_simulation(Limits::default());
+        assert_eq!(game.play(&Player::open(fair), &Player::new(friendly)), Ok((2, 2)));
+        assert_eq!(game.play(&Player::open(fair), &Player::new(evil)), Ok((1, 1)));
+        assert_eq!(game.play(&Player::open(fair), &Player::open(fair)), Ok((2, 2)));
+        assert_eq!(Game::new(1).play(&Player::open(fair), &Player::new(evil)), Ok((0, 3)));
+    }
+
+    #[test]
+    fn exploit_predictable_test() {
+        let game = Game::new(1).set_simulation(Limits::default());
+        assert_eq!(game.play(&Player::open(exploit_predictable), &Player::new(friendly)), Ok((3, 0)));
+        assert_eq!(game.play(&Player::open(exploit_predictable), &Player::new(blue)), Ok((1, -2)));
+        assert_eq!(game.play(&Player::open(exploit_predictable), &Player::open(fair)), Ok((1, 1)));
+    }
+
     #[test]
     fn smarter_copy_test() {
         assert!(copy(&[(Color::Red, Color::Red)]) == Color::Red);
@@ -10,11 +10,11 @@ pub fn chat_gpt_adaptive(last_moves: &[Move]) -> Color {
     }
 
     // If no opponent moves recorded, choose randomly
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
 
 pub fn chat_gpt_proactive(last_moves: &[Move]) -> Color {
-    if let Some(_) = last_moves.last() {
+    if !last_moves.is_empty() {
         // Analyze the opponent's historical moves
         let green_count = last_moves.iter().filter(|m| m.1 == Color::Green).count();
         let blue_count = last_moves.iter().filter(|m| m.1 == Color::Blue).count();
@@ -30,13 +30,13 @@ pub fn chat_gpt_proactive(last_moves: &[Move]) -> Color {
     }
 
     // If no opponent moves recorded, choose randomly
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
 
 pub fn chat_gpt_versatile(last_moves: &[Move]) -> Color {
-    if last_moves.is_empty() || rand::thread_rng().gen::<f64>() < 0.5 {
+    if last_moves.is_empty() || rng().gen::<f64>() < 0.5 {
         // Introduce randomness or choose randomly if no history
-        return *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap();
+        return *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap();
     }
 
     // Follow the opponent's recent move
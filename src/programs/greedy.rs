@@ -1,15 +1,9 @@
 use crate::programs::prelude::*;
 
 pub fn greedy_blue_and_friendly(last_moves: &[Move]) -> Color {
-    let blue_count = last_moves.iter()
-        .fold((0, 0), |acc, m| {
-            match m {
-                (Color::Blue, Color::Blue) => (acc.0 + 1, acc.1 + 1),
-                (Color::Blue, _) => (acc.0 + 1, acc.1),
-                (_, Color::Blue) => (acc.0, acc.1 + 1),
-                _ => acc,
-            }
-        });
+    let blue_count = History::cached(last_moves, |history| {
+        (history.count(Side::Own, Color::Blue), history.count(Side::Opponent, Color::Blue))
+    });
 
     if blue_count.0 > blue_count.1 {
         Color::Green
@@ -19,15 +13,9 @@ pub fn greedy_blue_and_friendly(last_moves: &[Move]) -> Color {
 }
 
 pub fn greedy_blue_and_evil(last_moves: &[Move]) -> Color {
-    let blue_count = last_moves.iter()
-        .fold((0, 0), |acc, m| {
-            match m {
-                (Color::Blue, Color::Blue) => (acc.0 + 1, acc.1 + 1),
-                (Color::Blue, _) => (acc.0 + 1, acc.1),
-                (_, Color::Blue) => (acc.0, acc.1 + 1),
-                _ => acc,
-            }
-        });
+    let blue_count = History::cached(last_moves, |history| {
+        (history.count(Side::Own, Color::Blue), history.count(Side::Opponent, Color::Blue))
+    });
 
     if blue_count.0 > blue_count.1 {
         Color::Red
@@ -43,7 +31,7 @@ pub fn greedy_if_winning_else_random(last_moves: &[Move]) -> Color {
         return Color::Blue;
     }
 
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
 
 pub fn greedy_if_2x_score_else_random(last_moves: &[Move]) -> Color {
@@ -53,5 +41,5 @@ pub fn greedy_if_2x_score_else_random(last_moves: &[Move]) -> Color {
         return Color::Blue;
     }
 
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use crate::programs::prelude::*;
+
+/// `fair` plays what the opponent would play in its place, which means it cooperates exactly when the opponent
+/// would cooperate back. If the opponent cannot be simulated, e.g. when it simulates `fair` in turn, it cooperates.
+pub fn fair(last_moves: &[Move], context: &mut Context) -> Color {
+    match context.simulate(last_moves) {
+        Ok(Color::Red) => Color::Red,
+        _ => Color::Green,
+    }
+}
+
+/// `exploit_predictable` exploits opponents that would cooperate and punishes those that would not.
+/// Opponents that give away their points with blue are answered with green.
+pub fn exploit_predictable(last_moves: &[Move], context: &mut Context) -> Color {
+    match context.simulate(last_moves) {
+        Ok(Color::Blue) => Color::Green,
+        _ => Color::Red,
+    }
+}
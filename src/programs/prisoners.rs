@@ -32,4 +32,218 @@ pub fn tit_for_two_tats_prisoner(last_moves: &[Move]) -> Color {
     }
 
     Color::Green
-}
\ No newline at end of file
+}
+
+/// `grim_trigger` cooperates until the opponent plays red once, and then plays red forever.
+/// Blue gives away a point, so it is treated as cooperation.
+pub fn grim_trigger(last_moves: &[Move]) -> Color {
+    if last_moves.iter().any(|m| m.1 == Color::Red) {
+        return Color::Red;
+    }
+
+    Color::Green
+}
+
+/// `pavlov` (win-stay, lose-shift) repeats its color after a good round, in which the opponent played green,
+/// and switches between green and red after a bad one. Blue is a bad round either way; after playing blue itself
+/// it switches to green.
+pub fn pavlov(last_moves: &[Move]) -> Color {
+    match last_moves.last() {
+        Some((Color::Blue, _)) => Color::Green,
+        Some((own, Color::Green)) => *own,
+        Some((Color::Green, _)) => Color::Red,
+        _ => Color::Green,
+    }
+}
+
+/// `generous_tit_for_tat` answers red with red, but forgives it a third of the time.
+/// Blue is answered with green.
+pub fn generous_tit_for_tat(last_moves: &[Move]) -> Color {
+    if let Some(last_move) = last_moves.last() {
+        if last_move.1 == Color::Red && rng().gen::<f64>() >= 1.0 / 3.0 {
+            return Color::Red;
+        }
+    }
+
+    Color::Green
+}
+
+/// `gradual` answers the opponent's n-th red with n reds, followed by two greens to calm things down.
+/// Reds played while it punishes or calms down count towards n, blue does not.
+pub fn gradual(last_moves: &[Move]) -> Color {
+    let (mut reds, mut punish, mut calm) = (0, 0, 0);
+
+    for m in last_moves {
+        if m.1 == Color::Red {
+            reds += 1;
+        }
+
+        if punish > 0 {
+            punish -= 1;
+            if punish == 0 {
+                calm = 2;
+            }
+        } else if calm > 0 {
+            calm -= 1;
+        } else if m.1 == Color::Red {
+            punish = reds;
+        }
+    }
+
+    if punish > 0 {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+/// `prober` opens with red, green, green. If the opponent did not answer the red with red, it plays red forever,
+/// otherwise it plays tit for tat. Blue does not count as an answer.
+pub fn prober(last_moves: &[Move]) -> Color {
+    match last_moves.len() {
+        0 => Color::Red,
+        1 | 2 => Color::Green,
+        _ if last_moves[1..3].iter().all(|m| m.1 != Color::Red) => Color::Red,
+        _ => tit_for_tat_prisoner(last_moves),
+    }
+}
+
+/// `tester` opens with red. Once the opponent answers with red it apologises with green and plays tit for tat,
+/// until then it alternates between green and red. Blue does not count as an answer.
+pub fn tester(last_moves: &[Move]) -> Color {
+    match last_moves.iter().position(|m| m.1 == Color::Red) {
+        Some(first) if first + 1 == last_moves.len() => Color::Green,
+        Some(_) => tit_for_tat_prisoner(last_moves),
+        None if last_moves.len() % 2 == 0 => Color::Red,
+        None => Color::Green,
+    }
+}
+
+/// `joss` plays tit for tat, but sneaks in red instead of green a tenth of the time.
+/// Blue is answered like green.
+pub fn joss(last_moves: &[Move]) -> Color {
+    match tit_for_tat_prisoner(last_moves) {
+        Color::Green if rng().gen::<f64>() < 0.1 => Color::Red,
+        color => color,
+    }
+}
+
+/// `suspicious_tit_for_tat` plays tit for tat, but opens with red.
+/// Blue is answered with green.
+pub fn suspicious_tit_for_tat(last_moves: &[Move]) -> Color {
+    if last_moves.is_empty() {
+        return Color::Red;
+    }
+
+    tit_for_tat_prisoner(last_moves)
+}
+
+/// `two_tits_for_tat` answers red with two reds.
+/// Blue is answered with green.
+pub fn two_tits_for_tat(last_moves: &[Move]) -> Color {
+    if last_moves.iter().rev().take(2).any(|m| m.1 == Color::Red) {
+        return Color::Red;
+    }
+
+    Color::Green
+}
+
+/// `hard_majority` plays red unless the opponent has played green more often than red, and opens with red.
+/// Blue is not counted.
+pub fn hard_majority(last_moves: &[Move]) -> Color {
+    let (reds, greens) = last_moves.iter()
+        .fold((0, 0), |(r, g), m| match m.1 {
+            Color::Red => (r + 1, g),
+            Color::Green => (r, g + 1),
+            Color::Blue => (r, g),
+        });
+
+    if greens > reds {
+        Color::Green
+    } else {
+        Color::Red
+    }
+}
+
+/// `soft_majority` plays green unless the opponent has played red more often than green, and opens with green.
+/// Blue is not counted.
+pub fn soft_majority(last_moves: &[Move]) -> Color {
+    let (reds, greens) = last_moves.iter()
+        .fold((0, 0), |(r, g), m| match m.1 {
+            Color::Red => (r + 1, g),
+            Color::Green => (r, g + 1),
+            Color::Blue => (r, g),
+        });
+
+    if reds > greens {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+/// `adaptive` opens with six greens and five reds, and then plays whichever of green and red has scored
+/// the most per round so far. It never plays blue, but the points an opponent's blue gives away are counted.
+pub fn adaptive(last_moves: &[Move]) -> Color {
+    const OPENING: [Color; 11] = [
+        Color::Green, Color::Green, Color::Green, Color::Green, Color::Green, Color::Green,
+        Color::Red, Color::Red, Color::Red, Color::Red, Color::Red,
+    ];
+
+    if let Some(color) = OPENING.get(last_moves.len()) {
+        return *color;
+    }
+
+    let average = |color: Color| {
+        let moves = last_moves.iter().filter(|m| m.0 == color).copied().collect::<Vec<_>>();
+        calculate_scores(&moves).0 as f64 / moves.len().max(1) as f64
+    };
+
+    if average(Color::Red) > average(Color::Green) {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+/// `handshake` opens with red, green. If the opponent opened the same way it cooperates forever,
+/// otherwise it plays red forever. Blue in the opening breaks the handshake.
+pub fn handshake(last_moves: &[Move]) -> Color {
+    match last_moves.len() {
+        0 => Color::Red,
+        1 => Color::Green,
+        _ if last_moves[0].1 == Color::Red && last_moves[1].1 == Color::Green => Color::Green,
+        _ => Color::Red,
+    }
+}
+
+/// `alternator` alternates between green and red, starting with green.
+/// It ignores the opponent, blue included.
+pub fn alternator(last_moves: &[Move]) -> Color {
+    if last_moves.len() % 2 == 0 {
+        Color::Green
+    } else {
+        Color::Red
+    }
+}
+
+/// `forgiver` plays red after the opponent has played red in more than a tenth of the rounds, otherwise green.
+/// Blue is not counted.
+pub fn forgiver(last_moves: &[Move]) -> Color {
+    let reds = last_moves.iter().filter(|m| m.1 == Color::Red).count();
+
+    if reds * 10 > last_moves.len() {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+/// `blue_tit_for_tat` plays tit for tat, but answers blue with blue so the opponent cannot take the doubling
+/// for free.
+pub fn blue_tit_for_tat(last_moves: &[Move]) -> Color {
+    match last_moves.last() {
+        Some((_, Color::Blue)) => Color::Blue,
+        _ => tit_for_tat_prisoner(last_moves),
+    }
+}
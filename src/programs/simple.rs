@@ -13,12 +13,12 @@ pub fn blue(_last_moves: &[Move]) -> Color {
 }
 
 pub fn random(_last_moves: &[Move]) -> Color {
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
 
 pub fn cooperate_until_defection(last_moves: &[Move]) -> Color {
     if last_moves.iter().any(|m| m.1 == Color::Red) {
-        return *[Color::Red, Color::Blue].choose(&mut rand::thread_rng()).unwrap();
+        return *[Color::Red, Color::Blue].choose(&mut rng()).unwrap();
     }
 
     Color::Green
@@ -29,13 +29,13 @@ pub fn copy(last_moves: &[Move]) -> Color {
         return last_move.1;
     }
 
-    *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap()
+    *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
 }
 
 pub fn smarter_copy(last_moves: &[Move]) -> Color {
     match last_moves.last().map(|m| m.1) {
-        Some(Color::Blue) => *[Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap(),
+        Some(Color::Blue) => *[Color::Green, Color::Blue].choose(&mut rng()).unwrap(),
         Some(opponent_move) => opponent_move,
-        _ => *[Color::Red, Color::Green, Color::Blue].choose(&mut rand::thread_rng()).unwrap(),
+        _ => *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap(),
     }
 }
\ No newline at end of file
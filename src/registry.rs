@@ -0,0 +1,241 @@
+//! `registry` maps stable identifiers to program constructors and their metadata.
+
+use std::{fmt, sync::Arc};
+use crate::game::Player;
+
+/// The type definition for a constructor of a named [player](Player).
+pub type Constructor = Arc<dyn Fn() -> Player + Send + Sync>;
+
+/// A registered program.
+#[derive(Clone)]
+pub struct Entry {
+    id: String,
+    name: String,
+    author: String,
+    description: String,
+    deterministic: bool,
+    memory: Option<usize>,
+    constructor: Constructor,
+}
+
+impl Entry {
+    /// Create a new entry, without an author or description. The program is assumed to be
+    /// randomised and to look at the whole history.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A stable identifier, e.g. `tit_for_tat`
+    /// * `name` - A display name, which is also given to the constructed [player](Player)
+    /// * `constructor` - A constructor of the [player](Player)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tourney::game::{Player, Color, Move};
+    /// use tourney::registry::Entry;
+    ///
+    /// fn example_friendly_program(_last_moves: &[Move]) -> Color {
+    ///     Color::Green
+    /// }
+    ///
+    /// let entry = Entry::new("friendly", "Friendly", Arc::new(|| Player::new(example_friendly_program)))
+    ///     .set_description("Always cooperates")
+    ///     .set_deterministic(true)
+    ///     .set_memory(Some(0));
+    ///
+    /// assert_eq!(entry.player().get_name(), "Friendly");
+    /// ```
+    pub fn new(id: &str, name: &str, constructor: Constructor) -> Entry {
+        Entry {
+            id: String::from(id),
+            name: String::from(name),
+            author: String::new(),
+            description: String::new(),
+            deterministic: false,
+            memory: None,
+            constructor,
+        }
+    }
+
+    /// Create a new entry for a [program](crate::game::Program).
+    pub fn program(id: &str, name: &str, program: crate::game::Program) -> Entry {
+        Entry::new(id, name, Arc::new(move || Player::new(program)))
+    }
+
+    /// Create a new entry for an open-source [program](crate::game::ContextProgram).
+    pub fn open(id: &str, name: &str, program: crate::game::ContextProgram) -> Entry {
+        Entry::new(id, name, Arc::new(move || Player::open(program)))
+    }
+
+    /// Set the author of the program.
+    pub fn set_author(mut self, author: &str) -> Entry {
+        self.author = String::from(author);
+        self
+    }
+
+    /// Set a short description of the program.
+    pub fn set_description(mut self, description: &str) -> Entry {
+        self.description = String::from(description);
+        self
+    }
+
+    /// Set whether the program always plays the same color after the same moves.
+    pub fn set_deterministic(mut self, deterministic: bool) -> Entry {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Set how many of the last moves the program looks at, `None` if it may look at the whole history.
+    pub fn set_memory(mut self, memory: Option<usize>) -> Entry {
+        self.memory = memory;
+        self
+    }
+
+    /// Get the stable identifier.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the display name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the author.
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    /// Get the description.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Whether the program always plays the same color after the same moves.
+    pub fn deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Get how many of the last moves the program looks at, `None` if it may look at the whole history.
+    pub fn memory(&self) -> Option<usize> {
+        self.memory
+    }
+
+    /// Construct the [player](Player), named by the display name.
+    pub fn player(&self) -> Player {
+        (self.constructor)().set_name(&self.name)
+    }
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("author", &self.author)
+            .field("description", &self.description)
+            .field("deterministic", &self.deterministic)
+            .field("memory", &self.memory)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A collection of registered programs, in the order they were registered.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// Create a new empty registry.
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Register a program.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The [entry](Entry) of the program
+    ///
+    /// # Errors
+    ///
+    /// If a program with the same identifier is already registered.
+    pub fn register(&mut self, entry: Entry) -> Result<(), String> {
+        if self.get(entry.id()).is_some() {
+            return Err(format!("Duplicate program: {0}", entry.id()));
+        }
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Get the entry of a program by its identifier.
+    pub fn get(&self, id: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id() == id)
+    }
+
+    /// Get the entry of a program by its identifier or, failing that, its display name.
+    pub fn find(&self, name: &str) -> Option<&Entry> {
+        self.get(name).or_else(|| self.entries.iter().find(|entry| entry.name() == name))
+    }
+
+    /// Get every entry.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Construct every registered program as a [player](Player).
+    pub fn players(&self) -> Vec<Player> {
+        self.entries.iter().map(Entry::player).collect()
+    }
+
+    /// Construct a selection of programs as [players](Player).
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The identifiers of the programs
+    ///
+    /// # Errors
+    ///
+    /// If an identifier is not registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::programs::registry;
+    ///
+    /// let players = registry().select(&["tit_for_tat", "evil"]).unwrap();
+    ///
+    /// assert_eq!(players[0].get_name(), "Tit for tat");
+    /// assert_eq!(registry().select(&["nobody"]).err(), Some(String::from("Unknown program: nobody")));
+    /// ```
+    pub fn select<S: AsRef<str>>(&self, ids: &[S]) -> Result<Vec<Player>, String> {
+        ids.iter()
+            .map(|id| {
+                self.get(id.as_ref())
+                    .map(Entry::player)
+                    .ok_or_else(|| format!("Unknown program: {0}", id.as_ref()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::all::*;
+
+    #[test]
+    fn register_test() {
+        let mut registry = Registry::new();
+        registry.register(Entry::program("evil", "Evil", evil)).unwrap();
+        registry.register(Entry::open("fair", "Fair", fair).set_author("someone")).unwrap();
+
+        assert_eq!(registry.register(Entry::program("evil", "Evil", evil)), Err(String::from("Duplicate program: evil")));
+        assert_eq!(registry.entries().len(), 2);
+        assert_eq!(registry.find("Fair").map(Entry::author), Some("someone"));
+        assert_eq!(registry.players()[1].get_name(), "Fair");
+    }
+}
@@ -0,0 +1,387 @@
+//! `replay` contains transcripts of recorded games, and the means to verify them against their programs.
+//!
+//! A [transcript](Transcript) is saved in a simple text format:
+//!
+//! ```text
+//! player_1 Tit for tat
+//! player_2 Random
+//! rounds 3
+//! seed 1234
+//! rule payoffs 1 3 1 0 2 1 -1 -1 0
+//! rule doubling on
+//! scores 5 2
+//! moves GB GR RG
+//! ```
+//!
+//! Open-source games also have a `simulation <depth> <calls> <milliseconds>` line, and games with a compute penalty
+//! a `penalty <cost>` line. The `rule` lines hold the [schedule](crate::rules) of the game.
+
+use std::{fmt, str::FromStr, time::Duration};
+use crate::game::{Color, Move, Score, Game, Player};
+use crate::rules::Schedule;
+use crate::simulation::Limits;
+
+/// A recorded game.
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    names: (String, String),
+    game: Game,
+    moves: Vec<Move>,
+    scores: (Score, Score),
+}
+
+impl Transcript {
+    /// Create a new transcript.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names of the players
+    /// * `game` - The [game](Game) that was played, with the seed that was used
+    /// * `moves` - The [moves](Move) that were played
+    /// * `scores` - The final scores
+    pub(crate) fn new(names: (&str, &str), game: Game, moves: Vec<Move>, scores: (Score, Score)) -> Transcript {
+        Transcript {
+            names: (String::from(names.0), String::from(names.1)),
+            game,
+            moves,
+            scores,
+        }
+    }
+
+    /// Get the names of the players.
+    pub fn names(&self) -> (&str, &str) {
+        (&self.names.0, &self.names.1)
+    }
+
+    /// Get the [game](Game) that was played, including its seed.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Get the [moves](Move) that were played.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Get the final scores.
+    pub fn scores(&self) -> (Score, Score) {
+        self.scores
+    }
+
+    /// Re-run the programs move by move on the recorded moves, with the recorded seed.
+    ///
+    /// Every move is made on the recorded moves before it, so a divergence is reported in the round it happens.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_1` - The first [player](Player) of the recorded game
+    /// * `player_2` - The second [player](Player) of the recorded game
+    ///
+    /// # Returns
+    ///
+    /// The first [divergence](Divergence) from the recorded moves, or `None` if the programs play every recorded move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::*;
+    /// use tourney::replay::Transcript;
+    /// # use rand::prelude::*;
+    /// # fn example_random_program(_last_moves: &[Move]) -> Color {
+    /// #     *[Color::Red, Color::Green, Color::Blue].choose(&mut rng()).unwrap()
+    /// # }
+    /// # fn example_evil_program(_last_moves: &[Move]) -> Color {
+    /// #     Color::Red
+    /// # }
+    ///
+    /// let random = Player::with_name("Random", example_random_program);
+    /// let evil = Player::with_name("Evil", example_evil_program);
+    ///
+    /// let transcript = Game::new(50).record(&random, &evil).unwrap();
+    /// let saved = transcript.to_string().parse::<Transcript>().unwrap();
+    ///
+    /// assert_eq!(saved.verify(&random, &evil), None);
+    /// assert_eq!(saved.verify(&evil, &evil).map(|divergence| divergence.player_1()), Some(true));
+    /// ```
+    pub fn verify(&self, player_1: &Player, player_2: &Player) -> Option<Divergence> {
+        self.game.verify(player_1, player_2, &self.moves)
+    }
+}
+
+/// The first round in which a replayed game differs from its [transcript](Transcript).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divergence {
+    round: u32,
+    recorded: Move,
+    replayed: Move,
+}
+
+impl Divergence {
+    /// Create a new divergence.
+    pub(crate) fn new(round: u32, recorded: Move, replayed: Move) -> Divergence {
+        Divergence {
+            round,
+            recorded,
+            replayed,
+        }
+    }
+
+    /// Get the round, counting from 1.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Get the recorded [move](Move).
+    pub fn recorded(&self) -> Move {
+        self.recorded
+    }
+
+    /// Get the replayed [move](Move).
+    pub fn replayed(&self) -> Move {
+        self.replayed
+    }
+
+    /// Whether the first player's output differs.
+    pub fn player_1(&self) -> bool {
+        self.recorded.0 != self.replayed.0
+    }
+
+    /// Whether the second player's output differs.
+    pub fn player_2(&self) -> bool {
+        self.recorded.1 != self.replayed.1
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Round {0}:", self.round)?;
+
+        if self.player_1() {
+            write!(f, " player 1 played {0:?} (recorded {1:?})", self.replayed.0, self.recorded.0)?;
+        }
+
+        if self.player_2() {
+            write!(f, " player 2 played {0:?} (recorded {1:?})", self.replayed.1, self.recorded.1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Transcript {
+    /// Write the transcript in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "player_1 {0}", self.names.0)?;
+        writeln!(f, "player_2 {0}", self.names.1)?;
+        writeln!(f, "rounds {0}", self.game.rounds())?;
+        writeln!(f, "seed {0}", self.game.seed().unwrap_or_default())?;
+
+        if let Some(limits) = self.game.simulation() {
+            writeln!(f, "simulation {0} {1} {2}", limits.depth(), limits.calls(), limits.time().as_millis())?;
+        }
+
+        if let Some(cost) = self.game.penalty() {
+            writeln!(f, "penalty {cost}")?;
+        }
+
+        for line in self.game.schedule().to_string().lines() {
+            writeln!(f, "rule {line}")?;
+        }
+
+        writeln!(f, "scores {0} {1}", self.scores.0, self.scores.1)?;
+
+        let moves = self.moves.iter()
+            .map(|m| format!("{0}{1}", m.0.letter(), m.1.letter()))
+            .collect::<Vec<_>>();
+        writeln!(f, "moves {0}", moves.join(" "))
+    }
+}
+
+/// An error from parsing a [transcript](Transcript).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTranscriptError {
+    line: usize,
+    reason: String,
+}
+
+impl fmt::Display for ParseTranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{0}", self.reason),
+            line => write!(f, "Line {0}: {1}", line, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseTranscriptError {}
+
+impl FromStr for Transcript {
+    type Err = ParseTranscriptError;
+
+    fn from_str(s: &str) -> Result<Transcript, ParseTranscriptError> {
+        let mut names = (None, None);
+        let mut rounds = None;
+        let mut seed = None;
+        let mut simulation = None;
+        let mut penalty = None;
+        let mut rules = String::new();
+        let mut scores = None;
+        let mut moves = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseTranscriptError { line: i + 1, reason: String::from(reason) };
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+
+            match key {
+                "" => {}
+                "player_1" => names.0 = Some(String::from(value)),
+                "player_2" => names.1 = Some(String::from(value)),
+                "rounds" => rounds = Some(value.parse::<u32>().map_err(|_| error("Incorrect rounds"))?),
+                "seed" => seed = Some(value.parse::<u64>().map_err(|_| error("Incorrect seed"))?),
+                "simulation" => {
+                    let values = value.split_whitespace()
+                        .map(|v| v.parse::<u32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| error("Incorrect simulation limits"))?;
+
+                    match values.as_slice() {
+                        &[depth, calls, time] => simulation = Some(Limits::new(depth, calls, Duration::from_millis(time.into()))),
+                        _ => return Err(error("Expected 3 simulation limits")),
+                    }
+                }
+                "penalty" => {
+                    match value.parse::<u64>() {
+                        Ok(cost) if cost > 0 => penalty = Some(cost),
+                        _ => return Err(error("Incorrect penalty")),
+                    }
+                }
+                "rule" => {
+                    rules.push_str(value);
+                    rules.push('\n');
+                }
+                "scores" => {
+                    match value.split_once(' ').map(|(a, b)| (a.parse::<Score>(), b.parse::<Score>())) {
+                        Some((Ok(a), Ok(b))) => scores = Some((a, b)),
+                        _ => return Err(error("Incorrect scores")),
+                    }
+                }
+                "moves" => {
+                    let parsed = value.split_whitespace()
+                        .map(|m| {
+                            let mut letters = m.chars().map(Color::from_letter);
+                            match (letters.next(), letters.next(), letters.next()) {
+                                (Some(Some(a)), Some(Some(b)), None) => Some((a, b)),
+                                _ => None,
+                            }
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("Incorrect moves"))?;
+
+                    moves = Some(parsed);
+                }
+                _ => return Err(error("Unknown key")),
+            }
+        }
+
+        let missing = |key: &str| ParseTranscriptError { line: 0, reason: format!("Missing {key}") };
+        let rounds = rounds.ok_or_else(|| missing("rounds"))?;
+        let moves = moves.ok_or_else(|| missing("moves"))?;
+
+        if moves.len() != rounds as usize {
+            return Err(ParseTranscriptError { line: 0, reason: String::from("The amount of moves does not match the rounds") });
+        }
+
+        let schedule = rules.parse::<Schedule>()
+            .map_err(|error| ParseTranscriptError { line: 0, reason: format!("Incorrect rules ({error})") })?;
+
+        let mut game = Game::new(rounds)
+            .set_schedule(schedule)
+            .set_seed(seed.ok_or_else(|| missing("seed"))?);
+
+        if let Some(limits) = simulation {
+            game = game.set_simulation(limits);
+        }
+
+        if let Some(cost) = penalty {
+            game = game.set_penalty(cost);
+        }
+
+        Ok(Transcript {
+            names: (names.0.ok_or_else(|| missing("player_1"))?, names.1.ok_or_else(|| missing("player_2"))?),
+            game,
+            moves,
+            scores: scores.ok_or_else(|| missing("scores"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::all::*;
+    use crate::rules::{Change, Start};
+
+    #[test]
+    fn round_trip_test() {
+        let schedule = Schedule::default().add_change(Change::new(Start::Last(3), true).set_doubling(false));
+        let game = Game::new(10).set_schedule(schedule).set_simulation(Limits::default());
+        let p_1 = Player::with_name("Chat GPT versatile", chat_gpt_versatile);
+        let p_2 = Player::open_with_name("Fair", fair);
+
+        let transcript = game.record(&p_1, &p_2).unwrap();
+        let saved = transcript.to_string().parse::<Transcript>().unwrap();
+
+        assert_eq!(saved.to_string(), transcript.to_string());
+        assert_eq!(saved.names(), ("Chat GPT versatile", "Fair"));
+        assert_eq!(saved.moves().len(), 10);
+        assert_eq!(saved.game().simulation(), Some(Limits::default()));
+        assert_eq!(saved.game().play(&p_1, &p_2), Ok(saved.scores()));
+        assert_eq!(saved.verify(&p_1, &p_2), None);
+    }
+
+    #[test]
+    fn penalty_test() {
+        fn costly(_last_moves: &[Move], context: &mut crate::game::Context) -> Color {
+            context.charge(10);
+            Color::Green
+        }
+
+        let game = Game::new(4).set_penalty(5);
+        let (p_1, p_2) = (Player::open_with_name("Costly", costly), Player::with_name("Friendly", friendly));
+
+        let transcript = game.record(&p_1, &p_2).unwrap();
+        let saved = transcript.to_string().parse::<Transcript>().unwrap();
+
+        assert_eq!(saved.scores(), (8 - 8, 8));
+        assert_eq!(saved.game().penalty(), Some(5));
+        assert_eq!(saved.game().play(&p_1, &p_2), Ok(saved.scores()));
+
+        let error = "penalty 0".parse::<Transcript>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Incorrect penalty");
+    }
+
+    #[test]
+    fn divergence_test() {
+        let transcript = "player_1 a\nplayer_2 b\nrounds 3\nseed 0\nscores 6 6\nmoves GG GG GG".parse::<Transcript>().unwrap();
+
+        let divergence = transcript.verify(&Player::new(friendly), &Player::new(tit_for_two_tats_prisoner));
+        assert_eq!(divergence, None);
+
+        let divergence = transcript.verify(&Player::new(friendly), &Player::new(evil)).unwrap();
+        assert_eq!(divergence.round(), 1);
+        assert!(!divergence.player_1() && divergence.player_2());
+        assert_eq!(divergence.to_string(), "Round 1: player 2 played Red (recorded Green)");
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let error = "player_1 a\nrounds x".parse::<Transcript>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Incorrect rounds");
+
+        let error = "rounds 2\nmoves GG".parse::<Transcript>().unwrap_err();
+        assert_eq!(error.to_string(), "The amount of moves does not match the rounds");
+
+        let error = "rounds 1\nmoves GX".parse::<Transcript>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Incorrect moves");
+    }
+}
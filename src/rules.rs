@@ -0,0 +1,488 @@
+//! `rules` contains the scoring rules of the game and schedules for changing them mid-game.
+//!
+//! A [schedule](Schedule) may be read from a simple text format:
+//!
+//! ```text
+//! # Lines before the first change define the initial rules
+//! payoffs 1 3 1  0 2 1  -1 -1 0
+//! doubling on
+//!
+//! # A change from round 50 (counting from 1), announced to the programs
+//! round 50 announced
+//! payoffs 1 5 1  0 3 1  -1 -1 0
+//!
+//! # A hidden change for the final 10 rounds
+//! last 10 hidden
+//! doubling off
+//! ```
+//!
+//! The nine payoff values are those of the first player, for each of its colors (red, green, blue)
+//! against each of the opponent's colors (red, green, blue). The game is symmetric.
+
+use std::{fmt, str::FromStr};
+use crate::game::{Move, Score};
+
+/// The payoff matrix of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Payoffs {
+    matrix: [[i32; 3]; 3],
+}
+
+impl Payoffs {
+    /// Create a new payoff matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `matrix` - The payoffs of a player, indexed by its own color and the opponent's color
+    ///   in the order red, green, blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::rules::Payoffs;
+    ///
+    /// let payoffs = Payoffs::new([[1, 3, 1], [0, 2, 1], [-1, -1, 0]]);
+    ///
+    /// assert_eq!(payoffs, Payoffs::default());
+    /// assert_eq!(payoffs.score((Color::Red, Color::Green)), (3, 0));
+    /// ```
+    pub fn new(matrix: [[i32; 3]; 3]) -> Payoffs {
+        Payoffs {
+            matrix,
+        }
+    }
+
+    /// Get the scores of both players for a single [move](Move).
+    pub fn score(&self, m: Move) -> (i32, i32) {
+        (self.matrix[m.0.index()][m.1.index()], self.matrix[m.1.index()][m.0.index()])
+    }
+
+    /// Get the largest absolute payoff of the matrix.
+    pub fn max_abs(&self) -> u32 {
+        self.matrix.iter()
+            .flatten()
+            .map(|payoff| payoff.unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Default for Payoffs {
+    /// The payoff matrix described in the `README`.
+    fn default() -> Payoffs {
+        Payoffs::new([[1, 3, 1], [0, 2, 1], [-1, -1, 0]])
+    }
+}
+
+/// The rules in effect for a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    payoffs: Payoffs,
+    doubling: bool,
+}
+
+impl Rules {
+    /// Create new rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `payoffs` - The [payoff matrix](Payoffs)
+    /// * `doubling` - Whether blue options count towards doubling the score at the end of the game
+    pub fn new(payoffs: Payoffs, doubling: bool) -> Rules {
+        Rules {
+            payoffs,
+            doubling,
+        }
+    }
+
+    /// Get the payoff matrix.
+    pub fn payoffs(&self) -> &Payoffs {
+        &self.payoffs
+    }
+
+    /// Whether blue options count towards doubling the score at the end of the game.
+    pub fn doubling(&self) -> bool {
+        self.doubling
+    }
+
+    /// Calculate the scores of a slice of [moves](Move) as if these rules were in effect for all of them.
+    /// The doubling is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::{Color, calculate_scores};
+    /// use tourney::rules::Rules;
+    ///
+    /// let moves = [(Color::Green, Color::Green), (Color::Blue, Color::Red)];
+    ///
+    /// assert_eq!(Rules::default().calculate_scores(&moves), calculate_scores(&moves));
+    /// ```
+    pub fn calculate_scores(&self, last_moves: &[Move]) -> (Score, Score) {
+        last_moves.iter()
+            .fold((0, 0), |acc, m| {
+                let scores = self.payoffs.score(*m);
+                (acc.0 + Score::from(scores.0), acc.1 + Score::from(scores.1))
+            })
+    }
+}
+
+impl Default for Rules {
+    /// The rules described in the `README`.
+    fn default() -> Rules {
+        Rules::new(Payoffs::default(), true)
+    }
+}
+
+/// When a [change](Change) takes effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Start {
+    /// From the given round, counting from 1.
+    Round(u32),
+    /// For the given amount of final rounds.
+    Last(u32),
+}
+
+impl Start {
+    /// The index of the first round (counting from 0) that the change applies to, in a game of `rounds` rounds.
+    fn index(&self, rounds: u32) -> u32 {
+        match *self {
+            Start::Round(round) => round.saturating_sub(1),
+            Start::Last(last) => rounds.saturating_sub(last),
+        }
+    }
+}
+
+/// A scheduled change of the [rules](Rules).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    start: Start,
+    announced: bool,
+    payoffs: Option<Payoffs>,
+    doubling: Option<bool>,
+}
+
+impl Change {
+    /// Create a new change which does not change anything yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - When the change takes effect
+    /// * `announced` - Whether programs are told about the change through their [context](crate::game::Context)
+    pub fn new(start: Start, announced: bool) -> Change {
+        Change {
+            start,
+            announced,
+            payoffs: None,
+            doubling: None,
+        }
+    }
+
+    /// Set the payoff matrix that takes effect.
+    pub fn set_payoffs(mut self, payoffs: Payoffs) -> Change {
+        self.payoffs = Some(payoffs);
+        self
+    }
+
+    /// Set whether blue options count towards the doubling from when the change takes effect.
+    pub fn set_doubling(mut self, doubling: bool) -> Change {
+        self.doubling = Some(doubling);
+        self
+    }
+
+    /// Get when the change takes effect.
+    pub fn start(&self) -> Start {
+        self.start
+    }
+
+    /// Whether programs are told about the change.
+    pub fn announced(&self) -> bool {
+        self.announced
+    }
+
+    /// Apply the change to some rules.
+    fn apply(&self, rules: &mut Rules) {
+        if let Some(payoffs) = self.payoffs {
+            rules.payoffs = payoffs;
+        }
+
+        if let Some(doubling) = self.doubling {
+            rules.doubling = doubling;
+        }
+    }
+}
+
+/// The initial [rules](Rules) of a game and the [changes](Change) scheduled for it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    initial: Rules,
+    changes: Vec<Change>,
+}
+
+impl Schedule {
+    /// Create a new schedule without any changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - The [rules](Rules) at the start of the game
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::rules::*;
+    ///
+    /// // A new payoff matrix from round 50, and no doubling in the final 10 rounds
+    /// let schedule = Schedule::new(Rules::default())
+    ///     .add_change(Change::new(Start::Round(50), true).set_payoffs(Payoffs::new([[1, 5, 1], [0, 3, 1], [-1, -1, 0]])))
+    ///     .add_change(Change::new(Start::Last(10), false).set_doubling(false));
+    ///
+    /// assert_eq!(schedule, "round 50 announced\npayoffs 1 5 1 0 3 1 -1 -1 0\nlast 10 hidden\ndoubling off".parse().unwrap());
+    /// ```
+    pub fn new(initial: Rules) -> Schedule {
+        Schedule {
+            initial,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Add a change to the schedule. Changes taking effect in the same round are applied in the order they are added.
+    pub fn add_change(mut self, change: Change) -> Schedule {
+        self.changes.push(change);
+        self
+    }
+
+    /// Get the rules at the start of the game.
+    pub fn initial(&self) -> &Rules {
+        &self.initial
+    }
+
+    /// Get the scheduled changes.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Get the largest absolute score a player could reach in a game, doubling included.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounds` - The amount of rounds in the game
+    ///
+    /// # Returns
+    ///
+    /// The bound, or `None` if it does not fit in a [`Score`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::rules::*;
+    ///
+    /// assert_eq!(Schedule::default().max_score(100), Some(600));
+    /// assert_eq!(Schedule::new(Rules::new(Payoffs::new([[i32::MIN; 3]; 3]), true)).max_score(u32::MAX), None);
+    /// ```
+    pub fn max_score(&self, rounds: u32) -> Option<Score> {
+        let max_abs = self.changes.iter()
+            .filter_map(|change| change.payoffs)
+            .chain([self.initial.payoffs])
+            .map(|payoffs| payoffs.max_abs())
+            .max()
+            .unwrap_or(0);
+
+        Score::from(max_abs)
+            .checked_mul(rounds.into())?
+            .checked_mul(2)
+    }
+
+    /// Apply the changes taking effect in a round.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The index of the round, counting from 0
+    /// * `rounds` - The amount of rounds in the game
+    /// * `actual` - The rules in effect
+    /// * `known` - The rules as known to the programs, only affected by announced changes
+    pub(crate) fn apply(&self, round: u32, rounds: u32, actual: &mut Rules, known: &mut Rules) {
+        for change in self.changes.iter().filter(|change| change.start.index(rounds) == round) {
+            change.apply(actual);
+
+            if change.announced {
+                change.apply(known);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    /// Write the schedule in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let payoffs = |f: &mut fmt::Formatter<'_>, payoffs: &Payoffs| {
+            let values = payoffs.matrix.iter()
+                .flatten()
+                .map(|payoff| payoff.to_string())
+                .collect::<Vec<_>>();
+            writeln!(f, "payoffs {0}", values.join(" "))
+        };
+        let doubling = |f: &mut fmt::Formatter<'_>, doubling: bool| {
+            writeln!(f, "doubling {0}", if doubling { "on" } else { "off" })
+        };
+
+        payoffs(f, &self.initial.payoffs)?;
+        doubling(f, self.initial.doubling)?;
+
+        for change in &self.changes {
+            let visibility = if change.announced { "announced" } else { "hidden" };
+            match change.start {
+                Start::Round(round) => writeln!(f, "round {round} {visibility}")?,
+                Start::Last(last) => writeln!(f, "last {last} {visibility}")?,
+            }
+
+            if let Some(p) = &change.payoffs {
+                payoffs(f, p)?;
+            }
+
+            if let Some(d) = change.doubling {
+                doubling(f, d)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error from parsing a [schedule](Schedule).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseScheduleError {
+    line: usize,
+    reason: &'static str,
+}
+
+impl fmt::Display for ParseScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {0}: {1}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseScheduleError {}
+
+impl FromStr for Schedule {
+    type Err = ParseScheduleError;
+
+    fn from_str(s: &str) -> Result<Schedule, ParseScheduleError> {
+        let mut schedule = Schedule::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason| ParseScheduleError { line: i + 1, reason };
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                ["payoffs", values @ ..] => {
+                    if values.len() != 9 {
+                        return Err(error("Expected 9 payoff values"));
+                    }
+
+                    let mut matrix = [[0; 3]; 3];
+                    for (j, value) in values.iter().enumerate() {
+                        matrix[j / 3][j % 3] = value.parse().map_err(|_| error("Incorrect payoff value"))?;
+                    }
+
+                    match schedule.changes.last_mut() {
+                        Some(change) => change.payoffs = Some(Payoffs::new(matrix)),
+                        None => schedule.initial.payoffs = Payoffs::new(matrix),
+                    }
+                }
+                ["doubling", value] => {
+                    let doubling = match *value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(error("Expected on or off for doubling")),
+                    };
+
+                    match schedule.changes.last_mut() {
+                        Some(change) => change.doubling = Some(doubling),
+                        None => schedule.initial.doubling = doubling,
+                    }
+                }
+                [kind @ ("round" | "last"), value, visibility] => {
+                    let value = value.parse().map_err(|_| error("Incorrect round value"))?;
+                    if value == 0 {
+                        return Err(error("Round value must be greater than 0"));
+                    }
+
+                    let start = match *kind {
+                        "round" => Start::Round(value),
+                        _ => Start::Last(value),
+                    };
+
+                    let announced = match *visibility {
+                        "announced" => true,
+                        "hidden" => false,
+                        _ => return Err(error("Expected announced or hidden")),
+                    };
+
+                    schedule.changes.push(Change::new(start, announced));
+                }
+                _ => return Err(error("Unknown rule")),
+            }
+        }
+
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Color;
+
+    #[test]
+    fn payoffs_test() {
+        let payoffs = Payoffs::default();
+        assert_eq!(payoffs.score((Color::Blue, Color::Red)), (-1, 1));
+        assert_eq!(payoffs.score((Color::Green, Color::Blue)), (1, -1));
+        assert_eq!(payoffs.max_abs(), 3);
+    }
+
+    #[test]
+    fn apply_test() {
+        let schedule = Schedule::default()
+            .add_change(Change::new(Start::Round(3), false).set_doubling(false))
+            .add_change(Change::new(Start::Last(2), true).set_payoffs(Payoffs::new([[0; 3]; 3])));
+
+        let mut actual = Rules::default();
+        let mut known = Rules::default();
+        for round in 0..3 {
+            schedule.apply(round, 5, &mut actual, &mut known);
+        }
+
+        assert!(!actual.doubling());
+        assert!(known.doubling());
+        assert_eq!(actual.payoffs(), known.payoffs());
+
+        schedule.apply(3, 5, &mut actual, &mut known);
+        assert_eq!(known.payoffs(), &Payoffs::new([[0; 3]; 3]));
+        assert_eq!(actual.payoffs(), &Payoffs::new([[0; 3]; 3]));
+    }
+
+    #[test]
+    fn display_test() {
+        let schedule = Schedule::default()
+            .add_change(Change::new(Start::Round(3), false).set_doubling(false))
+            .add_change(Change::new(Start::Last(2), true).set_payoffs(Payoffs::new([[0; 3]; 3])));
+
+        assert_eq!(schedule.to_string().parse::<Schedule>(), Ok(schedule));
+        assert_eq!(Schedule::default().to_string(), "payoffs 1 3 1 0 2 1 -1 -1 0\ndoubling on\n");
+    }
+
+    #[test]
+    fn parse_test() {
+        let schedule = "doubling off # comment\nround 2 hidden\ndoubling on".parse::<Schedule>().unwrap();
+        assert!(!schedule.initial().doubling());
+        assert_eq!(schedule.changes(), [Change::new(Start::Round(2), false).set_doubling(true)]);
+
+        assert_eq!("payoffs 1 2 3".parse::<Schedule>().unwrap_err().to_string(), "Line 1: Expected 9 payoff values");
+        assert_eq!("\nlast 0 hidden".parse::<Schedule>().unwrap_err().to_string(), "Line 2: Round value must be greater than 0");
+        assert_eq!("round 5".parse::<Schedule>().unwrap_err().to_string(), "Line 1: Unknown rule");
+    }
+}
@@ -0,0 +1,100 @@
+//! `simulation` contains the limits and errors for open-source games, in which
+//! [context programs](crate::game::ContextProgram) may simulate their opponent.
+
+use std::{fmt, time::Duration};
+
+/// The default value for the maximum simulation depth
+pub const DEFAULT_DEPTH: u32 = 4;
+/// The default value for the amount of simulations a program may run per move
+pub const DEFAULT_CALLS: u32 = 100;
+/// The default value for the time a program may spend simulating per move
+pub const DEFAULT_TIME: Duration = Duration::from_millis(50);
+
+/// Limits on how much a program may simulate its opponent during a single move.
+///
+/// Simulations started by a simulated program (e.g. the opponent simulating the caller back)
+/// are charged against the limits of the program that started the first simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    depth: u32,
+    calls: u32,
+    time: Duration,
+}
+
+impl Limits {
+    /// Create new simulation limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The maximum depth of nested simulations
+    /// * `calls` - The maximum amount of simulations per move, nested simulations included
+    /// * `time` - The maximum time a program may spend simulating per move
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tourney::simulation::Limits;
+    ///
+    /// let limits = Limits::new(2, 10, Duration::from_millis(5));
+    ///
+    /// assert_eq!(limits.depth(), 2);
+    /// assert_eq!(limits.calls(), 10);
+    /// ```
+    pub fn new(depth: u32, calls: u32, time: Duration) -> Limits {
+        Limits {
+            depth,
+            calls,
+            time,
+        }
+    }
+
+    /// Get the maximum depth of nested simulations.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Get the maximum amount of simulations per move.
+    pub fn calls(&self) -> u32 {
+        self.calls
+    }
+
+    /// Get the maximum time spent simulating per move.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits::new(DEFAULT_DEPTH, DEFAULT_CALLS, DEFAULT_TIME)
+    }
+}
+
+/// The reasons a simulation of the opponent may be refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationError {
+    /// The game is not played in open-source mode.
+    Disabled,
+    /// The simulation would be nested deeper than allowed.
+    DepthExceeded,
+    /// The amount of simulations for this move has been used up.
+    BudgetExhausted,
+    /// The time for simulating during this move has run out.
+    OutOfTime,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            SimulationError::Disabled => "Simulation is disabled",
+            SimulationError::DepthExceeded => "Simulation depth exceeded",
+            SimulationError::BudgetExhausted => "Simulation budget exhausted",
+            SimulationError::OutOfTime => "Simulation time exceeded",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for SimulationError {}
@@ -0,0 +1,553 @@
+//! `solver` computes the best response to a deterministic [player](Player): the sequence of colors that maximises
+//! the own final score against it over a fixed amount of rounds, with the doubling taken into account.
+//!
+//! A program that only reads its last `k` moves is a finite-state machine whose states are those moves, so the best
+//! response is found exactly by dynamic programming over the states the program can reach. Any other program is
+//! solved by a branch-and-bound search over whole histories, which starts from the plan for a program with a memory of
+//! [`ASSUMED_MEMORY`] moves and is only proven optimal if it finishes within its limit.
+//!
+//! The opponent is run as in a game without simulation, i.e. an open-source program cannot simulate the solution.
+//!
+//! A solution is [compiled](Solution::compile) into a [finite-state machine](FsmStrategy) that plays it while the
+//! opponent plays as predicted, and switches to a fallback when it does not.
+
+use std::collections::HashMap;
+use crate::game::{Color, Move, Player, Score};
+use crate::rules::{Rules, Schedule};
+use crate::strategies::fsm::{FsmStrategy, State};
+
+/// The default amount of moves the branch-and-bound search may simulate
+pub const DEFAULT_LIMIT: u64 = 10_000;
+/// The memory assumed for the first plan against a program without a known memory
+pub const ASSUMED_MEMORY: usize = 2;
+/// The largest memory for which the best response is found by dynamic programming
+pub const MAX_MEMORY: usize = 8;
+
+/// The best response found against a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    moves: Vec<Move>,
+    scores: (Score, Score),
+    optimal: bool,
+}
+
+impl Solution {
+    /// Get the [moves](Move) of the game, with the own color first.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Get the own colors of the game.
+    pub fn colors(&self) -> Vec<Color> {
+        self.moves.iter().map(|m| m.0).collect()
+    }
+
+    /// Get the final scores, the own score first and the doubling included.
+    pub fn scores(&self) -> (Score, Score) {
+        self.scores
+    }
+
+    /// Whether the own score is proven to be the highest possible against the program.
+    pub fn optimal(&self) -> bool {
+        self.optimal
+    }
+
+    /// Compile the solution into a [finite-state machine](FsmStrategy) with a state for every round.
+    ///
+    /// The machine plays the own colors of the solution for as long as the opponent plays its predicted colors. When
+    /// the opponent deviates, or the game lasts longer than the solution, the machine continues in the fallback as if
+    /// the fallback had been played from the start and the last color of the opponent was its first.
+    ///
+    /// # Arguments
+    ///
+    /// * `fallback` - The machine played after a deviation, e.g. [tit for tat](tit_for_tat)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::{Color, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::rules::Schedule;
+    /// use tourney::solver::{self, Solver};
+    ///
+    /// let solution = Solver::new(Schedule::default()).set_memory(Some(0)).solve(&Player::new(evil), 3);
+    /// let machine = solution.compile(&solver::tit_for_tat());
+    ///
+    /// assert_eq!(machine.states().len(), 3 + 2);
+    /// assert_eq!(machine.color(&solution.moves()[..2]), solution.moves()[2].0);
+    ///
+    /// // After a deviation, tit for tat answers the green option with green
+    /// assert_eq!(machine.color(&[(Color::Red, Color::Green)]), Color::Green);
+    /// ```
+    pub fn compile(&self, fallback: &FsmStrategy) -> FsmStrategy {
+        let rounds = self.moves.len();
+        let mut states = Vec::with_capacity(rounds + fallback.states().len());
+
+        for (round, m) in self.moves.iter().enumerate() {
+            let next = Color::ALL.map(|other| {
+                if other == m.1 && round + 1 < rounds {
+                    round + 1
+                } else {
+                    rounds + fallback.states()[0].next(other)
+                }
+            });
+
+            states.push(State::new(&format!("round_{0}", round + 1), m.0, next));
+        }
+
+        for state in fallback.states() {
+            let next = Color::ALL.map(|other| rounds + state.next(other));
+            states.push(State::new(&format!("fallback_{0}", state.name()), state.color(), next));
+        }
+
+        FsmStrategy::new(states).expect("Every next state exists")
+    }
+}
+
+/// Get tit for tat as a [finite-state machine](FsmStrategy), the default fallback of a
+/// [compiled](Solution::compile) solution.
+pub fn tit_for_tat() -> FsmStrategy {
+    FsmStrategy::new(vec![
+        State::new("nice", Color::Green, [1, 0, 0]),
+        State::new("mean", Color::Red, [1, 0, 0]),
+    ]).expect("Every next state exists")
+}
+
+/// A solver of the best response to a deterministic program.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    schedule: Schedule,
+    memory: Option<usize>,
+    limit: u64,
+}
+
+impl Solver {
+    /// Create a new solver for a program without a known memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - The [rules](Schedule) of the game
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::{Color, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::rules::Schedule;
+    /// use tourney::solver::Solver;
+    ///
+    /// let solution = Solver::new(Schedule::default()).set_memory(Some(1)).solve(&Player::new(tit_for_tat_prisoner), 10);
+    ///
+    /// // Defect, take the retaliation with the blue option that doubles the score, then cooperate until the last round
+    /// assert_eq!(solution.scores(), (38, 15));
+    /// assert_eq!(solution.colors()[..3], [Color::Red, Color::Blue, Color::Green]);
+    /// assert_eq!(solution.colors().last(), Some(&Color::Red));
+    /// assert!(solution.optimal());
+    /// ```
+    pub fn new(schedule: Schedule) -> Solver {
+        Solver {
+            schedule,
+            memory: None,
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    /// Set the amount of last moves the program reads, e.g. from its [registry entry](crate::registry::Entry).
+    ///
+    /// # Panics
+    ///
+    /// If `memory` is larger than [`MAX_MEMORY`].
+    pub fn set_memory(mut self, memory: Option<usize>) -> Solver {
+        assert!(memory.is_none_or(|memory| memory <= MAX_MEMORY), "Memory larger than {MAX_MEMORY} moves");
+        self.memory = memory;
+        self
+    }
+
+    /// Set the amount of moves the branch-and-bound search may simulate.
+    pub fn set_limit(mut self, limit: u64) -> Solver {
+        self.limit = limit;
+        self
+    }
+
+    /// Get the rules of the game.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get the amount of last moves the program reads, if it is known.
+    pub fn memory(&self) -> Option<usize> {
+        self.memory
+    }
+
+    /// Get the amount of moves the branch-and-bound search may simulate.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Find the best response to a deterministic program.
+    ///
+    /// # Arguments
+    ///
+    /// * `opponent` - The program, which must be deterministic and, if a memory is set, only read that many moves
+    /// * `rounds` - The amount of rounds of the game
+    ///
+    /// # Returns
+    ///
+    /// The best [solution](Solution) found. If the program reads more moves than its memory, the solution is checked
+    /// against the whole histories and improved by the search.
+    pub fn solve(&self, opponent: &Player, rounds: u32) -> Solution {
+        let mut model = Model::new(opponent, &self.schedule, rounds);
+
+        let (predicted, colors) = model.plan(self.memory.unwrap_or(ASSUMED_MEMORY));
+        let (moves, scores) = model.replay(&colors);
+        if self.memory.is_some() && scores.0 == predicted {
+            return Solution {
+                moves,
+                scores,
+                optimal: true,
+            };
+        }
+
+        let mut search = Search {
+            plan: &colors,
+            best: (scores.0, colors.clone()),
+            history: Vec::with_capacity(rounds as usize),
+            swapped: Vec::with_capacity(rounds as usize),
+            calls: 0,
+            limit: self.limit,
+        };
+        let optimal = search.search(&mut model, 0, 0);
+
+        let (moves, scores) = model.replay(&search.best.1);
+        Solution {
+            moves,
+            scores,
+            optimal,
+        }
+    }
+}
+
+/// The values of the states of the dynamic programming, by round, last moves and difference in blue options.
+type Values = HashMap<(usize, Vec<Move>, i64), [Option<Score>; 2]>;
+
+/// The opponent and the rules of every round.
+struct Model<'a> {
+    opponent: &'a Player,
+    actual: Vec<Rules>,
+    known: Vec<usize>,
+    rules: Vec<Rules>,
+    /// The amount of rounds from every round on in which blue options count towards the doubling.
+    doubling: Vec<i64>,
+    /// The highest own payoff possible from every round on.
+    bound: Vec<Score>,
+    responses: HashMap<(Vec<Move>, usize), Color>,
+}
+
+impl<'a> Model<'a> {
+    fn new(opponent: &'a Player, schedule: &Schedule, rounds: u32) -> Model<'a> {
+        let (mut actual, mut known) = (*schedule.initial(), *schedule.initial());
+        let mut model = Model {
+            opponent,
+            actual: Vec::with_capacity(rounds as usize),
+            known: Vec::with_capacity(rounds as usize),
+            rules: Vec::new(),
+            doubling: vec![0; rounds as usize + 1],
+            bound: vec![0; rounds as usize + 1],
+            responses: HashMap::new(),
+        };
+
+        for round in 0..rounds {
+            schedule.apply(round, rounds, &mut actual, &mut known);
+            if model.rules.last() != Some(&known) {
+                model.rules.push(known);
+            }
+
+            model.actual.push(actual);
+            model.known.push(model.rules.len() - 1);
+        }
+
+        for (round, rules) in model.actual.iter().enumerate().rev() {
+            let best = Color::ALL.iter()
+                .flat_map(|own| Color::ALL.map(|other| rules.payoffs().score((*own, other)).0))
+                .max()
+                .unwrap_or(0);
+
+            model.doubling[round] = model.doubling[round + 1] + i64::from(rules.doubling());
+            model.bound[round] = model.bound[round + 1] + Score::from(best);
+        }
+
+        model
+    }
+
+    fn rounds(&self) -> usize {
+        self.actual.len()
+    }
+
+    /// The color of the opponent in a round after some moves, with the own color first.
+    fn respond(&mut self, round: usize, last_moves: &[Move]) -> Color {
+        let key = (last_moves.to_vec(), self.known[round]);
+        if let Some(color) = self.responses.get(&key) {
+            return *color;
+        }
+
+        let swapped = last_moves.iter().map(|m| (m.1, m.0)).collect::<Vec<_>>();
+        let color = self.opponent.simulate(&swapped, &self.rules[key.1], 0);
+        self.responses.insert(key, color);
+        color
+    }
+
+    /// The own payoff of a move and its effect on the difference in blue options.
+    fn step(&self, round: usize, m: Move) -> (Score, i64) {
+        let rules = &self.actual[round];
+        let blues = if rules.doubling() {
+            i64::from(m.0 == Color::Blue) - i64::from(m.1 == Color::Blue)
+        } else {
+            0
+        };
+
+        (Score::from(rules.payoffs().score(m).0), blues)
+    }
+
+    /// Play colors against the opponent on whole histories.
+    ///
+    /// # Returns
+    ///
+    /// The moves and the final scores.
+    fn replay(&mut self, colors: &[Color]) -> (Vec<Move>, (Score, Score)) {
+        let mut moves: Vec<Move> = Vec::with_capacity(colors.len());
+        for (round, color) in colors.iter().enumerate() {
+            let swapped = moves.iter().map(|m| (m.1, m.0)).collect::<Vec<_>>();
+            let other = self.opponent.simulate(&swapped, &self.rules[self.known[round]], 0);
+            moves.push((*color, other));
+        }
+
+        let (mut scores, mut blues) = ((0, 0), 0);
+        for (round, m) in moves.iter().enumerate() {
+            let payoffs = self.actual[round].payoffs().score(*m);
+            scores = (scores.0 + Score::from(payoffs.0), scores.1 + Score::from(payoffs.1));
+            blues += self.step(round, *m).1;
+        }
+
+        let scores = match blues {
+            0 => scores,
+            blues if blues > 0 => (scores.0 * 2, scores.1),
+            _ => (scores.0, scores.1 * 2),
+        };
+
+        (moves, scores)
+    }
+
+    /// Find the best colors against a program that reads its last `memory` moves.
+    ///
+    /// # Returns
+    ///
+    /// The predicted own final score and the colors.
+    fn plan(&mut self, memory: usize) -> (Score, Vec<Color>) {
+        let mut values = HashMap::new();
+        let root = self.value(memory, &mut values, 0, &[], 0);
+        let doubled = root[1].is_some_and(|doubled| root[0].is_none_or(|single| 2 * doubled > single));
+        let class = usize::from(doubled);
+
+        let mut colors = Vec::with_capacity(self.rounds());
+        let (mut window, mut blues) = (Vec::new(), 0);
+        for round in 0..self.rounds() {
+            let target = self.value(memory, &mut values, round, &window, blues)[class];
+            let other = self.respond(round, &window);
+
+            for own in Color::ALL {
+                let (payoff, change) = self.step(round, (own, other));
+                let next = self.advance(memory, &window, (own, other));
+                let value = self.value(memory, &mut values, round + 1, &next, blues + change)[class];
+
+                if value.map(|value| value + payoff) == target {
+                    colors.push(own);
+                    (window, blues) = (next, blues + change);
+                    break;
+                }
+            }
+        }
+
+        let score = root[class].unwrap_or(0);
+        (if doubled { 2 * score } else { score }, colors)
+    }
+
+    fn advance(&self, memory: usize, window: &[Move], m: Move) -> Vec<Move> {
+        let skip = (window.len() + 1).saturating_sub(memory);
+        window.iter().copied().chain([m]).skip(skip).collect()
+    }
+
+    /// The highest own payoff from a round on, for a game that does not and does end with a doubled own score.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The last moves read by the program
+    /// * `blues` - The difference in blue options counting towards the doubling
+    fn value(
+        &mut self,
+        memory: usize,
+        values: &mut Values,
+        round: usize,
+        window: &[Move],
+        blues: i64,
+    ) -> [Option<Score>; 2] {
+        // Beyond the blue options left, the difference no longer changes which score is doubled
+        let left = self.doubling[round];
+        let blues = blues.clamp(-left, left + 1);
+        if round == self.rounds() {
+            return if blues > 0 { [None, Some(0)] } else { [Some(0), None] };
+        }
+
+        let key = (round, window.to_vec(), blues);
+        if let Some(value) = values.get(&key) {
+            return *value;
+        }
+
+        let other = self.respond(round, window);
+        let mut best = [None, None];
+        for own in Color::ALL {
+            let (payoff, change) = self.step(round, (own, other));
+            let next = self.advance(memory, window, (own, other));
+            let value = self.value(memory, values, round + 1, &next, blues + change);
+
+            for (best, value) in best.iter_mut().zip(value) {
+                if let Some(value) = value.map(|value| value + payoff) {
+                    *best = Some(best.map_or(value, |best: Score| best.max(value)));
+                }
+            }
+        }
+
+        values.insert(key, best);
+        best
+    }
+}
+
+/// A depth-first branch-and-bound search over whole histories.
+struct Search<'a> {
+    plan: &'a [Color],
+    best: (Score, Vec<Color>),
+    history: Vec<Move>,
+    swapped: Vec<Move>,
+    calls: u64,
+    limit: u64,
+}
+
+impl Search<'_> {
+    /// Search every continuation of the history.
+    ///
+    /// # Returns
+    ///
+    /// Whether the search finished within its limit.
+    fn search(&mut self, model: &mut Model, score: Score, blues: i64) -> bool {
+        let round = self.history.len();
+        if round == model.rounds() {
+            let score = if blues > 0 { 2 * score } else { score };
+            if score > self.best.0 {
+                self.best = (score, self.history.iter().map(|m| m.0).collect());
+            }
+
+            return true;
+        }
+
+        let bound = score + model.bound[round];
+        let bound = if blues + model.doubling[round] > 0 { bound.max(2 * bound) } else { bound };
+        if bound <= self.best.0 {
+            return true;
+        }
+
+        if self.calls >= self.limit {
+            return false;
+        }
+        self.calls += 1;
+
+        let other = model.opponent.simulate(&self.swapped, &model.rules[model.known[round]], 0);
+
+        // Follow the plan first, then the colors with the highest payoff
+        let following = self.history.iter().zip(self.plan).all(|(m, color)| m.0 == *color);
+        let mut colors = Color::ALL;
+        colors.sort_by_key(|own| std::cmp::Reverse(model.step(round, (*own, other)).0));
+        if following {
+            colors.sort_by_key(|own| *own != self.plan[round]);
+        }
+
+        for own in colors {
+            let (payoff, change) = model.step(round, (own, other));
+            self.history.push((own, other));
+            self.swapped.push((other, own));
+            let finished = self.search(model, score + payoff, blues + change);
+            self.history.pop();
+            self.swapped.pop();
+
+            if !finished {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Context, Game, Strategy};
+    use crate::programs::all::*;
+    use crate::rules::{Change, Start};
+
+    struct Sequence(Vec<Color>);
+
+    impl Strategy for Sequence {
+        fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+            self.0[last_moves.len()]
+        }
+    }
+
+    #[test]
+    fn memory_test() {
+        let solver = Solver::new(Schedule::default()).set_memory(Some(0));
+
+        // A single blue option doubles the score against a program that never plays blue
+        let solution = solver.solve(&Player::new(friendly), 10);
+        assert_eq!(solution.scores(), (2 * (9 * 3 - 1), 1));
+        assert_eq!(solution.colors().iter().filter(|color| **color == Color::Blue).count(), 1);
+        assert!(solution.optimal());
+
+        let solution = solver.solve(&Player::new(evil), 10);
+        assert_eq!(solution.scores().0, 2 * (9 - 1));
+
+        // Without the doubling blue options are never worth it
+        let schedule = Schedule::default().add_change(Change::new(Start::Round(0), false).set_doubling(false));
+        let solution = Solver::new(schedule).set_memory(Some(0)).solve(&Player::new(friendly), 10);
+        assert_eq!(solution.scores().0, 30);
+    }
+
+    #[test]
+    fn search_test() {
+        // Without a memory the plan is checked and improved by the search
+        let player = Player::new(tit_for_tat_prisoner);
+        let exact = Solver::new(Schedule::default()).set_memory(Some(1)).solve(&player, 8);
+        let searched = Solver::new(Schedule::default()).solve(&player, 8);
+        assert_eq!(exact.scores().0, searched.scores().0);
+        assert!(searched.optimal());
+
+        let stopped = Solver::new(Schedule::default()).set_limit(0).solve(&player, 8);
+        assert!(!stopped.optimal());
+        assert!(stopped.scores().0 <= exact.scores().0);
+
+        // The solution is the outcome of a real game
+        let sequence = Player::from_strategy(Sequence(exact.colors()));
+        assert_eq!(Game::new(8).play(&sequence, &player), Ok(exact.scores()));
+    }
+
+    #[test]
+    fn compile_test() {
+        let player = Player::new(tit_for_tat_prisoner);
+        let solution = Solver::new(Schedule::default()).set_memory(Some(1)).solve(&player, 20);
+        let machine = Player::from_strategy(solution.compile(&tit_for_tat()));
+
+        assert_eq!(Game::new(20).play(&machine, &player), Ok(solution.scores()));
+
+        // Against another opponent the machine falls back to tit for tat, and keeps it after the planned rounds
+        let transcript = Game::new(30).record(&machine, &Player::new(evil)).unwrap();
+        assert!(transcript.moves()[1..].iter().all(|m| m.0 == Color::Red));
+    }
+}
@@ -0,0 +1,370 @@
+//! `strategies` contains parameterized [strategies](crate::game::Strategy), which may be generated,
+//! saved and loaded instead of being written as a program.
+
+use std::{env, fmt, fs, path::Path, sync::Arc};
+use rand::Rng;
+use crate::game::{Color, Player, rng};
+use crate::registry::Entry;
+
+pub mod memory_one;
+pub mod fsm;
+pub mod lookup;
+pub mod zd;
+pub mod combinators;
+pub mod dsl;
+pub mod process;
+pub mod wasm;
+pub mod plugin;
+pub mod script;
+pub mod qlearning;
+pub mod mlp;
+pub mod genetic;
+pub mod bayesian;
+pub mod mcts;
+
+pub use memory_one::MemoryOne;
+pub use fsm::FsmStrategy;
+pub use lookup::LookupTable;
+pub use dsl::DslStrategy;
+pub use process::ProcessStrategy;
+pub use wasm::WasmStrategy;
+pub use script::ScriptStrategy;
+pub use qlearning::QTable;
+pub use mlp::Mlp;
+pub use bayesian::BayesianStrategy;
+pub use mcts::MctsStrategy;
+
+/// The extensions of the strategy files that can be [loaded](load).
+const EXTENSIONS: [&str; 9] = ["mem", "fsm", "lut", "dsl", "proc", "wasm", "rhai", "qt", "mlp"];
+
+/// Load a strategy from a file, registered by the name of the file.
+///
+/// The kind of strategy follows from the extension of the file:
+///
+/// * `.mem` - A [memory-one strategy](MemoryOne)
+/// * `.fsm` - A [finite-state machine](FsmStrategy)
+/// * `.lut` - A [lookup table](LookupTable)
+/// * `.dsl` - A [script](DslStrategy) of rules
+/// * `.proc` - A [child process](ProcessStrategy), run from the directory of the file
+/// * `.wasm` - A [WebAssembly module](WasmStrategy)
+/// * `.rhai` - A [Rhai script](ScriptStrategy), which is [reloaded](ScriptStrategy::reload) when a player is
+///   constructed if the file was modified
+/// * `.qt` - A [Q-learning table](QTable), played frozen
+/// * `.mlp` - A [neural network](Mlp)
+///
+/// A comment on the first line of the file, starting with `#` or `//`, is used as the description.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+///
+/// # Errors
+///
+/// If the file cannot be read or parsed, or if the extension is unknown.
+///
+/// # Examples
+///
+/// ```
+/// # use std::fs;
+/// use tourney::strategies::load;
+///
+/// let path = std::env::temp_dir().join("grim_trigger.fsm");
+/// fs::write(&path, "# Red forever after red\nstate nice G  mean nice nice\nstate mean R  mean mean mean\n").unwrap();
+///
+/// let entry = load(&path).unwrap();
+///
+/// assert_eq!(entry.id(), "grim_trigger");
+/// assert_eq!(entry.description(), "Red forever after red");
+/// # fs::remove_file(&path).unwrap();
+/// ```
+pub fn load(path: &Path) -> Result<Entry, String> {
+    let id = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    let bytes = fs::read(path).map_err(|error| format!("Could not read {0} ({1})", path.display(), error))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let invalid = |error: ParseStrategyError| format!("Invalid strategy in {0} ({1})", path.display(), error);
+
+    let entry = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("mem") => {
+            let strategy = text.parse::<MemoryOne>().map_err(invalid)?;
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy)))
+                .set_deterministic(strategy.is_pure())
+                .set_memory(Some(1))
+        }
+        Some("fsm") => {
+            let strategy = text.parse::<FsmStrategy>().map_err(invalid)?;
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy.clone())))
+                .set_deterministic(true)
+        }
+        Some("lut") => {
+            let strategy = text.parse::<LookupTable>().map_err(invalid)?;
+            let memory = strategy.own().max(strategy.opponent());
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy.clone())))
+                .set_deterministic(true)
+                .set_memory(Some(memory))
+        }
+        Some("dsl") => {
+            let strategy = text.parse::<DslStrategy>().map_err(invalid)?;
+            let deterministic = strategy.is_deterministic();
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy.clone())))
+                .set_deterministic(deterministic)
+        }
+        Some("proc") => {
+            let mut strategy = text.parse::<ProcessStrategy>().map_err(invalid)?;
+            if let Some(dir) = path.parent() {
+                strategy = strategy.set_dir(dir);
+            }
+
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy.clone())))
+        }
+        Some("wasm") => {
+            let strategy = WasmStrategy::new(&bytes).map_err(|error| format!("Invalid strategy in {0} ({1})", path.display(), error))?;
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(strategy.clone())))
+        }
+        Some("qt") => {
+            let table = text.parse::<QTable>().map_err(invalid)?;
+            let memory = table.memory();
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(table.clone())))
+                .set_deterministic(true)
+                .set_memory(Some(memory))
+        }
+        Some("mlp") => {
+            let mlp = text.parse::<Mlp>().map_err(invalid)?;
+            Entry::new(id, id, Arc::new(move || Player::from_strategy(mlp.clone())))
+                .set_deterministic(true)
+        }
+        Some("rhai") => {
+            let strategy = ScriptStrategy::new(&text).map_err(|error| format!("Invalid strategy in {0} ({1})", path.display(), error))?
+                .set_path(path);
+
+            // A script that no longer compiles keeps being played as it was
+            Entry::new(id, id, Arc::new(move || {
+                let _ = strategy.reload();
+                Player::from_strategy(strategy.clone())
+            }))
+        }
+        _ => return Err(format!("Unknown kind of strategy: {0}", path.display())),
+    };
+
+    let description = text.lines()
+        .next()
+        .and_then(|line| line.trim().strip_prefix('#').or_else(|| line.trim().strip_prefix("//")))
+        .unwrap_or_default();
+
+    Ok(entry.set_description(description.trim()))
+}
+
+/// Load every strategy in a directory (see [`load`]), ordered by file name, together with every strategy of the
+/// [plugins](plugin) in it, dynamic libraries such as `.so` files on Linux. Files with another extension, e.g. the
+/// programs run by a `.proc` strategy, are skipped.
+///
+/// # Arguments
+///
+/// * `dir` - The path of the directory
+///
+/// # Errors
+///
+/// If the directory cannot be read, or if a strategy or plugin cannot be loaded.
+pub fn load_dir(dir: &Path) -> Result<Vec<Entry>, String> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(|error| format!("Could not read {0} ({1})", dir.display(), error))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| {
+            EXTENSIONS.contains(&extension) || extension == env::consts::DLL_EXTENSION
+        }))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut entries = Vec::new();
+    for path in paths {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension == env::consts::DLL_EXTENSION => entries.extend(plugin::load(&path)?),
+            _ => entries.push(load(&path)?),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// A probability distribution over the [colors](Color).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mix([f64; 3]);
+
+impl Mix {
+    /// Create a new distribution from weights, which are normalized to probabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `red` - The weight of red
+    /// * `green` - The weight of green
+    /// * `blue` - The weight of blue
+    ///
+    /// # Returns
+    ///
+    /// The distribution, or `None` if a weight is negative or not finite, or if every weight is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::strategies::Mix;
+    ///
+    /// let mix = Mix::new(1.0, 3.0, 0.0).unwrap();
+    ///
+    /// assert_eq!(mix.probability(Color::Green), 0.75);
+    /// assert!(Mix::new(0.0, 0.0, 0.0).is_none());
+    /// ```
+    pub fn new(red: f64, green: f64, blue: f64) -> Option<Mix> {
+        let weights = [red, green, blue];
+        if weights.iter().any(|weight| !weight.is_finite() || *weight < 0.0) {
+            return None;
+        }
+
+        let total = red + green + blue;
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+
+        // Probabilities are kept as they are, which lets a mix be written and parsed without rounding
+        if (total - 1.0).abs() < 1e-9 {
+            return Some(Mix(weights));
+        }
+
+        Some(Mix(weights.map(|weight| weight / total)))
+    }
+
+    /// Create a distribution that always picks the same color.
+    pub fn pure(color: Color) -> Mix {
+        let mut probabilities = [0.0; 3];
+        probabilities[color.index()] = 1.0;
+        Mix(probabilities)
+    }
+
+    /// Create a distribution that picks every color with the same probability.
+    pub fn uniform() -> Mix {
+        Mix([1.0 / 3.0; 3])
+    }
+
+    /// Create a random distribution, uniformly distributed over all distributions.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Mix {
+        // Normalized exponential weights are uniformly distributed over the simplex
+        let weights = [(); 3].map(|_| -(1.0 - rng.gen::<f64>()).ln());
+        Mix::new(weights[0], weights[1], weights[2]).unwrap_or_else(Mix::uniform)
+    }
+
+    /// Get the probability of a color.
+    pub fn probability(&self, color: Color) -> f64 {
+        self.0[color.index()]
+    }
+
+    /// Get the color that is always picked, `None` if the distribution is not pure.
+    pub fn as_pure(&self) -> Option<Color> {
+        Color::ALL.into_iter().find(|color| self.probability(*color) == 1.0)
+    }
+
+    /// Pick a color, drawing from the [game's random number generator](rng) unless the distribution is pure.
+    pub fn sample(&self) -> Color {
+        if let Some(color) = self.as_pure() {
+            return color;
+        }
+
+        let mut value = rng().gen::<f64>();
+        for color in Color::ALL {
+            value -= self.probability(color);
+            if value < 0.0 {
+                return color;
+            }
+        }
+
+        // Rounding may leave a small remainder, which belongs to the last possible color
+        Color::ALL.into_iter().rev().find(|color| self.probability(*color) > 0.0).unwrap_or(Color::Blue)
+    }
+}
+
+impl fmt::Display for Mix {
+    /// Write the letter of the color if the distribution is pure, otherwise the probabilities.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_pure() {
+            Some(color) => write!(f, "{0}", color.letter()),
+            None => write!(f, "{0} {1} {2}", self.0[0], self.0[1], self.0[2]),
+        }
+    }
+}
+
+impl std::str::FromStr for Mix {
+    type Err = String;
+
+    /// Parse either the letter of a color (`R`, `G` or `B`) or the weights of red, green and blue.
+    fn from_str(s: &str) -> Result<Mix, String> {
+        let values = s.split_whitespace().collect::<Vec<_>>();
+        match values[..] {
+            [letter] => {
+                let mut chars = letter.chars();
+                match (chars.next().and_then(Color::from_letter), chars.next()) {
+                    (Some(color), None) => Ok(Mix::pure(color)),
+                    _ => Err(format!("Invalid color: {letter}")),
+                }
+            }
+            [red, green, blue] => {
+                let weight = |value: &str| value.parse::<f64>().map_err(|_| format!("Invalid weight: {value}"));
+                Mix::new(weight(red)?, weight(green)?, weight(blue)?)
+                    .ok_or_else(|| String::from("Weights must be non-negative and not all 0"))
+            }
+            _ => Err(String::from("Expected a color or 3 weights")),
+        }
+    }
+}
+
+/// An error from parsing a strategy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStrategyError {
+    line: usize,
+    reason: String,
+}
+
+impl ParseStrategyError {
+    /// Create a new error.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line of the error, counting from 1, or 0 if it concerns the whole text
+    /// * `reason` - The reason for the error
+    pub(crate) fn new(line: usize, reason: &str) -> ParseStrategyError {
+        ParseStrategyError {
+            line,
+            reason: String::from(reason),
+        }
+    }
+}
+
+impl fmt::Display for ParseStrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{0}", self.reason),
+            line => write!(f, "Line {0}: {1}", line, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseStrategyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_test() {
+        assert_eq!("G".parse::<Mix>(), Ok(Mix::pure(Color::Green)));
+        assert_eq!("0 2 2".parse::<Mix>().map(|mix| mix.probability(Color::Blue)), Ok(0.5));
+        assert_eq!("X".parse::<Mix>(), Err(String::from("Invalid color: X")));
+        assert_eq!("1 -1 0".parse::<Mix>(), Err(String::from("Weights must be non-negative and not all 0")));
+
+        let mix = Mix::random(&mut rand::thread_rng());
+        assert_eq!(mix.to_string().parse::<Mix>(), Ok(mix));
+        assert_eq!(Mix::pure(Color::Red).sample(), Color::Red);
+    }
+}
@@ -0,0 +1,371 @@
+//! `bayesian` contains a strategy that models its opponent as one of a set of known programs, e.g. the
+//! [registered programs](crate::programs::registry).
+//!
+//! The [strategy](BayesianStrategy) keeps a posterior over which program it is facing, starting from a uniform
+//! prior. Every move of the opponent updates it by how likely each program was to play that color, found by
+//! [simulating](Player::simulate) the program on the moves so far: a deterministic program plays one color, and
+//! the distribution of a randomised program is estimated from a few seeded samples. Since the opponent may be none
+//! of the programs, or may make mistakes, every color keeps a small probability (the noise).
+//!
+//! The strategy then best-responds to the [most likely program or to the mixture](Response) of the programs by
+//! their posterior. The value of a color is the best payoff over a few rounds of playing it followed by a simple
+//! continuation (always the same color, or copying the opponent), against the simulated program. The doubling of
+//! the score for the most blue options is not taken into account.
+//!
+//! ```
+//! use tourney::game::Color;
+//! use tourney::programs::all::*;
+//! use tourney::registry::Entry;
+//! use tourney::rules::Rules;
+//! use tourney::strategies::BayesianStrategy;
+//!
+//! let strategy = BayesianStrategy::new(&[
+//!     Entry::program("friendly", "Friendly", friendly).set_deterministic(true),
+//!     Entry::program("evil", "Evil", evil).set_deterministic(true),
+//! ]);
+//!
+//! assert_eq!(strategy.most_likely(&[(Color::Green, Color::Red)], &Rules::default()), Some("evil"));
+//! ```
+
+use std::{fmt, sync::{Arc, Mutex}};
+use crate::game::{Color, Move, Context, Player, Strategy};
+use crate::registry::Entry;
+use crate::rules::Rules;
+
+/// How many posteriors are cached, enough for the games played at the same time.
+const CACHE_SIZE: usize = 64;
+
+/// The continuations of a color in the value of a move: always the same color, or copying the opponent's last
+/// color (`None`).
+const CONTINUATIONS: [Option<Color>; 4] = [None, Some(Color::Red), Some(Color::Green), Some(Color::Blue)];
+
+/// What the [strategy](BayesianStrategy) best-responds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    /// The program with the highest posterior.
+    MostLikely,
+    /// Every program, weighted by its posterior.
+    Mixture,
+}
+
+/// A program the opponent may be.
+#[derive(Clone)]
+struct Candidate {
+    id: String,
+    player: Player,
+    deterministic: bool,
+}
+
+/// The posterior after some moves, as logarithms of unnormalised probabilities.
+struct Belief {
+    moves: Vec<Move>,
+    log_weights: Vec<f64>,
+}
+
+/// A strategy that identifies which of a set of programs it is facing, and best-responds to it.
+#[derive(Clone)]
+pub struct BayesianStrategy {
+    candidates: Arc<[Candidate]>,
+    response: Response,
+    noise: f64,
+    samples: u64,
+    horizon: u32,
+    beliefs: Arc<Mutex<Vec<Belief>>>,
+}
+
+impl BayesianStrategy {
+    /// Create a new strategy that best-responds to the mixture, with a noise of 0.05, 16 samples per move of a
+    /// randomised program and a horizon of 10 rounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The programs the opponent may be, of which the [deterministic](Entry::deterministic) ones
+    ///   are simulated once per move
+    pub fn new(entries: &[Entry]) -> BayesianStrategy {
+        let candidates = entries.iter()
+            .map(|entry| Candidate {
+                id: String::from(entry.id()),
+                player: entry.player(),
+                deterministic: entry.deterministic(),
+            })
+            .collect();
+
+        BayesianStrategy {
+            candidates,
+            response: Response::Mixture,
+            noise: 0.05,
+            samples: 16,
+            horizon: 10,
+            beliefs: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Set what the strategy best-responds to.
+    pub fn set_response(mut self, response: Response) -> BayesianStrategy {
+        self.response = response;
+        self
+    }
+
+    /// Set the probability that the opponent plays a uniformly random color instead of the color of its program.
+    ///
+    /// # Panics
+    ///
+    /// If `noise` is not within `0.0..=1.0` or is 0.
+    pub fn set_noise(mut self, noise: f64) -> BayesianStrategy {
+        if !(0.0..=1.0).contains(&noise) || noise == 0.0 {
+            panic!("Noise must be within 0.0..=1.0 and not 0");
+        }
+
+        self.noise = noise;
+        self.beliefs = Arc::new(Mutex::new(Vec::new()));
+        self
+    }
+
+    /// Set the amount of samples of the distribution of a randomised program.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is 0.
+    pub fn set_samples(mut self, samples: u64) -> BayesianStrategy {
+        if samples == 0 {
+            panic!("Cannot estimate a distribution without samples");
+        }
+
+        self.samples = samples;
+        self.beliefs = Arc::new(Mutex::new(Vec::new()));
+        self
+    }
+
+    /// Set the amount of rounds looked ahead in the value of a move.
+    ///
+    /// # Panics
+    ///
+    /// If `horizon` is 0.
+    pub fn set_horizon(mut self, horizon: u32) -> BayesianStrategy {
+        if horizon == 0 {
+            panic!("Cannot look ahead 0 rounds");
+        }
+
+        self.horizon = horizon;
+        self
+    }
+
+    /// Get the posterior of every program after some moves, in the order the programs were given.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - The moves, with the own color first
+    /// * `rules` - The rules the programs are simulated with
+    pub fn posterior(&self, last_moves: &[Move], rules: &Rules) -> Vec<(&str, f64)> {
+        self.candidates.iter()
+            .zip(self.weights(last_moves, rules))
+            .map(|(candidate, weight)| (candidate.id.as_str(), weight))
+            .collect()
+    }
+
+    /// Get the program with the highest posterior after some moves, the first of them if there are several, or
+    /// `None` without programs.
+    pub fn most_likely(&self, last_moves: &[Move], rules: &Rules) -> Option<&str> {
+        argmax(&self.weights(last_moves, rules)).map(|i| self.candidates[i].id.as_str())
+    }
+
+    /// Get the normalised posterior after some moves, continuing the longest cached posterior of the moves.
+    fn weights(&self, last_moves: &[Move], rules: &Rules) -> Vec<f64> {
+        let cached = {
+            let mut beliefs = self.beliefs.lock().unwrap_or_else(|error| error.into_inner());
+            let found = beliefs.iter()
+                .enumerate()
+                .filter(|(_, belief)| last_moves.starts_with(&belief.moves))
+                .max_by_key(|(_, belief)| belief.moves.len())
+                .map(|(i, _)| i);
+
+            found.map(|i| beliefs.remove(i))
+        };
+
+        let mut belief = cached.unwrap_or_else(|| Belief {
+            moves: Vec::new(),
+            log_weights: vec![0.0; self.candidates.len()],
+        });
+
+        let mut swapped = belief.moves.iter().map(|m| (m.1, m.0)).collect::<Vec<_>>();
+        for m in &last_moves[belief.moves.len()..] {
+            for (candidate, log_weight) in self.candidates.iter().zip(belief.log_weights.iter_mut()) {
+                let probability = self.distribution(candidate, &swapped, rules)[m.1.index()];
+                *log_weight += ((1.0 - self.noise) * probability + self.noise / 3.0).ln();
+            }
+
+            // Keep the largest weight at 0, so that long games do not underflow
+            let max = belief.log_weights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            belief.log_weights.iter_mut().for_each(|log_weight| *log_weight -= max);
+
+            swapped.push((m.1, m.0));
+            belief.moves.push(*m);
+        }
+
+        let weights = belief.log_weights.iter().map(|log_weight| log_weight.exp()).collect::<Vec<_>>();
+        let total = weights.iter().sum::<f64>();
+
+        let mut beliefs = self.beliefs.lock().unwrap_or_else(|error| error.into_inner());
+        beliefs.truncate(CACHE_SIZE - 1);
+        beliefs.insert(0, belief);
+
+        weights.iter().map(|weight| weight / total).collect()
+    }
+
+    /// Estimate the distribution of the next color of a program.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidate` - The program
+    /// * `moves` - The moves, with the program's color first
+    /// * `rules` - The rules the program is simulated with
+    fn distribution(&self, candidate: &Candidate, moves: &[Move], rules: &Rules) -> [f64; 3] {
+        let samples = if candidate.deterministic { 1 } else { self.samples };
+
+        let mut distribution = [0.0; 3];
+        for sample in 0..samples {
+            let color = candidate.player.simulate(moves, rules, seed(moves.len(), sample));
+            distribution[color.index()] += 1.0 / samples as f64;
+        }
+
+        distribution
+    }
+
+    /// Get the value of playing a color against a program: the best total payoff over the horizon of playing the
+    /// color followed by one of the [continuations](CONTINUATIONS).
+    fn value(&self, candidate: &Candidate, last_moves: &[Move], color: Color, rules: &Rules) -> i64 {
+        let swapped = last_moves.iter().map(|m| (m.1, m.0)).collect::<Vec<_>>();
+
+        CONTINUATIONS.iter()
+            .map(|continuation| {
+                let mut moves = swapped.clone();
+                let mut total = 0;
+                for round in 0..self.horizon {
+                    let own = match (round, continuation) {
+                        (0, _) => color,
+                        (_, Some(continuation)) => *continuation,
+                        (_, None) => moves[moves.len() - 1].0,
+                    };
+
+                    let opponent = candidate.player.simulate(&moves, rules, seed(moves.len(), 0));
+                    total += i64::from(rules.payoffs().score((own, opponent)).0);
+                    moves.push((opponent, own));
+                }
+
+                total
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Strategy for BayesianStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let rules = *context.rules();
+        let weights = self.weights(last_moves, &rules);
+
+        let targets = match self.response {
+            Response::MostLikely => argmax(&weights).map(|i| (i, 1.0)).into_iter().collect::<Vec<_>>(),
+            // Programs with a negligible posterior are not worth simulating
+            Response::Mixture => weights.iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, weight)| *weight >= 0.001)
+                .collect(),
+        };
+
+        let mut best = (Color::Green, f64::NEG_INFINITY);
+        for color in Color::ALL {
+            let value = targets.iter()
+                .map(|(i, weight)| weight * self.value(&self.candidates[*i], last_moves, color, &rules) as f64)
+                .sum::<f64>();
+
+            if value > best.1 {
+                best = (color, value);
+            }
+        }
+
+        best.0
+    }
+}
+
+impl fmt::Debug for BayesianStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BayesianStrategy")
+            .field("candidates", &self.candidates.iter().map(|candidate| &candidate.id).collect::<Vec<_>>())
+            .field("response", &self.response)
+            .field("noise", &self.noise)
+            .field("samples", &self.samples)
+            .field("horizon", &self.horizon)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Get the index of the largest weight, the first of them if there are several.
+fn argmax(weights: &[f64]) -> Option<usize> {
+    weights.iter()
+        .enumerate()
+        .fold(None, |best: Option<(usize, f64)>, (i, weight)| match best {
+            Some(best) if best.1 >= *weight => Some(best),
+            _ => Some((i, *weight)),
+        })
+        .map(|(i, _)| i)
+}
+
+/// The seed of a simulated move, which only depends on the round and the sample, so that the posterior only
+/// depends on the moves.
+fn seed(round: usize, sample: u64) -> u64 {
+    (round as u64) << 32 | sample
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::programs::all::*;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry::program("friendly", "Friendly", friendly).set_deterministic(true),
+            Entry::program("evil", "Evil", evil).set_deterministic(true),
+            Entry::program("tit_for_tat", "Tit for tat", tit_for_tat_prisoner).set_deterministic(true),
+            Entry::program("random", "Random", random),
+        ]
+    }
+
+    #[test]
+    fn posterior_test() {
+        let strategy = BayesianStrategy::new(&entries());
+        let player = Player::from_strategy(strategy.clone());
+        let rules = Rules::default();
+
+        for (opponent, id) in [(tit_for_tat_prisoner as fn(&[Move]) -> Color, "tit_for_tat"), (evil, "evil"), (random, "random")] {
+            let transcript = Game::new(30).set_seed(3).record(&player, &Player::new(opponent)).unwrap();
+            let posterior = strategy.posterior(transcript.moves(), &rules);
+
+            assert_eq!(strategy.most_likely(transcript.moves(), &rules), Some(id));
+            assert!((posterior.iter().map(|(_, weight)| weight).sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+
+        // Before the first move, every program is as likely
+        assert_eq!(strategy.posterior(&[], &rules).iter().map(|(_, weight)| *weight).collect::<Vec<_>>(), vec![0.25; 4]);
+
+        // The cached posterior is the same as a new one
+        let moves = [(Color::Green, Color::Green), (Color::Red, Color::Green), (Color::Green, Color::Red)];
+        assert_eq!(strategy.posterior(&moves, &rules), BayesianStrategy::new(&entries()).posterior(&moves, &rules));
+    }
+
+    #[test]
+    fn response_test() {
+        for response in [Response::MostLikely, Response::Mixture] {
+            let player = Player::from_strategy(BayesianStrategy::new(&entries()).set_response(response));
+
+            // Red exploits friendly, but tit for tat answers it
+            let transcript = Game::new(30).set_seed(3).record(&player, &Player::new(friendly)).unwrap();
+            assert!(transcript.moves()[5..].iter().all(|m| m.0 == Color::Red));
+
+            let transcript = Game::new(30).set_seed(3).record(&player, &Player::new(tit_for_tat_prisoner)).unwrap();
+            assert!(transcript.moves()[5..25].iter().all(|m| *m == (Color::Green, Color::Green)));
+        }
+    }
+}
@@ -0,0 +1,327 @@
+//! `combinators` builds new [players](Player) out of existing programs and strategies.
+//!
+//! Every combinator takes players and returns a player, so combinators can be nested and the result can be
+//! [registered](crate::registry::Entry) like any other program. The inner players are given the same moves and
+//! [context](Context) as the combined player, and only the players whose color is needed are asked for one. Every
+//! inner player is told when a game is over.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use tourney::game::Player;
+//! use tourney::programs::all::*;
+//! use tourney::registry::Entry;
+//! use tourney::strategies::combinators::*;
+//!
+//! // Tit for tat that makes a mistake in 5% of the moves, and turns greedy once it is winning
+//! let entry = Entry::new("noisy_greed", "Noisy greed", Arc::new(|| {
+//!     if_winning(Player::new(blue), with_noise(0.05, Player::new(tit_for_tat_prisoner)))
+//! }));
+//! ```
+
+use rand::Rng;
+use crate::game::{Color, Move, Score, Context, Player, Strategy, rng};
+
+/// Play either of two players, picking the first with probability `p` every move.
+struct Mixed {
+    p: f64,
+    a: Player,
+    b: Player,
+}
+
+impl Strategy for Mixed {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        if rng().gen_bool(self.p) {
+            self.a.make_move(last_moves, context)
+        } else {
+            self.b.make_move(last_moves, context)
+        }
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        self.a.game_over(moves, scores);
+        self.b.game_over(moves, scores);
+    }
+}
+
+/// Play the first player for a number of moves, then the second.
+struct SwitchAfter {
+    n: usize,
+    a: Player,
+    b: Player,
+}
+
+impl Strategy for SwitchAfter {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        if last_moves.len() < self.n {
+            self.a.make_move(last_moves, context)
+        } else {
+            self.b.make_move(last_moves, context)
+        }
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        self.a.game_over(moves, scores);
+        self.b.game_over(moves, scores);
+    }
+}
+
+/// Play a player, but replace its color by one of the other colors with probability `p`.
+struct WithNoise {
+    p: f64,
+    s: Player,
+}
+
+impl Strategy for WithNoise {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let color = self.s.make_move(last_moves, context);
+        if !rng().gen_bool(self.p) {
+            return color;
+        }
+
+        let others = Color::ALL.into_iter().filter(|other| *other != color).collect::<Vec<_>>();
+        others[rng().gen_range(0..others.len())]
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        self.s.game_over(moves, scores);
+    }
+}
+
+/// Play a player with red and green swapped.
+struct Invert {
+    s: Player,
+}
+
+impl Strategy for Invert {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        match self.s.make_move(last_moves, context) {
+            Color::Red => Color::Green,
+            Color::Green => Color::Red,
+            Color::Blue => Color::Blue,
+        }
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        self.s.game_over(moves, scores);
+    }
+}
+
+/// Play the first player while winning, otherwise the second.
+struct IfWinning {
+    a: Player,
+    b: Player,
+}
+
+impl Strategy for IfWinning {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let scores = context.rules().calculate_scores(last_moves);
+
+        if scores.0 > scores.1 {
+            self.a.make_move(last_moves, context)
+        } else {
+            self.b.make_move(last_moves, context)
+        }
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        self.a.game_over(moves, scores);
+        self.b.game_over(moves, scores);
+    }
+}
+
+/// Play the color most of the players pick.
+struct MajorityVote {
+    players: Vec<Player>,
+}
+
+impl Strategy for MajorityVote {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let votes = self.players.iter()
+            .map(|player| player.make_move(last_moves, context))
+            .collect::<Vec<_>>();
+
+        let mut counts = [0; 3];
+        for color in &votes {
+            counts[color.index()] += 1;
+        }
+
+        // A tie goes to the tied color picked by the earliest player
+        let most = counts.iter().max().copied().unwrap_or_default();
+        votes.into_iter().find(|color| counts[color.index()] == most).unwrap_or(Color::Green)
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        for player in &self.players {
+            player.game_over(moves, scores);
+        }
+    }
+}
+
+/// Create a player that plays `a` with probability `p` and otherwise `b`, drawn every move from the
+/// [game's random number generator](rng).
+///
+/// # Panics
+///
+/// If `p` is not within `0.0..=1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use tourney::game::Player;
+/// use tourney::programs::all::*;
+/// use tourney::strategies::combinators::mixed;
+///
+/// // Cooperate three moves out of four
+/// let player = mixed(0.75, Player::new(friendly), Player::new(evil));
+/// ```
+pub fn mixed(p: f64, a: Player, b: Player) -> Player {
+    if !(0.0..=1.0).contains(&p) {
+        panic!("Cannot mix with probability {p}");
+    }
+
+    Player::from_strategy(Mixed { p, a, b })
+}
+
+/// Create a player that plays `a` for the first `n` moves and `b` from then on. Only the active player is asked for a
+/// move; both are told when the game is over.
+///
+/// # Examples
+///
+/// ```
+/// use tourney::game::Player;
+/// use tourney::programs::all::*;
+/// use tourney::strategies::combinators::switch_after;
+///
+/// // Go for a blue lead in the first 15 rounds, then play tit for tat
+/// let player = switch_after(15, Player::new(blue), Player::new(tit_for_tat_prisoner));
+/// ```
+pub fn switch_after(n: usize, a: Player, b: Player) -> Player {
+    Player::from_strategy(SwitchAfter { n, a, b })
+}
+
+/// Create a player that plays `s`, but plays one of the two other colors with probability `p`, drawn every move
+/// from the [game's random number generator](rng).
+///
+/// # Panics
+///
+/// If `p` is not within `0.0..=1.0`.
+pub fn with_noise(p: f64, s: Player) -> Player {
+    if !(0.0..=1.0).contains(&p) {
+        panic!("Cannot add noise with probability {p}");
+    }
+
+    Player::from_strategy(WithNoise { p, s })
+}
+
+/// Create a player that plays `s` with cooperation and defection swapped: red becomes green, green becomes red
+/// and blue stays blue.
+pub fn invert(s: Player) -> Player {
+    Player::from_strategy(Invert { s })
+}
+
+/// Create a player that plays `a` while its score is higher than the opponent's, otherwise `b`.
+///
+/// The scores are calculated with the [rules](crate::rules::Rules) in effect for the current move, as far as
+/// they have been announced, and without the doubling.
+pub fn if_winning(a: Player, b: Player) -> Player {
+    Player::from_strategy(IfWinning { a, b })
+}
+
+/// Create a player that plays the color picked by most of the players. A tie goes to the tied color picked by
+/// the earliest player.
+///
+/// # Panics
+///
+/// If there are no players.
+///
+/// # Examples
+///
+/// ```
+/// use tourney::game::Player;
+/// use tourney::programs::all::*;
+/// use tourney::strategies::combinators::majority_vote;
+///
+/// let player = majority_vote(vec![Player::new(tit_for_tat_prisoner), Player::new(copy), Player::new(grim_trigger)]);
+/// ```
+pub fn majority_vote(players: Vec<Player>) -> Player {
+    if players.is_empty() {
+        panic!("Cannot vote without players");
+    }
+
+    Player::from_strategy(MajorityVote { players })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::programs::all::*;
+    use crate::rules::{Payoffs, Rules, Schedule};
+
+    fn colors(player: &Player, opponent: &Player, rounds: u32) -> Vec<Color> {
+        let transcript = Game::new(rounds).set_seed(3).record(player, opponent).unwrap();
+        transcript.moves().iter().map(|m| m.0).collect()
+    }
+
+    #[test]
+    fn mixed_test() {
+        let (friendly, evil) = (Player::new(friendly), Player::new(evil));
+
+        assert_eq!(colors(&mixed(1.0, evil.clone(), friendly.clone()), &friendly, 10), vec![Color::Red; 10]);
+        assert_eq!(colors(&mixed(0.0, evil.clone(), friendly.clone()), &friendly, 10), vec![Color::Green; 10]);
+
+        let moves = colors(&mixed(0.5, evil, friendly.clone()), &friendly, 1000);
+        let red = moves.iter().filter(|color| **color == Color::Red).count();
+        assert!((400..600).contains(&red));
+    }
+
+    #[test]
+    fn switch_after_test() {
+        let player = switch_after(2, Player::new(friendly), Player::new(tit_for_tat_prisoner));
+        let moves = colors(&player, &Player::new(evil), 4);
+
+        assert_eq!(moves, vec![Color::Green, Color::Green, Color::Red, Color::Red]);
+    }
+
+    #[test]
+    fn with_noise_test() {
+        let friendly = Player::new(friendly);
+
+        assert!(colors(&with_noise(1.0, friendly.clone()), &friendly, 100).iter().all(|color| *color != Color::Green));
+        assert_eq!(colors(&with_noise(0.0, friendly.clone()), &friendly, 100), vec![Color::Green; 100]);
+    }
+
+    #[test]
+    fn invert_test() {
+        let player = invert(Player::new(copy));
+        let moves = colors(&player, &Player::new(blue), 3);
+
+        assert_eq!(colors(&invert(Player::new(evil)), &Player::new(evil), 3), vec![Color::Green; 3]);
+        assert_eq!(moves[1..], [Color::Blue, Color::Blue]);
+    }
+
+    #[test]
+    fn if_winning_test() {
+        let player = if_winning(Player::new(blue), Player::new(friendly));
+
+        // Blue gives away a point, after which the player is winning
+        assert_eq!(colors(&player, &Player::new(blue), 3), vec![Color::Green, Color::Blue, Color::Blue]);
+
+        // With blue giving away nothing, the player is never ahead
+        let rules = Rules::new(Payoffs::new([[1, 3, 0], [0, 2, 0], [0, 0, 0]]), true);
+        let game = Game::new(3).set_schedule(Schedule::new(rules));
+        let transcript = game.record(&player, &Player::new(blue)).unwrap();
+        assert!(transcript.moves().iter().all(|m| m.0 == Color::Green));
+    }
+
+    #[test]
+    fn majority_vote_test() {
+        let (friendly, evil, blue) = (Player::new(friendly), Player::new(evil), Player::new(blue));
+
+        let player = majority_vote(vec![evil.clone(), friendly.clone(), evil.clone()]);
+        assert_eq!(colors(&player, &friendly, 3), vec![Color::Red; 3]);
+
+        let player = majority_vote(vec![blue, friendly.clone(), evil]);
+        assert_eq!(colors(&player, &friendly, 3), vec![Color::Blue; 3]);
+    }
+}
@@ -0,0 +1,729 @@
+//! `dsl` contains strategies written as rules in a small declarative language, which can be submitted without
+//! writing Rust.
+//!
+//! A [script](DslStrategy) has a rule on every line, and the first rule whose condition holds decides the color.
+//! A rule is either `if <condition> then <action>` or, as the last rule, `else <action>`. An action is a color
+//! (`Red`, `Green` or `Blue`) or `random(...)` of one or more colors, drawn from the
+//! [game's random number generator](crate::game::rng):
+//!
+//! ```text
+//! # Match opponent, 15 greedy start
+//! if round < 15 and my.blue - opp.blue < 2 then Blue
+//! if opp.red < opp.green then Green
+//! if opp.red > opp.green then Red
+//! if opp.red == opp.blue then random(Red, Green, Blue)
+//! else random(Red, Green)
+//! ```
+//!
+//! Conditions are expressions over these values:
+//!
+//! * `round` - The index of the current round, counting from 0
+//! * `my.last`, `opp.last` - The last color of the player or its opponent, which equals no color in round 0
+//! * `my.red`, `my.green`, `my.blue` (and `opp.*`) - How many times a color was played
+//! * `my.score`, `opp.score` - The score so far by the rules in effect, without the doubling
+//!
+//! Integers support `+`, `-` and `*`, and are compared with `<`, `<=`, `>`, `>=`, `==` and `!=`. Colors are
+//! compared with `==` and `!=`, and conditions are combined with `not`, `and` and `or`. A script is type checked
+//! when it is parsed, so a script that parses always picks a color.
+
+use std::{fmt, str::FromStr};
+use rand::seq::SliceRandom;
+use crate::game::{Color, Move, Context, Strategy, rng};
+use crate::rules::Rules;
+use crate::strategies::ParseStrategyError;
+
+/// Whose moves a value is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    My,
+    Opp,
+}
+
+/// A value that depends on the moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    Round,
+    Last(Side),
+    Count(Side, Color),
+    Score(Side),
+}
+
+/// A binary operator, from the lowest to the highest precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Op {
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge => 4,
+            Op::Add | Op::Sub => 5,
+            Op::Mul => 6,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Or => "or",
+            Op::And => "and",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+        }
+    }
+}
+
+/// An expression of a condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Int(i64),
+    Bool(bool),
+    Color(Color),
+    Var(Var),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+/// The type of an [expression](Expr).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Int,
+    Bool,
+    Color,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "an integer"),
+            Type::Bool => write!(f, "a condition"),
+            Type::Color => write!(f, "a color"),
+        }
+    }
+}
+
+/// The value of an [expression](Expr). A color is `None` where there is no last color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    Int(i64),
+    Bool(bool),
+    Color(Option<Color>),
+}
+
+/// What a rule plays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    Color(Color),
+    Random(Vec<Color>),
+}
+
+/// A rule of a script, without a condition for `else`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    condition: Option<Expr>,
+    action: Action,
+}
+
+/// The values of the variables for a move.
+struct Env {
+    round: i64,
+    last: [Option<Color>; 2],
+    counts: [[i64; 3]; 2],
+    scores: [i64; 2],
+}
+
+impl Env {
+    fn new(last_moves: &[Move], rules: &Rules) -> Env {
+        let mut counts = [[0; 3]; 2];
+        for m in last_moves {
+            counts[0][m.0.index()] += 1;
+            counts[1][m.1.index()] += 1;
+        }
+
+        let scores = rules.calculate_scores(last_moves);
+        let last = last_moves.last();
+
+        Env {
+            round: last_moves.len() as i64,
+            last: [last.map(|m| m.0), last.map(|m| m.1)],
+            counts,
+            scores: [scores.0, scores.1],
+        }
+    }
+
+    fn get(&self, var: Var) -> Value {
+        let side = |side: Side| match side {
+            Side::My => 0,
+            Side::Opp => 1,
+        };
+
+        match var {
+            Var::Round => Value::Int(self.round),
+            Var::Last(s) => Value::Color(self.last[side(s)]),
+            Var::Count(s, color) => Value::Int(self.counts[side(s)][color.index()]),
+            Var::Score(s) => Value::Int(self.scores[side(s)]),
+        }
+    }
+}
+
+impl Expr {
+    /// Get the type of the expression, or why it has none.
+    fn check(&self) -> Result<Type, String> {
+        let expect = |expr: &Expr, expected: Type| match expr.check()? {
+            found if found == expected => Ok(()),
+            found => Err(format!("Expected {expected}, found {found}: {expr}")),
+        };
+
+        match self {
+            Expr::Int(_) => Ok(Type::Int),
+            Expr::Bool(_) => Ok(Type::Bool),
+            Expr::Color(_) => Ok(Type::Color),
+            Expr::Var(Var::Last(_)) => Ok(Type::Color),
+            Expr::Var(_) => Ok(Type::Int),
+            Expr::Not(expr) => expect(expr, Type::Bool).map(|_| Type::Bool),
+            Expr::Neg(expr) => expect(expr, Type::Int).map(|_| Type::Int),
+            Expr::Binary(op, left, right) => match op {
+                Op::Or | Op::And => {
+                    expect(left, Type::Bool)?;
+                    expect(right, Type::Bool)?;
+                    Ok(Type::Bool)
+                }
+                Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+                    expect(left, Type::Int)?;
+                    expect(right, Type::Int)?;
+                    Ok(Type::Bool)
+                }
+                Op::Add | Op::Sub | Op::Mul => {
+                    expect(left, Type::Int)?;
+                    expect(right, Type::Int)?;
+                    Ok(Type::Int)
+                }
+                Op::Eq | Op::Ne => {
+                    let (left_type, right_type) = (left.check()?, right.check()?);
+                    if left_type != right_type {
+                        return Err(format!("Cannot compare {left_type} with {right_type}: {self}"));
+                    }
+
+                    Ok(Type::Bool)
+                }
+            },
+        }
+    }
+
+    /// Evaluate a [type checked](Expr::check) expression.
+    fn eval(&self, env: &Env) -> Value {
+        let int = |expr: &Expr| match expr.eval(env) {
+            Value::Int(value) => value,
+            _ => 0,
+        };
+        let bool = |expr: &Expr| matches!(expr.eval(env), Value::Bool(true));
+
+        match self {
+            Expr::Int(value) => Value::Int(*value),
+            Expr::Bool(value) => Value::Bool(*value),
+            Expr::Color(color) => Value::Color(Some(*color)),
+            Expr::Var(var) => env.get(*var),
+            Expr::Not(expr) => Value::Bool(!bool(expr)),
+            Expr::Neg(expr) => Value::Int(int(expr).saturating_neg()),
+            Expr::Binary(op, left, right) => match op {
+                Op::Or => Value::Bool(bool(left) || bool(right)),
+                Op::And => Value::Bool(bool(left) && bool(right)),
+                Op::Lt => Value::Bool(int(left) < int(right)),
+                Op::Le => Value::Bool(int(left) <= int(right)),
+                Op::Gt => Value::Bool(int(left) > int(right)),
+                Op::Ge => Value::Bool(int(left) >= int(right)),
+                Op::Add => Value::Int(int(left).saturating_add(int(right))),
+                Op::Sub => Value::Int(int(left).saturating_sub(int(right))),
+                Op::Mul => Value::Int(int(left).saturating_mul(int(right))),
+                Op::Eq | Op::Ne => {
+                    // No last color equals no color at all
+                    let equal = match (left.eval(env), right.eval(env)) {
+                        (Value::Color(None), _) | (_, Value::Color(None)) => false,
+                        (left, right) => left == right,
+                    };
+
+                    Value::Bool(equal == (*op == Op::Eq))
+                }
+            },
+        }
+    }
+
+    /// Write the expression, with parentheses where an operator of the given precedence surrounds it.
+    fn write(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        match self {
+            Expr::Int(value) => write!(f, "{value}"),
+            Expr::Bool(value) => write!(f, "{value}"),
+            Expr::Color(color) => write!(f, "{color:?}"),
+            Expr::Var(Var::Round) => write!(f, "round"),
+            Expr::Var(Var::Last(side)) => write!(f, "{0}.last", side_name(*side)),
+            Expr::Var(Var::Count(side, color)) => write!(f, "{0}.{1}", side_name(*side), format!("{color:?}").to_lowercase()),
+            Expr::Var(Var::Score(side)) => write!(f, "{0}.score", side_name(*side)),
+            Expr::Not(expr) => {
+                if precedence > 3 {
+                    write!(f, "(not ")?;
+                    expr.write(f, 3)?;
+                    write!(f, ")")
+                } else {
+                    write!(f, "not ")?;
+                    expr.write(f, 3)
+                }
+            }
+            Expr::Neg(expr) => {
+                write!(f, "-")?;
+                expr.write(f, 7)
+            }
+            Expr::Binary(op, left, right) => {
+                let own = op.precedence();
+                // Comparisons do not chain, and the right side of a left-associative operator needs parentheses
+                let left_precedence = if own == 4 { own + 1 } else { own };
+
+                if own < precedence {
+                    write!(f, "(")?;
+                }
+
+                left.write(f, left_precedence)?;
+                write!(f, " {0} ", op.symbol())?;
+                right.write(f, own + 1)?;
+
+                if own < precedence {
+                    write!(f, ")")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::My => "my",
+        Side::Opp => "opp",
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// A token of a line of a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Int(i64),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{word}"),
+            Token::Int(value) => write!(f, "{value}"),
+            Token::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+/// Split a line into tokens.
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    const SYMBOLS: [&str; 13] = ["==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "(", ")", ",", "."];
+
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            tokens.push(Token::Word(String::from(&rest[..end])));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let value = rest[..end].parse::<i64>().map_err(|_| format!("Invalid integer: {0}", &rest[..end]))?;
+            tokens.push(Token::Int(value));
+            rest = &rest[end..];
+        } else {
+            let symbol = SYMBOLS.iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| format!("Unexpected character: {c}"))?;
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        }
+
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a line.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.peek().cloned().ok_or("Unexpected end of line")?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Skip the next token if it is a word or symbol.
+    fn eat(&mut self, expected: &str) -> bool {
+        let found = match self.peek() {
+            Some(Token::Word(word)) => word == expected,
+            Some(Token::Symbol(symbol)) => *symbol == expected,
+            _ => false,
+        };
+
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+
+        match self.peek() {
+            Some(token) => Err(format!("Expected {expected}, found {token}")),
+            None => Err(format!("Expected {expected} at the end of the line")),
+        }
+    }
+
+    fn rule(&mut self) -> Result<Rule, String> {
+        let condition = match self.next()? {
+            Token::Word(word) if word == "if" => {
+                let condition = self.expr(1)?;
+                self.expect("then")?;
+                Some(condition)
+            }
+            Token::Word(word) if word == "else" => None,
+            token => return Err(format!("Expected if or else, found {token}")),
+        };
+
+        let action = self.action()?;
+        if let Some(token) = self.peek() {
+            return Err(format!("Unexpected {token} after the action"));
+        }
+
+        Ok(Rule { condition, action })
+    }
+
+    fn action(&mut self) -> Result<Action, String> {
+        if !self.eat("random") {
+            return self.color().map(Action::Color);
+        }
+
+        self.expect("(")?;
+        let mut colors = vec![self.color()?];
+        while self.eat(",") {
+            colors.push(self.color()?);
+        }
+        self.expect(")")?;
+
+        Ok(Action::Random(colors))
+    }
+
+    fn color(&mut self) -> Result<Color, String> {
+        match self.next()? {
+            Token::Word(word) => color(&word).ok_or_else(|| format!("Expected a color, found {word}")),
+            token => Err(format!("Expected a color, found {token}")),
+        }
+    }
+
+    /// Parse an expression of binary operators with at least the given precedence.
+    fn expr(&mut self, precedence: u8) -> Result<Expr, String> {
+        if precedence == 3 {
+            return if self.eat("not") {
+                Ok(Expr::Not(Box::new(self.expr(3)?)))
+            } else {
+                self.expr(4)
+            };
+        }
+
+        if precedence > 6 {
+            return self.unary();
+        }
+
+        let mut left = self.expr(precedence + 1)?;
+        loop {
+            let op = [Op::Or, Op::And, Op::Eq, Op::Ne, Op::Lt, Op::Le, Op::Gt, Op::Ge, Op::Add, Op::Sub, Op::Mul]
+                .into_iter()
+                .find(|op| op.precedence() == precedence && self.peek().is_some_and(|token| token.to_string() == op.symbol()));
+
+            let Some(op) = op else {
+                return Ok(left);
+            };
+
+            self.position += 1;
+            let right = self.expr(precedence + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+
+            // Comparisons do not chain
+            if precedence == 4 {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+
+        if self.eat("(") {
+            let expr = self.expr(1)?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+
+        match self.next()? {
+            Token::Int(value) => Ok(Expr::Int(value)),
+            Token::Word(word) => match word.as_str() {
+                "true" => Ok(Expr::Bool(true)),
+                "false" => Ok(Expr::Bool(false)),
+                "round" => Ok(Expr::Var(Var::Round)),
+                "my" | "opp" => {
+                    let side = if word == "my" { Side::My } else { Side::Opp };
+                    self.expect(".")?;
+
+                    match self.next()? {
+                        Token::Word(field) => match field.as_str() {
+                            "last" => Ok(Expr::Var(Var::Last(side))),
+                            "red" => Ok(Expr::Var(Var::Count(side, Color::Red))),
+                            "green" => Ok(Expr::Var(Var::Count(side, Color::Green))),
+                            "blue" => Ok(Expr::Var(Var::Count(side, Color::Blue))),
+                            "score" => Ok(Expr::Var(Var::Score(side))),
+                            _ => Err(format!("Unknown value: {word}.{field}")),
+                        },
+                        token => Err(format!("Unknown value: {word}.{token}")),
+                    }
+                }
+                _ => color(&word).map(Expr::Color).ok_or_else(|| format!("Unknown value: {word}")),
+            },
+            token => Err(format!("Unexpected {token}")),
+        }
+    }
+}
+
+fn color(word: &str) -> Option<Color> {
+    match word {
+        "Red" => Some(Color::Red),
+        "Green" => Some(Color::Green),
+        "Blue" => Some(Color::Blue),
+        _ => None,
+    }
+}
+
+/// A strategy that plays the action of the first rule whose condition holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DslStrategy {
+    rules: Vec<Rule>,
+}
+
+impl DslStrategy {
+    /// Whether the script always plays the same color after the same moves, i.e. never plays `random(...)` of
+    /// more than one color.
+    pub fn is_deterministic(&self) -> bool {
+        self.rules.iter().all(|rule| match &rule.action {
+            Action::Color(_) => true,
+            Action::Random(colors) => colors.iter().all(|color| *color == colors[0]),
+        })
+    }
+
+    /// Choose a [color](Color) after some [moves](Move).
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - A slice of [moves](Move), with the own color first in every move
+    /// * `rules` - The [rules](Rules) by which `my.score` and `opp.score` are calculated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::rules::Rules;
+    /// use tourney::strategies::dsl::DslStrategy;
+    ///
+    /// let script = "if opp.last == Red then Red\nelse Green\n".parse::<DslStrategy>().unwrap();
+    ///
+    /// assert_eq!(script.color(&[], &Rules::default()), Color::Green);
+    /// assert_eq!(script.color(&[(Color::Green, Color::Red)], &Rules::default()), Color::Red);
+    /// ```
+    pub fn color(&self, last_moves: &[Move], rules: &Rules) -> Color {
+        let env = Env::new(last_moves, rules);
+        let rule = self.rules.iter()
+            .find(|rule| rule.condition.as_ref().is_none_or(|condition| condition.eval(&env) == Value::Bool(true)));
+
+        match rule.map(|rule| &rule.action) {
+            Some(Action::Color(color)) => *color,
+            Some(Action::Random(colors)) => *colors.choose(&mut rng()).unwrap_or(&Color::Green),
+            // A parsed script always ends with an else rule
+            None => Color::Green,
+        }
+    }
+}
+
+impl Strategy for DslStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        self.color(last_moves, context.rules())
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Color(color) => write!(f, "{color:?}"),
+            Action::Random(colors) => {
+                let colors = colors.iter().map(|color| format!("{color:?}")).collect::<Vec<_>>();
+                write!(f, "random({0})", colors.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for DslStrategy {
+    /// Write the script in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            match &rule.condition {
+                Some(condition) => writeln!(f, "if {0} then {1}", condition, rule.action)?,
+                None => writeln!(f, "else {0}", rule.action)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for DslStrategy {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<DslStrategy, ParseStrategyError> {
+        let mut rules = Vec::new();
+        let mut otherwise = false;
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: String| ParseStrategyError::new(i + 1, &reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let tokens = tokenize(line).map_err(error)?;
+
+            if tokens.is_empty() {
+                continue;
+            }
+
+            if otherwise {
+                return Err(error(String::from("Unreachable rule after else")));
+            }
+
+            let rule = Parser { tokens, position: 0 }.rule().map_err(error)?;
+            if let Some(condition) = &rule.condition {
+                match condition.check().map_err(error)? {
+                    Type::Bool => {}
+                    found => return Err(error(format!("Expected a condition, found {found}: {condition}"))),
+                }
+            }
+
+            otherwise = rule.condition.is_none();
+            rules.push(rule);
+        }
+
+        if !otherwise {
+            return Err(ParseStrategyError::new(0, "Missing else rule"));
+        }
+
+        Ok(DslStrategy { rules })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Player};
+    use crate::programs::all::*;
+
+    const GREED_FIRST_15: &str = "\
+# Match opponent, 15 greedy start
+if round < 15 and my.blue - opp.blue < 2 then Blue
+if opp.red < opp.green then Green
+if opp.red > opp.green then Red
+if opp.red == opp.blue then random(Red, Green, Blue)
+else random(Red, Green)
+";
+
+    #[test]
+    fn greed_first_15_test() {
+        let script = Player::from_strategy(GREED_FIRST_15.parse::<DslStrategy>().unwrap());
+        let program = Player::new(greed_first_15);
+
+        for opponent in [random, tit_for_tat_prisoner, blue, evil, greed_first_15, try_to_guess] {
+            let game = Game::new(100).set_seed(11);
+            let expected = game.record(&program, &Player::new(opponent)).unwrap();
+            let actual = game.record(&script, &Player::new(opponent)).unwrap();
+
+            assert_eq!(actual.moves(), expected.moves());
+        }
+    }
+
+    #[test]
+    fn evaluation_test() {
+        let rules = Rules::default();
+        let script = "\
+if opp.last != Red and (my.score - opp.score >= 2 or -round * 2 < -6) then Blue
+if not true == false then Red
+else Green
+".parse::<DslStrategy>().unwrap();
+
+        assert!(script.is_deterministic());
+        assert_eq!(script.color(&[], &rules), Color::Red);
+        assert_eq!(script.color(&[(Color::Red, Color::Green)], &rules), Color::Blue);
+        assert_eq!(script.color(&[(Color::Red, Color::Red)], &rules), Color::Red);
+        assert_eq!(script.color(&[(Color::Green, Color::Green); 4], &rules), Color::Blue);
+        assert_eq!(script.to_string().parse::<DslStrategy>(), Ok(script.clone()));
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let error = |s: &str| s.parse::<DslStrategy>().unwrap_err().to_string();
+
+        assert_eq!(error("if round then Red\nelse Green"), "Line 1: Expected a condition, found an integer: round");
+        assert_eq!(error("if my.last < 2 then Red\nelse Green"), "Line 1: Expected an integer, found a color: my.last");
+        assert_eq!(error("if opp.last == 1 then Red\nelse Green"), "Line 1: Cannot compare a color with an integer: opp.last == 1");
+        assert_eq!(error("if round < 1 < 2 then Red\nelse Green"), "Line 1: Expected then, found <");
+        assert_eq!(error("\nif opp.lst == Red then Red"), "Line 2: Unknown value: opp.lst");
+        assert_eq!(error("if round > 2 then Purple\nelse Green"), "Line 1: Expected a color, found Purple");
+        assert_eq!(error("else Red\nelse Green"), "Line 2: Unreachable rule after else");
+        assert_eq!(error("if round > 2 then Red"), "Missing else rule");
+        assert_eq!(error("if round ? 2 then Red"), "Line 1: Unexpected character: ?");
+    }
+}
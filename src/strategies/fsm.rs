@@ -0,0 +1,265 @@
+//! `fsm` contains strategies defined by a finite-state machine.
+//!
+//! A [finite-state machine](FsmStrategy) is saved in a simple text format, with a line for each state: its name,
+//! its color and the names of the next state after the opponent plays red, green and blue. The first state is
+//! the initial state:
+//!
+//! ```text
+//! # Grim trigger
+//! state cooperate G  defect cooperate cooperate
+//! state defect    R  defect defect defect
+//! ```
+
+use std::{fmt, str::FromStr};
+use rand::Rng;
+use crate::game::{Color, Move, Context, Strategy};
+use crate::strategies::ParseStrategyError;
+
+/// A state of a [finite-state machine](FsmStrategy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    name: String,
+    color: Color,
+    next: [usize; 3],
+}
+
+impl State {
+    /// Create a new state.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the state, without whitespace
+    /// * `color` - The color played in the state
+    /// * `next` - The indices of the next state after the opponent plays red, green and blue
+    pub fn new(name: &str, color: Color, next: [usize; 3]) -> State {
+        State {
+            name: String::from(name),
+            color,
+            next,
+        }
+    }
+
+    /// Get the name of the state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the color played in the state.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Get the index of the next state after the opponent plays a color.
+    pub fn next(&self, opponent: Color) -> usize {
+        self.next[opponent.index()]
+    }
+}
+
+/// A strategy that plays the color of its current state, and moves to another state on the opponent's color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsmStrategy {
+    states: Vec<State>,
+}
+
+impl FsmStrategy {
+    /// Create a new finite-state machine, which starts in the first state.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The [states](State) of the machine
+    ///
+    /// # Returns
+    ///
+    /// The machine, or `None` if there are no states or if a transition leads to a state that does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::strategies::fsm::{FsmStrategy, State};
+    ///
+    /// // Cooperate until the opponent plays red, then play red forever
+    /// let grim_trigger = FsmStrategy::new(vec![
+    ///     State::new("cooperate", Color::Green, [1, 0, 0]),
+    ///     State::new("defect", Color::Red, [1, 1, 1]),
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(grim_trigger.color(&[(Color::Green, Color::Red), (Color::Red, Color::Green)]), Color::Red);
+    /// ```
+    pub fn new(states: Vec<State>) -> Option<FsmStrategy> {
+        if states.is_empty() || states.iter().flat_map(|state| state.next).any(|next| next >= states.len()) {
+            return None;
+        }
+
+        Some(FsmStrategy { states })
+    }
+
+    /// Create a random finite-state machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    /// * `size` - The amount of states
+    ///
+    /// # Panics
+    ///
+    /// If `size == 0`.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, size: usize) -> FsmStrategy {
+        if size == 0 {
+            panic!("Cannot create a finite-state machine without states");
+        }
+
+        let states = (0..size)
+            .map(|i| {
+                let color = Color::ALL[rng.gen_range(0..3)];
+                let next = [(); 3].map(|_| rng.gen_range(0..size));
+                State::new(&format!("s{i}"), color, next)
+            })
+            .collect();
+
+        FsmStrategy { states }
+    }
+
+    /// Get the states, the first of which is the initial state.
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    /// Set the color played in a state.
+    ///
+    /// # Panics
+    ///
+    /// If the state does not exist.
+    pub fn set_color(mut self, state: usize, color: Color) -> FsmStrategy {
+        self.states[state].color = color;
+        self
+    }
+
+    /// Set the next state after the opponent plays a color.
+    ///
+    /// # Panics
+    ///
+    /// If either state does not exist.
+    pub fn set_next(mut self, state: usize, opponent: Color, next: usize) -> FsmStrategy {
+        if next >= self.states.len() {
+            panic!("Cannot move to state {next} of {0}", self.states.len());
+        }
+
+        self.states[state].next[opponent.index()] = next;
+        self
+    }
+
+    /// Get the index of the state reached after some [moves](Move).
+    pub fn state(&self, last_moves: &[Move]) -> usize {
+        last_moves.iter().fold(0, |state, m| self.states[state].next(m.1))
+    }
+
+    /// Choose a [color](Color) after some [moves](Move).
+    pub fn color(&self, last_moves: &[Move]) -> Color {
+        self.states[self.state(last_moves)].color
+    }
+}
+
+impl Strategy for FsmStrategy {
+    fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+        self.color(last_moves)
+    }
+}
+
+impl fmt::Display for FsmStrategy {
+    /// Write the machine in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for state in &self.states {
+            let next = state.next.map(|next| self.states[next].name());
+            writeln!(f, "state {0} {1}  {2} {3} {4}", state.name, state.color.letter(), next[0], next[1], next[2])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for FsmStrategy {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<FsmStrategy, ParseStrategyError> {
+        // The next states are resolved once every state name is known
+        let mut lines = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                ["state", name, color, red, green, blue] => {
+                    let mut letters = color.chars();
+                    let color = match (letters.next().and_then(Color::from_letter), letters.next()) {
+                        (Some(color), None) => color,
+                        _ => return Err(error(&format!("Invalid color: {color}"))),
+                    };
+
+                    if lines.iter().any(|(_, state, _, _)| state == name) {
+                        return Err(error(&format!("Duplicate state: {name}")));
+                    }
+
+                    lines.push((i + 1, *name, color, [*red, *green, *blue]));
+                }
+                _ => return Err(error("Expected state <name> <color> <next on red> <next on green> <next on blue>")),
+            }
+        }
+
+        if lines.is_empty() {
+            return Err(ParseStrategyError::new(0, "Missing states"));
+        }
+
+        let mut states = Vec::with_capacity(lines.len());
+        for (line, name, color, next) in &lines {
+            let mut indices = [0; 3];
+            for (index, next) in indices.iter_mut().zip(next) {
+                *index = lines.iter()
+                    .position(|(_, state, _, _)| state == next)
+                    .ok_or_else(|| ParseStrategyError::new(*line, &format!("Unknown state: {next}")))?;
+            }
+
+            states.push(State::new(name, *color, indices));
+        }
+
+        Ok(FsmStrategy { states })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::all::*;
+
+    #[test]
+    fn tit_for_tat_test() {
+        let fsm = "state nice G  mean nice nice\nstate mean R  mean nice nice\n".parse::<FsmStrategy>().unwrap();
+        let mut moves = vec![];
+
+        for opponent in [Color::Red, Color::Blue, Color::Green, Color::Red, Color::Red] {
+            assert_eq!(fsm.color(&moves), tit_for_tat_prisoner(&moves));
+            moves.push((fsm.color(&moves), opponent));
+        }
+
+        assert_eq!(fsm.state(&moves), 1);
+    }
+
+    #[test]
+    fn text_format_test() {
+        let fsm = FsmStrategy::random(&mut rand::thread_rng(), 5);
+        assert_eq!(fsm.to_string().parse::<FsmStrategy>(), Ok(fsm.clone()));
+        assert_eq!(fsm.set_next(4, Color::Blue, 0).states()[4].next(Color::Blue), 0);
+
+        let error = "state a G a a b\n".parse::<FsmStrategy>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Unknown state: b");
+
+        let error = "state a G a a a\n\nstate a R a a a\n".parse::<FsmStrategy>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 3: Duplicate state: a");
+
+        let error = "# Nothing\n".parse::<FsmStrategy>().unwrap_err();
+        assert_eq!(error.to_string(), "Missing states");
+    }
+}
@@ -0,0 +1,485 @@
+//! `genetic` contains a genetic algorithm, which evolves [finite-state machines](FsmStrategy),
+//! [lookup tables](LookupTable) or pure [memory-one strategies](MemoryOne) by their score against a roster of
+//! players.
+//!
+//! A [genome](Genome) is a list of genes, each of which takes one of a few values:
+//!
+//! * Memory-one - The color of the opening and the color after each of the 9 previous moves
+//! * Finite-state machine - For every state, its color and the next state after each of the opponent's colors
+//! * Lookup table - The colors of the opening and of every entry
+//!
+//! [Every generation](GeneticAlgorithm::step), each genome plays every player of the roster. The best genomes are
+//! kept unchanged (elitism), and the rest of the next generation are children of parents chosen by tournament
+//! selection: the best of a few random genomes. A child takes each gene from a random parent (uniform crossover)
+//! and then changes each gene with a small probability (mutation). The best genomes ever seen are kept in a hall
+//! of fame.
+
+use std::{cmp::Reverse, fmt};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use crate::config::{Config, run_against};
+use crate::game::{Color, Score, Player};
+use super::{FsmStrategy, LookupTable, MemoryOne};
+use super::fsm::State;
+use super::lookup::MAX_WINDOW;
+
+/// The kind of strategy a [genome](Genome) encodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Species {
+    /// A pure [memory-one strategy](MemoryOne).
+    MemoryOne,
+    /// A [finite-state machine](FsmStrategy) with an amount of states.
+    Fsm(usize),
+    /// A [lookup table](LookupTable) with an amount of own colors and of the opponent's colors looked at.
+    Lookup(usize, usize),
+}
+
+impl Species {
+    /// Get how many values each gene can take.
+    ///
+    /// # Panics
+    ///
+    /// If a finite-state machine has no states, or if the windows of a lookup table together are larger than
+    /// [`MAX_WINDOW`].
+    pub fn alleles(&self) -> Vec<usize> {
+        match *self {
+            Species::MemoryOne => vec![3; 10],
+            Species::Fsm(0) => panic!("Cannot evolve a finite-state machine without states"),
+            Species::Fsm(states) => [3, states, states, states].repeat(states),
+            Species::Lookup(own, opponent) if own + opponent > MAX_WINDOW => panic!("Cannot evolve a lookup table larger than the maximum"),
+            Species::Lookup(own, opponent) => vec![3; own.max(opponent) + 3usize.pow((own + opponent) as u32)],
+        }
+    }
+
+    /// Get the file extension of the strategies (see [`load`](super::load)).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Species::MemoryOne => "mem",
+            Species::Fsm(_) => "fsm",
+            Species::Lookup(..) => "lut",
+        }
+    }
+}
+
+/// A strategy encoded as genes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Genome {
+    species: Species,
+    genes: Vec<usize>,
+}
+
+impl Genome {
+    /// Create a random genome.
+    ///
+    /// # Arguments
+    ///
+    /// * `species` - The kind of strategy
+    /// * `rng` - A random number generator
+    ///
+    /// # Panics
+    ///
+    /// If the species is invalid (see [`Species::alleles`]).
+    pub fn random<R: Rng + ?Sized>(species: Species, rng: &mut R) -> Genome {
+        let genes = species.alleles().iter().map(|alleles| rng.gen_range(0..*alleles)).collect();
+        Genome { species, genes }
+    }
+
+    /// Get the kind of strategy.
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
+    /// Get the genes.
+    pub fn genes(&self) -> &[usize] {
+        &self.genes
+    }
+
+    /// Create a child which takes each gene from either parent with equal probability.
+    ///
+    /// # Panics
+    ///
+    /// If the parents are of different species.
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Genome, rng: &mut R) -> Genome {
+        if self.species != other.species {
+            panic!("Cannot cross genomes of different species");
+        }
+
+        let genes = self.genes.iter()
+            .zip(&other.genes)
+            .map(|(a, b)| if rng.gen() { *a } else { *b })
+            .collect();
+
+        Genome { species: self.species, genes }
+    }
+
+    /// Change each gene to another value with a probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    /// * `rate` - The probability of changing a gene
+    pub fn mutate<R: Rng + ?Sized>(mut self, rng: &mut R, rate: f64) -> Genome {
+        for (gene, alleles) in self.genes.iter_mut().zip(self.species.alleles()) {
+            if alleles > 1 && rng.gen_bool(rate) {
+                *gene = (*gene + rng.gen_range(1..alleles)) % alleles;
+            }
+        }
+
+        self
+    }
+
+    /// Create a [player](Player) of the strategy.
+    pub fn player(&self) -> Player {
+        match self.species {
+            Species::MemoryOne => Player::from_strategy(self.memory_one()),
+            Species::Fsm(_) => Player::from_strategy(self.fsm()),
+            Species::Lookup(..) => Player::from_strategy(self.lookup()),
+        }
+    }
+
+    fn memory_one(&self) -> MemoryOne {
+        let color = |i: usize| Color::ALL[self.genes[i]];
+        MemoryOne::pure(color(0), [0, 1, 2].map(|own| [0, 1, 2].map(|opponent| color(1 + 3 * own + opponent))))
+    }
+
+    fn fsm(&self) -> FsmStrategy {
+        let states = self.genes.chunks(4)
+            .enumerate()
+            .map(|(i, genes)| State::new(&format!("s{i}"), Color::ALL[genes[0]], [genes[1], genes[2], genes[3]]))
+            .collect();
+
+        FsmStrategy::new(states).expect("Transitions lead to existing states")
+    }
+
+    fn lookup(&self) -> LookupTable {
+        let Species::Lookup(own, opponent) = self.species else {
+            unreachable!()
+        };
+
+        let (opening, entries) = self.genes.split_at(own.max(opponent));
+        let table = LookupTable::new(own, opponent, Color::Red).expect("Windows within the maximum");
+        let table = opening.iter()
+            .enumerate()
+            .fold(table, |table, (round, gene)| table.set_opening(round, Color::ALL[*gene]));
+        entries.iter()
+            .enumerate()
+            .fold(table, |table, (index, gene)| table.set_entry(index, Color::ALL[*gene]))
+    }
+}
+
+impl fmt::Display for Genome {
+    /// Write the strategy in the format of its [file extension](Species::extension).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.species {
+            Species::MemoryOne => write!(f, "{}", self.memory_one()),
+            Species::Fsm(_) => write!(f, "{}", self.fsm()),
+            Species::Lookup(..) => write!(f, "{}", self.lookup()),
+        }
+    }
+}
+
+/// The statistics of a generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Generation {
+    number: u32,
+    best: Score,
+    mean: f64,
+    worst: Score,
+    distinct: usize,
+}
+
+impl Generation {
+    /// Get the number of the generation, counting from 1.
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// Get the best fitness.
+    pub fn best(&self) -> Score {
+        self.best
+    }
+
+    /// Get the mean fitness.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the worst fitness.
+    pub fn worst(&self) -> Score {
+        self.worst
+    }
+
+    /// Get the amount of distinct genomes.
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+}
+
+/// A genetic algorithm, which evolves a population of [genomes](Genome) by their total score against a roster.
+#[derive(Debug, Clone)]
+pub struct GeneticAlgorithm {
+    population: Vec<Genome>,
+    generation: u32,
+    elites: usize,
+    selection: usize,
+    crossover: f64,
+    mutation: f64,
+    hall_of_fame: Vec<(Genome, Score)>,
+    hall_size: usize,
+    rng: StdRng,
+}
+
+impl GeneticAlgorithm {
+    /// Create a new genetic algorithm with a random population. It keeps 2 elites, selects the best of 3 genomes
+    /// as a parent, crosses 70% of the parents, changes each gene with a probability of 5% and keeps the 10 best
+    /// genomes in its hall of fame.
+    ///
+    /// # Arguments
+    ///
+    /// * `species` - The kind of strategy
+    /// * `size` - The amount of genomes per generation
+    /// * `seed` - The seed of the population and of the changes to it
+    ///
+    /// # Panics
+    ///
+    /// If `size` is 0, or if the species is invalid (see [`Species::alleles`]).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tourney::config::Config;
+    /// use tourney::game::Player;
+    /// use tourney::programs::all::*;
+    /// use tourney::strategies::genetic::{GeneticAlgorithm, Species};
+    ///
+    /// let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("50"), String::from("--seed"), String::from("1")]).unwrap();
+    /// let roster = [Player::new(tit_for_tat_prisoner), Player::new(evil), Player::new(friendly)];
+    ///
+    /// let mut evolution = GeneticAlgorithm::new(Species::Fsm(4), 40, 7);
+    /// for _ in 0..30 {
+    ///     let generation = evolution.step(&config, &roster).unwrap();
+    ///     println!("{0} {1}", generation.number(), generation.best());
+    /// }
+    ///
+    /// let (best, score) = &evolution.hall_of_fame()[0];
+    /// ```
+    pub fn new(species: Species, size: usize, seed: u64) -> GeneticAlgorithm {
+        if size == 0 {
+            panic!("Cannot evolve without a population");
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let population = (0..size).map(|_| Genome::random(species, &mut rng)).collect();
+
+        GeneticAlgorithm {
+            population,
+            generation: 0,
+            elites: 2,
+            selection: 3,
+            crossover: 0.7,
+            mutation: 0.05,
+            hall_of_fame: Vec::new(),
+            hall_size: 10,
+            rng,
+        }
+    }
+
+    /// Set the amount of best genomes that are kept unchanged, at most the size of the population.
+    pub fn set_elites(mut self, elites: usize) -> GeneticAlgorithm {
+        self.elites = elites.min(self.population.len());
+        self
+    }
+
+    /// Set the amount of random genomes of which the best is chosen as a parent.
+    ///
+    /// # Panics
+    ///
+    /// If `selection` is 0.
+    pub fn set_selection(mut self, selection: usize) -> GeneticAlgorithm {
+        if selection == 0 {
+            panic!("Cannot select a parent of no genomes");
+        }
+
+        self.selection = selection;
+        self
+    }
+
+    /// Set the probability that a child is a crossover of two parents instead of a copy of one.
+    ///
+    /// # Panics
+    ///
+    /// If `crossover` is not within `0.0..=1.0`.
+    pub fn set_crossover(mut self, crossover: f64) -> GeneticAlgorithm {
+        if !(0.0..=1.0).contains(&crossover) {
+            panic!("Probability must be within 0.0..=1.0");
+        }
+
+        self.crossover = crossover;
+        self
+    }
+
+    /// Set the probability that a gene of a child is changed.
+    ///
+    /// # Panics
+    ///
+    /// If `mutation` is not within `0.0..=1.0`.
+    pub fn set_mutation(mut self, mutation: f64) -> GeneticAlgorithm {
+        if !(0.0..=1.0).contains(&mutation) {
+            panic!("Probability must be within 0.0..=1.0");
+        }
+
+        self.mutation = mutation;
+        self
+    }
+
+    /// Set the amount of genomes in the hall of fame.
+    pub fn set_hall_of_fame(mut self, size: usize) -> GeneticAlgorithm {
+        self.hall_size = size;
+        self.hall_of_fame.truncate(size);
+        self
+    }
+
+    /// Get the genomes of the next generation.
+    pub fn population(&self) -> &[Genome] {
+        &self.population
+    }
+
+    /// Get the best distinct genomes so far and their fitness, the best first.
+    pub fn hall_of_fame(&self) -> &[(Genome, Score)] {
+        &self.hall_of_fame
+    }
+
+    /// Run a generation: measure the fitness of every genome, and replace the population with the next
+    /// generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the games
+    /// * `roster` - The players every genome plays against
+    ///
+    /// # Returns
+    ///
+    /// The statistics of the generation.
+    ///
+    /// # Errors
+    ///
+    /// If the games cannot be run.
+    pub fn step(&mut self, config: &Config, roster: &[Player]) -> Result<Generation, String> {
+        let players = self.population.iter().map(Genome::player).collect::<Vec<_>>();
+        let scores = run_against(config, &players, roster)?;
+
+        let mut ranked = self.population.drain(..).zip(scores).collect::<Vec<_>>();
+        ranked.sort_by_key(|(_, score)| Reverse(*score));
+
+        let mut distinct = ranked.iter().map(|(genome, _)| genome).collect::<Vec<_>>();
+        distinct.sort_by(|a, b| a.genes.cmp(&b.genes));
+        distinct.dedup();
+
+        self.generation += 1;
+        let generation = Generation {
+            number: self.generation,
+            best: ranked[0].1,
+            mean: ranked.iter().map(|(_, score)| *score as f64).sum::<f64>() / ranked.len() as f64,
+            worst: ranked[ranked.len() - 1].1,
+            distinct: distinct.len(),
+        };
+
+        for (genome, score) in &ranked {
+            if !self.hall_of_fame.iter().any(|(other, _)| other == genome) {
+                self.hall_of_fame.push((genome.clone(), *score));
+            }
+        }
+        self.hall_of_fame.sort_by_key(|(_, score)| Reverse(*score));
+        self.hall_of_fame.truncate(self.hall_size);
+
+        let mut next = ranked.iter().take(self.elites).map(|(genome, _)| genome.clone()).collect::<Vec<_>>();
+        while next.len() < ranked.len() {
+            let parent = self.select(&ranked);
+            let child = match self.rng.gen_bool(self.crossover) {
+                true => parent.crossover(self.select(&ranked), &mut self.rng),
+                false => parent.clone(),
+            };
+            next.push(child.mutate(&mut self.rng, self.mutation));
+        }
+        self.population = next;
+
+        Ok(generation)
+    }
+
+    /// Choose the best of a few random genomes.
+    fn select<'a>(&mut self, ranked: &'a [(Genome, Score)]) -> &'a Genome {
+        let (genome, _) = (0..self.selection)
+            .map(|_| ranked.choose(&mut self.rng).expect("Population is not empty"))
+            .max_by_key(|(_, score)| *score)
+            .expect("Selection is not empty");
+        genome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::programs::all::*;
+
+    #[test]
+    fn genome_test() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for species in [Species::MemoryOne, Species::Fsm(3), Species::Lookup(1, 2)] {
+            let genome = Genome::random(species, &mut rng);
+            let other = Genome::random(species, &mut rng);
+            assert_eq!(genome.genes().len(), species.alleles().len());
+
+            // The text format loads as the same strategy
+            let text = genome.to_string();
+            let loaded = match species {
+                Species::MemoryOne => Player::from_strategy(text.parse::<MemoryOne>().unwrap()),
+                Species::Fsm(_) => Player::from_strategy(text.parse::<FsmStrategy>().unwrap()),
+                Species::Lookup(..) => Player::from_strategy(text.parse::<LookupTable>().unwrap()),
+            };
+            let game = Game::new(30).set_seed(3);
+            assert_eq!(game.record(&genome.player(), &Player::new(random)).unwrap().moves(), game.record(&loaded, &Player::new(random)).unwrap().moves());
+
+            let child = genome.crossover(&other, &mut rng);
+            assert!(child.genes().iter().zip(genome.genes().iter().zip(other.genes())).all(|(gene, (a, b))| gene == a || gene == b));
+
+            assert_eq!(genome.clone().mutate(&mut rng, 0.0), genome);
+            let mutant = genome.clone().mutate(&mut rng, 1.0);
+            assert!(mutant.genes().iter().zip(genome.genes()).all(|(a, b)| a != b));
+            assert!(mutant.genes().iter().zip(species.alleles()).all(|(gene, alleles)| *gene < alleles));
+        }
+    }
+
+    #[test]
+    fn memory_one_test() {
+        // Tit for tat: green, then red only after the opponent's red
+        let genes = [1, 0, 1, 1, 0, 1, 1, 0, 1, 1].to_vec();
+        let genome = Genome { species: Species::MemoryOne, genes };
+        assert_eq!(Some(genome.memory_one()), MemoryOne::preset("tit_for_tat"));
+    }
+
+    #[test]
+    fn evolution_test() {
+        let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("20"), String::from("--seed"), String::from("1")]).unwrap();
+        let roster = [Player::new(friendly), Player::new(tit_for_tat_prisoner), Player::new(evil)];
+
+        let mut evolution = GeneticAlgorithm::new(Species::Fsm(2), 12, 3).set_hall_of_fame(4);
+        let first = evolution.step(&config, &roster).unwrap();
+        assert_eq!(first.number(), 1);
+        assert!(first.worst() as f64 <= first.mean() && first.mean() <= first.best() as f64);
+
+        let mut best = first.best();
+        for _ in 0..5 {
+            let generation = evolution.step(&config, &roster).unwrap();
+            assert_eq!(evolution.population().len(), 12);
+
+            // The elites are kept, so the best fitness never decreases against deterministic players
+            assert!(generation.best() >= best);
+            best = generation.best();
+        }
+
+        let hall_of_fame = evolution.hall_of_fame();
+        assert_eq!(hall_of_fame.len(), 4);
+        assert_eq!(hall_of_fame[0].1, best);
+        assert!(hall_of_fame.windows(2).all(|pair| pair[0].1 >= pair[1].1 && pair[0].0 != pair[1].0));
+    }
+}
@@ -0,0 +1,362 @@
+//! `lookup` contains strategies defined by a table over the last moves of both players.
+//!
+//! A [lookup table](LookupTable) is saved in a simple text format. It has the size of the window over the own and
+//! the opponent's colors, the opening played until the window is filled, and an entry for every window: the own
+//! colors and the opponent's colors (oldest first, `-` for an empty window) and the color to play. A `default`
+//! line fills the entries that are left out:
+//!
+//! ```text
+//! # Tit for two tats
+//! own 0
+//! opponent 2
+//! opening G G
+//! default G
+//! - RR R
+//! ```
+
+use std::{fmt, str::FromStr};
+use rand::Rng;
+use crate::game::{Color, Move, Context, Strategy};
+use crate::strategies::ParseStrategyError;
+
+/// The maximum size of the own and the opponent's window together.
+pub const MAX_WINDOW: usize = 8;
+
+/// A strategy that plays an opening, and then looks up its color by the last colors of both players.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupTable {
+    own: usize,
+    opponent: usize,
+    opening: Vec<Color>,
+    table: Vec<Color>,
+}
+
+impl LookupTable {
+    /// Create a new lookup table that plays the same color in every situation.
+    ///
+    /// # Arguments
+    ///
+    /// * `own` - The amount of own colors looked at
+    /// * `opponent` - The amount of the opponent's colors looked at
+    /// * `color` - The color of the opening and of every entry
+    ///
+    /// # Returns
+    ///
+    /// The table, or `None` if the windows together are larger than [`MAX_WINDOW`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::strategies::lookup::LookupTable;
+    ///
+    /// // Answer two reds in a row with red
+    /// let tit_for_two_tats = LookupTable::new(0, 2, Color::Green).unwrap()
+    ///     .set(&[], &[Color::Red, Color::Red], Color::Red);
+    ///
+    /// assert_eq!(tit_for_two_tats.color(&[(Color::Green, Color::Red), (Color::Green, Color::Red)]), Color::Red);
+    /// assert_eq!(tit_for_two_tats.color(&[(Color::Green, Color::Red)]), Color::Green);
+    /// ```
+    pub fn new(own: usize, opponent: usize, color: Color) -> Option<LookupTable> {
+        if own + opponent > MAX_WINDOW {
+            return None;
+        }
+
+        Some(LookupTable {
+            own,
+            opponent,
+            opening: vec![color; own.max(opponent)],
+            table: vec![color; 3usize.pow((own + opponent) as u32)],
+        })
+    }
+
+    /// Create a random lookup table.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    /// * `own` - The amount of own colors looked at
+    /// * `opponent` - The amount of the opponent's colors looked at
+    ///
+    /// # Returns
+    ///
+    /// The table, or `None` if the windows together are larger than [`MAX_WINDOW`].
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, own: usize, opponent: usize) -> Option<LookupTable> {
+        let mut table = LookupTable::new(own, opponent, Color::Red)?;
+        for color in table.opening.iter_mut().chain(table.table.iter_mut()) {
+            *color = Color::ALL[rng.gen_range(0..3)];
+        }
+
+        Some(table)
+    }
+
+    /// Get the amount of own colors looked at.
+    pub fn own(&self) -> usize {
+        self.own
+    }
+
+    /// Get the amount of the opponent's colors looked at.
+    pub fn opponent(&self) -> usize {
+        self.opponent
+    }
+
+    /// Get the opening, which is played until both windows are filled.
+    pub fn opening(&self) -> &[Color] {
+        &self.opening
+    }
+
+    /// Get every entry, indexed by the own colors and then the opponent's colors (oldest first) as
+    /// the digits of a number in base 3, with red, green and blue as the digits 0, 1 and 2.
+    pub fn entries(&self) -> &[Color] {
+        &self.table
+    }
+
+    /// The index of an entry.
+    fn index(&self, own: &[Color], opponent: &[Color]) -> usize {
+        if own.len() != self.own || opponent.len() != self.opponent {
+            panic!("Expected windows of {0} and {1} colors", self.own, self.opponent);
+        }
+
+        own.iter().chain(opponent).fold(0, |index, color| index * 3 + color.index())
+    }
+
+    /// Get the color played after a window of colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `own` - The last own colors, oldest first
+    /// * `opponent` - The last colors of the opponent, oldest first
+    ///
+    /// # Panics
+    ///
+    /// If the windows do not have the size of the table.
+    pub fn get(&self, own: &[Color], opponent: &[Color]) -> Color {
+        self.table[self.index(own, opponent)]
+    }
+
+    /// Set the color played after a window of colors.
+    ///
+    /// # Panics
+    ///
+    /// If the windows do not have the size of the table.
+    pub fn set(mut self, own: &[Color], opponent: &[Color], color: Color) -> LookupTable {
+        let index = self.index(own, opponent);
+        self.table[index] = color;
+        self
+    }
+
+    /// Set the color of an entry by its index (see [`entries`](LookupTable::entries)).
+    ///
+    /// # Panics
+    ///
+    /// If the entry does not exist.
+    pub fn set_entry(mut self, index: usize, color: Color) -> LookupTable {
+        self.table[index] = color;
+        self
+    }
+
+    /// Set the color of a round of the opening.
+    ///
+    /// # Panics
+    ///
+    /// If the round is not part of the opening.
+    pub fn set_opening(mut self, round: usize, color: Color) -> LookupTable {
+        self.opening[round] = color;
+        self
+    }
+
+    /// Choose a [color](Color) after some [moves](Move).
+    pub fn color(&self, last_moves: &[Move]) -> Color {
+        if let Some(color) = self.opening.get(last_moves.len()) {
+            return *color;
+        }
+
+        let own = last_moves[last_moves.len() - self.own..].iter().map(|m| m.0);
+        let opponent = last_moves[last_moves.len() - self.opponent..].iter().map(|m| m.1);
+        self.table[own.chain(opponent).fold(0, |index, color| index * 3 + color.index())]
+    }
+}
+
+impl Strategy for LookupTable {
+    fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+        self.color(last_moves)
+    }
+}
+
+/// The letters of a window of colors, `-` if it is empty.
+fn letters(colors: &[Color]) -> String {
+    if colors.is_empty() {
+        return String::from("-");
+    }
+
+    colors.iter().map(|color| color.letter()).collect()
+}
+
+/// The colors of the letters of a window, `-` if it is empty.
+fn colors(letters: &str) -> Option<Vec<Color>> {
+    if letters == "-" {
+        return Some(Vec::new());
+    }
+
+    letters.chars().map(Color::from_letter).collect()
+}
+
+impl fmt::Display for LookupTable {
+    /// Write the table in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "own {0}", self.own)?;
+        writeln!(f, "opponent {0}", self.opponent)?;
+        writeln!(f, "opening {0}", self.opening.iter().map(|color| color.letter().to_string()).collect::<Vec<_>>().join(" "))?;
+
+        let size = self.own + self.opponent;
+        for (index, color) in self.table.iter().enumerate() {
+            let window = (0..size)
+                .map(|digit| Color::ALL[index / 3usize.pow((size - 1 - digit) as u32) % 3])
+                .collect::<Vec<_>>();
+            let (own, opponent) = window.split_at(self.own);
+            writeln!(f, "{0} {1} {2}", letters(own), letters(opponent), color.letter())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for LookupTable {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<LookupTable, ParseStrategyError> {
+        let mut own = None;
+        let mut opponent = None;
+        let mut opening = None;
+        let mut default = None;
+        let mut entries = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                [key @ ("own" | "opponent"), value] => {
+                    let slot = if *key == "own" { &mut own } else { &mut opponent };
+                    let value = value.parse::<usize>().map_err(|_| error(&format!("Incorrect value for {key}")))?;
+                    if slot.replace(value).is_some() {
+                        return Err(error(&format!("Duplicate entry: {key}")));
+                    }
+                }
+                ["opening", values @ ..] => {
+                    let values = values.iter().map(|value| colors(value));
+                    let values = values.collect::<Option<Vec<_>>>().ok_or_else(|| error("Invalid opening"))?;
+                    if opening.replace(values.concat()).is_some() {
+                        return Err(error("Duplicate entry: opening"));
+                    }
+                }
+                ["default", color] => {
+                    let color = colors(color).filter(|colors| colors.len() == 1).ok_or_else(|| error("Invalid color"))?;
+                    if default.replace(color[0]).is_some() {
+                        return Err(error("Duplicate entry: default"));
+                    }
+                }
+                [own_letters, opponent_letters, color] => {
+                    let own = colors(own_letters).ok_or_else(|| error("Invalid own colors"))?;
+                    let opponent = colors(opponent_letters).ok_or_else(|| error("Invalid opponent colors"))?;
+                    let color = colors(color).filter(|colors| colors.len() == 1).ok_or_else(|| error("Invalid color"))?;
+                    entries.push((i + 1, own, opponent, color[0]));
+                }
+                _ => return Err(error("Unknown entry")),
+            }
+        }
+
+        let own = own.ok_or_else(|| ParseStrategyError::new(0, "Missing own window"))?;
+        let opponent = opponent.ok_or_else(|| ParseStrategyError::new(0, "Missing opponent window"))?;
+        let opening = opening.unwrap_or_default();
+        if opening.len() != own.max(opponent) {
+            return Err(ParseStrategyError::new(0, &format!("Expected an opening of {0} colors", own.max(opponent))));
+        }
+
+        let mut table = LookupTable::new(own, opponent, Color::Red)
+            .ok_or_else(|| ParseStrategyError::new(0, &format!("Windows larger than {MAX_WINDOW} colors")))?;
+        table.opening = opening;
+
+        let mut filled = vec![false; table.table.len()];
+        for (line, own, opponent, color) in entries {
+            if own.len() != table.own || opponent.len() != table.opponent {
+                return Err(ParseStrategyError::new(line, "Window does not match the size of the table"));
+            }
+
+            let index = table.index(&own, &opponent);
+            if filled[index] {
+                return Err(ParseStrategyError::new(line, "Duplicate entry"));
+            }
+
+            filled[index] = true;
+            table.table[index] = color;
+        }
+
+        for (index, filled) in filled.into_iter().enumerate() {
+            if !filled {
+                table.table[index] = default.ok_or_else(|| ParseStrategyError::new(0, "Missing entries without a default"))?;
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::all::*;
+
+    /// Every possible history of at most `rounds` moves in which the own player plays green.
+    fn histories(rounds: usize) -> Vec<Vec<Move>> {
+        let mut histories = vec![vec![]];
+        for length in 0..rounds {
+            let longer = histories.iter()
+                .filter(|history| history.len() == length)
+                .flat_map(|history| Color::ALL.map(|color| [history.clone(), vec![(Color::Green, color)]].concat()))
+                .collect::<Vec<_>>();
+            histories.extend(longer);
+        }
+
+        histories
+    }
+
+    #[test]
+    fn tit_for_two_tats_test() {
+        let table = "own 0\nopponent 2\nopening G G\ndefault G\n- RR R\n".parse::<LookupTable>().unwrap();
+
+        for history in histories(4) {
+            assert_eq!(table.color(&history), tit_for_two_tats_prisoner(&history), "after {history:?}");
+        }
+    }
+
+    #[test]
+    fn take_back_once_test() {
+        let table = LookupTable::new(1, 1, Color::Green).unwrap()
+            .set(&[Color::Green], &[Color::Red], Color::Red)
+            .set_opening(0, Color::Green);
+
+        for history in histories(3) {
+            assert_eq!(table.color(&history), take_back_once_prisoner(&history), "after {history:?}");
+        }
+    }
+
+    #[test]
+    fn text_format_test() {
+        let table = LookupTable::random(&mut rand::thread_rng(), 2, 1).unwrap();
+        assert_eq!(table.to_string().parse::<LookupTable>(), Ok(table.clone()));
+        assert_eq!(table.set_entry(26, Color::Blue).get(&[Color::Blue, Color::Blue], &[Color::Blue]), Color::Blue);
+        assert!(LookupTable::new(5, 4, Color::Red).is_none());
+
+        let error = "own 1\nopponent 1\nopening G\nG R R\n".parse::<LookupTable>().unwrap_err();
+        assert_eq!(error.to_string(), "Missing entries without a default");
+
+        let error = "own 1\nopponent 1\nopening G\ndefault G\nG RR R\n".parse::<LookupTable>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 5: Window does not match the size of the table");
+
+        let error = "own 0\nopponent 2\nopening G\n".parse::<LookupTable>().unwrap_err();
+        assert_eq!(error.to_string(), "Expected an opening of 2 colors");
+    }
+}
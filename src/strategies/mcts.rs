@@ -0,0 +1,351 @@
+//! `mcts` contains a strategy that plans ahead with Monte Carlo tree search, against a model of its opponent
+//! learned from the moves so far.
+//!
+//! The [model](NGram) predicts the opponent's next color from the last few moves, by how often the opponent
+//! played each color after the same moves earlier in the game. Every move, the [strategy](MctsStrategy) runs a
+//! budget of iterations from the current moves to the expected end of the game. An iteration walks down a tree of
+//! hypothetical moves, choosing its own colors by UCB1 and drawing the opponent's colors from the model, then
+//! plays out the rest of the game with random own colors and scores it by the rules in effect, including the
+//! doubling for the most blue options. The strategy plays the color with the best mean final score. Every
+//! hypothetical round is [charged](Context::charge) as compute cost.
+//!
+//! ```
+//! use tourney::game::{Game, Player};
+//! use tourney::programs::all::*;
+//! use tourney::strategies::MctsStrategy;
+//!
+//! let strategy = MctsStrategy::new().set_iterations(200).set_rounds(50);
+//! let scores = Game::new(50).play(&Player::from_strategy(strategy), &Player::new(tit_for_tat_prisoner)).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use rand::Rng;
+use crate::config::{MAX_ROUNDS, MIN_ROUNDS};
+use crate::game::{Color, Move, Score, Context, Strategy, rng};
+use crate::rules::Rules;
+
+/// A model of the opponent's next color, given the last moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NGram {
+    order: usize,
+    counts: HashMap<Vec<Move>, [u32; 3]>,
+}
+
+impl NGram {
+    /// Count the opponent's colors after every sequence of up to `order` moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The amount of last moves a prediction depends on
+    /// * `last_moves` - The moves, with the own color first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color::*;
+    /// use tourney::strategies::mcts::NGram;
+    ///
+    /// // The opponent answers red with red
+    /// let model = NGram::new(1, &[(Red, Green), (Green, Red), (Red, Green), (Green, Red)]);
+    ///
+    /// assert!(model.predict(&[(Red, Green)])[0] > 0.5);
+    /// ```
+    pub fn new(order: usize, last_moves: &[Move]) -> NGram {
+        let mut counts = HashMap::new();
+        for (round, m) in last_moves.iter().enumerate() {
+            for length in 0..=order.min(round) {
+                let context = last_moves[round - length..round].to_vec();
+                counts.entry(context).or_insert([0; 3])[m.1.index()] += 1;
+            }
+        }
+
+        NGram { order, counts }
+    }
+
+    /// Get the amount of last moves a prediction depends on.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Predict the distribution of the opponent's next color after some moves, in the order red, green, blue.
+    ///
+    /// The longest sequence of last moves that was seen before is used, and every color keeps some probability.
+    /// Without any moves, every color is as likely.
+    pub fn predict(&self, last_moves: &[Move]) -> [f64; 3] {
+        (0..=self.order.min(last_moves.len()))
+            .rev()
+            .find_map(|length| self.counts.get(&last_moves[last_moves.len() - length..]))
+            .map_or([1.0 / 3.0; 3], |counts| {
+                let total = counts.iter().sum::<u32>() as f64 + 1.5;
+                counts.map(|count| (count as f64 + 0.5) / total)
+            })
+    }
+
+    /// Draw the opponent's next color after some moves.
+    fn sample<R: Rng + ?Sized>(&self, last_moves: &[Move], rng: &mut R) -> Color {
+        let distribution = self.predict(last_moves);
+        let x = rng.gen::<f64>();
+        match x {
+            x if x < distribution[0] => Color::Red,
+            x if x < distribution[0] + distribution[1] => Color::Green,
+            _ => Color::Blue,
+        }
+    }
+}
+
+/// A node of the search tree: the statistics of every own color, and the child of every move.
+#[derive(Debug, Default)]
+struct Node {
+    visits: [u32; 3],
+    totals: [f64; 3],
+    children: [[Option<usize>; 3]; 3],
+}
+
+/// The scores and blue options of a hypothetical game.
+#[derive(Debug, Clone, Copy)]
+struct Tally {
+    scores: (Score, Score),
+    blues: (u32, u32),
+}
+
+impl Tally {
+    fn new(last_moves: &[Move], rules: &Rules) -> Tally {
+        let mut tally = Tally { scores: (0, 0), blues: (0, 0) };
+        for m in last_moves {
+            tally.push(*m, rules);
+        }
+
+        tally
+    }
+
+    fn push(&mut self, m: Move, rules: &Rules) {
+        let scores = rules.payoffs().score(m);
+        self.scores = (self.scores.0 + Score::from(scores.0), self.scores.1 + Score::from(scores.1));
+        if rules.doubling() {
+            self.blues = (self.blues.0 + (m.0 == Color::Blue) as u32, self.blues.1 + (m.1 == Color::Blue) as u32);
+        }
+    }
+
+    /// Get the own final score, doubled for the most blue options.
+    fn value(&self) -> f64 {
+        match self.blues.0 > self.blues.1 {
+            true => 2.0 * self.scores.0 as f64,
+            false => self.scores.0 as f64,
+        }
+    }
+}
+
+/// A strategy that plans ahead with Monte Carlo tree search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MctsStrategy {
+    iterations: u32,
+    order: usize,
+    rounds: u32,
+    exploration: f64,
+}
+
+impl Default for MctsStrategy {
+    fn default() -> MctsStrategy {
+        MctsStrategy::new()
+    }
+}
+
+impl MctsStrategy {
+    /// Create a new strategy with 100 iterations per move and a model of order 2, which expects games of the
+    /// default length, the mean of [`MIN_ROUNDS`] and [`MAX_ROUNDS`].
+    pub fn new() -> MctsStrategy {
+        MctsStrategy {
+            iterations: 100,
+            order: 2,
+            rounds: (MIN_ROUNDS + MAX_ROUNDS) / 2,
+            exploration: 2f64.sqrt(),
+        }
+    }
+
+    /// Set the amount of iterations per move.
+    ///
+    /// # Panics
+    ///
+    /// If `iterations` is 0.
+    pub fn set_iterations(mut self, iterations: u32) -> MctsStrategy {
+        if iterations == 0 {
+            panic!("Cannot search without iterations");
+        }
+
+        self.iterations = iterations;
+        self
+    }
+
+    /// Set the amount of last moves the opponent's colors are predicted from (see [`NGram`]).
+    pub fn set_order(mut self, order: usize) -> MctsStrategy {
+        self.order = order;
+        self
+    }
+
+    /// Set the expected amount of rounds of a game. A game that goes on longer is searched one round ahead.
+    pub fn set_rounds(mut self, rounds: u32) -> MctsStrategy {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Set the exploration constant of UCB1, relative to the largest payoff of the remaining rounds.
+    pub fn set_exploration(mut self, exploration: f64) -> MctsStrategy {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Get the amount of iterations per move.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Search for the color with the best mean final score.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_moves` - The moves, with the own color first
+    /// * `rules` - The rules the hypothetical moves are scored by
+    ///
+    /// # Returns
+    ///
+    /// The color, and the amount of hypothetical rounds played in the search.
+    fn search(&self, last_moves: &[Move], rules: &Rules) -> (Color, u64) {
+        let model = NGram::new(self.order, last_moves);
+        let tally = Tally::new(last_moves, rules);
+        let remaining = self.rounds.saturating_sub(last_moves.len() as u32).max(1) as usize;
+
+        // The exploration is scaled to the range of the scores of the remaining rounds, doubled
+        let scale = self.exploration * 2.0 * (rules.payoffs().max_abs() as usize * remaining) as f64;
+
+        let mut rng = rng();
+        let mut tree = vec![Node::default()];
+        let mut moves = last_moves.to_vec();
+        let mut path = Vec::new();
+        let mut rounds = 0;
+
+        for _ in 0..self.iterations {
+            moves.truncate(last_moves.len());
+            path.clear();
+            let mut tally = tally;
+            let mut node = Some(0);
+
+            while moves.len() - last_moves.len() < remaining {
+                let color = match node {
+                    Some(index) => {
+                        let color = ucb(&tree[index], scale);
+                        path.push((index, color));
+                        color
+                    }
+                    None => Color::ALL[rng.gen_range(0..3)],
+                };
+
+                let m = (color, model.sample(&moves, &mut rng));
+                tally.push(m, rules);
+                moves.push(m);
+
+                // Every iteration adds one node to the tree, and plays out the rest of the game randomly
+                node = match node {
+                    Some(index) => match tree[index].children[m.0.index()][m.1.index()] {
+                        Some(child) => Some(child),
+                        None => {
+                            tree.push(Node::default());
+                            let child = tree.len() - 1;
+                            tree[index].children[m.0.index()][m.1.index()] = Some(child);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+            }
+
+            rounds += (moves.len() - last_moves.len()) as u64;
+            let value = tally.value();
+            for (index, color) in &path {
+                tree[*index].visits[color.index()] += 1;
+                tree[*index].totals[color.index()] += value;
+            }
+        }
+
+        let root = &tree[0];
+        let color = Color::ALL.into_iter()
+            .filter(|color| root.visits[color.index()] > 0)
+            .map(|color| (color, root.totals[color.index()] / root.visits[color.index()] as f64))
+            .fold(None, |best: Option<(Color, f64)>, (color, mean)| match best {
+                Some(best) if best.1 >= mean => Some(best),
+                _ => Some((color, mean)),
+            })
+            .map_or(Color::Green, |(color, _)| color);
+
+        (color, rounds)
+    }
+}
+
+impl Strategy for MctsStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let (color, rounds) = self.search(last_moves, context.rules());
+        context.charge(rounds);
+        color
+    }
+}
+
+/// Choose an own color at a node by UCB1, every color once first.
+fn ucb(node: &Node, scale: f64) -> Color {
+    if let Some(color) = Color::ALL.into_iter().find(|color| node.visits[color.index()] == 0) {
+        return color;
+    }
+
+    let visits = node.visits.iter().sum::<u32>() as f64;
+    let bound = |color: Color| {
+        let n = node.visits[color.index()] as f64;
+        node.totals[color.index()] / n + scale * (visits.ln() / n).sqrt()
+    };
+
+    Color::ALL.into_iter()
+        .fold(Color::Red, |best, color| if bound(color) > bound(best) { color } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Player};
+    use crate::programs::all::*;
+
+    #[test]
+    fn ngram_test() {
+        let moves = [(Color::Green, Color::Blue), (Color::Red, Color::Green), (Color::Green, Color::Blue), (Color::Red, Color::Green)];
+        let model = NGram::new(1, &moves);
+
+        // After green against blue the opponent played green, and in general blue and green as often
+        assert!(model.predict(&[(Color::Green, Color::Blue)])[1] > 0.7);
+        let unseen = model.predict(&[(Color::Blue, Color::Blue)]);
+        assert!(unseen[1] == unseen[2] && unseen[0] < unseen[1]);
+        assert_eq!(NGram::new(2, &[]).predict(&moves), [1.0 / 3.0; 3]);
+        assert!((model.predict(&moves).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn search_test() {
+        let strategy = MctsStrategy::new().set_iterations(300).set_rounds(30);
+        let game = Game::new(30).set_seed(3);
+
+        // Red exploits an opponent that always plays green
+        let transcript = game.record(&Player::from_strategy(strategy), &Player::new(friendly)).unwrap();
+        assert!(transcript.moves()[10..].iter().filter(|m| m.0 == Color::Red).count() >= 15);
+
+        // In the last round, blue wins the doubling when the blue options are tied
+        let moves = [(Color::Green, Color::Green); 29];
+        let (color, rounds) = strategy.search(&moves, &Rules::default());
+        assert_eq!((color, rounds), (Color::Blue, 300));
+    }
+
+    #[test]
+    fn charge_test() {
+        let strategy = MctsStrategy::new().set_iterations(10).set_rounds(5);
+        let game = Game::new(5).set_seed(3).set_penalty(1);
+
+        // Every move is charged for the rounds it searched, 10 iterations of 5 to 1 rounds
+        let scores = game.play(&Player::from_strategy(strategy), &Player::new(evil)).unwrap();
+        let unpenalised = Game::new(5).set_seed(3).record(&Player::from_strategy(strategy), &Player::new(evil)).unwrap().scores();
+        assert_eq!(scores.0, unpenalised.0 - 150);
+    }
+}
@@ -0,0 +1,350 @@
+//! `memory_one` contains strategies that only look at the previous move.
+//!
+//! A [memory-one strategy](MemoryOne) is saved in a simple text format, with a [mix](Mix) for the opening and
+//! for each of the nine possible previous moves (own color first). A mix is either the letter of a color or the
+//! weights of red, green and blue:
+//!
+//! ```text
+//! # Tit for tat, but red or green at random after mutual blue
+//! opening G
+//! RR R
+//! RG G
+//! RB G
+//! GR R
+//! GG G
+//! GB G
+//! BR R
+//! BG G
+//! BB 1 1 0
+//! ```
+
+use std::{fmt, str::FromStr};
+use rand::Rng;
+use crate::game::{Color, Move, Context, Strategy};
+use crate::strategies::{Mix, ParseStrategyError};
+
+/// The amount of pure memory-one strategies, which always play the same color in the same situation.
+pub const PURE_STRATEGIES: usize = 59049;
+
+/// The names of the [presets](MemoryOne::preset).
+pub const PRESETS: [&str; 8] = [
+    "friendly",
+    "evil",
+    "blue",
+    "random",
+    "tit_for_tat",
+    "take_back_once",
+    "copy",
+    "chat_gpt_adaptive",
+];
+
+/// A strategy defined by a [mix](Mix) for the opening and a mix for each possible previous [move](Move).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryOne {
+    opening: Mix,
+    responses: [[Mix; 3]; 3],
+}
+
+impl MemoryOne {
+    /// Create a new memory-one strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `opening` - The [mix](Mix) of the first move
+    /// * `responses` - The mixes after each previous move, indexed by the own color and then the opponent's
+    ///   color in the order red, green, blue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::strategies::{Mix, MemoryOne};
+    ///
+    /// // Cooperate, but answer red with red
+    /// let green = Mix::pure(Color::Green);
+    /// let red = Mix::pure(Color::Red);
+    /// let tit_for_tat = MemoryOne::new(green, [[red, green, green]; 3]);
+    ///
+    /// assert_eq!(tit_for_tat.color(&[(Color::Green, Color::Red)]), Color::Red);
+    /// assert_eq!(Some(tit_for_tat), MemoryOne::preset("tit_for_tat"));
+    /// ```
+    pub fn new(opening: Mix, responses: [[Mix; 3]; 3]) -> MemoryOne {
+        MemoryOne {
+            opening,
+            responses,
+        }
+    }
+
+    /// Create a new memory-one strategy that always plays the same color in the same situation.
+    ///
+    /// # Arguments
+    ///
+    /// * `opening` - The color of the first move
+    /// * `responses` - The colors after each previous move, indexed as in [`new`](MemoryOne::new)
+    pub fn pure(opening: Color, responses: [[Color; 3]; 3]) -> MemoryOne {
+        MemoryOne::new(Mix::pure(opening), responses.map(|row| row.map(Mix::pure)))
+    }
+
+    /// Create a random memory-one strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> MemoryOne {
+        let opening = Mix::random(rng);
+        let responses = [(); 3].map(|_| [(); 3].map(|_| Mix::random(rng)));
+        MemoryOne::new(opening, responses)
+    }
+
+    /// Create a random pure memory-one strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    pub fn random_pure<R: Rng + ?Sized>(rng: &mut R) -> MemoryOne {
+        MemoryOne::nth_pure(rng.gen_range(0..PURE_STRATEGIES)).unwrap()
+    }
+
+    /// Get one of the pure memory-one strategies, which may be used to sweep all of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The index of the strategy, less than [`PURE_STRATEGIES`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::Color;
+    /// use tourney::strategies::memory_one::*;
+    ///
+    /// let evil = MemoryOne::nth_pure(0).unwrap();
+    ///
+    /// assert_eq!(Some(evil), MemoryOne::preset("evil"));
+    /// assert!(MemoryOne::nth_pure(PURE_STRATEGIES).is_none());
+    /// ```
+    pub fn nth_pure(n: usize) -> Option<MemoryOne> {
+        if n >= PURE_STRATEGIES {
+            return None;
+        }
+
+        let mut digits = [Color::Red; 10];
+        let mut rest = n;
+        for digit in digits.iter_mut() {
+            *digit = Color::ALL[rest % 3];
+            rest /= 3;
+        }
+
+        let responses = [0, 1, 2].map(|own| [0, 1, 2].map(|opponent| digits[1 + own * 3 + opponent]));
+        Some(MemoryOne::pure(digits[0], responses))
+    }
+
+    /// Get a named memory-one strategy, which plays like the built-in program with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - One of the [`PRESETS`]
+    pub fn preset(name: &str) -> Option<MemoryOne> {
+        use Color::*;
+
+        let strategy = match name {
+            "friendly" => MemoryOne::pure(Green, [[Green; 3]; 3]),
+            "evil" => MemoryOne::pure(Red, [[Red; 3]; 3]),
+            "blue" => MemoryOne::pure(Blue, [[Blue; 3]; 3]),
+            "random" => MemoryOne::new(Mix::uniform(), [[Mix::uniform(); 3]; 3]),
+            "tit_for_tat" => MemoryOne::pure(Green, [[Red, Green, Green]; 3]),
+            "take_back_once" => MemoryOne::pure(Green, [[Green; 3], [Red, Green, Green], [Green; 3]]),
+            "copy" => MemoryOne::new(Mix::uniform(), [[Red, Green, Blue].map(Mix::pure); 3]),
+            "chat_gpt_adaptive" => MemoryOne::new(Mix::uniform(), [[Blue, Red, Green].map(Mix::pure); 3]),
+            _ => return None,
+        };
+
+        Some(strategy)
+    }
+
+    /// Get the [mix](Mix) of the first move.
+    pub fn opening(&self) -> Mix {
+        self.opening
+    }
+
+    /// Get the [mix](Mix) after a previous [move](Move), with the own color first.
+    pub fn response(&self, previous: Move) -> Mix {
+        self.responses[previous.0.index()][previous.1.index()]
+    }
+
+    /// Set the [mix](Mix) of the first move.
+    pub fn set_opening(mut self, opening: Mix) -> MemoryOne {
+        self.opening = opening;
+        self
+    }
+
+    /// Set the [mix](Mix) after a previous [move](Move), with the own color first.
+    pub fn set_response(mut self, previous: Move, response: Mix) -> MemoryOne {
+        self.responses[previous.0.index()][previous.1.index()] = response;
+        self
+    }
+
+    /// Whether the strategy always plays the same color in the same situation.
+    pub fn is_pure(&self) -> bool {
+        self.opening.as_pure().is_some() && self.responses.iter().flatten().all(|mix| mix.as_pure().is_some())
+    }
+
+    /// Choose a [color](Color) after some [moves](Move).
+    pub fn color(&self, last_moves: &[Move]) -> Color {
+        match last_moves.last() {
+            Some(previous) => self.response(*previous).sample(),
+            None => self.opening.sample(),
+        }
+    }
+}
+
+impl Strategy for MemoryOne {
+    fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+        self.color(last_moves)
+    }
+}
+
+impl fmt::Display for MemoryOne {
+    /// Write the strategy in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "opening {0}", self.opening)?;
+
+        for own in Color::ALL {
+            for opponent in Color::ALL {
+                writeln!(f, "{0}{1} {2}", own.letter(), opponent.letter(), self.response((own, opponent)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for MemoryOne {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<MemoryOne, ParseStrategyError> {
+        let mut opening = None;
+        let mut responses = [[None; 3]; 3];
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let mix = value.parse::<Mix>().map_err(|reason| error(&reason))?;
+
+            let slot = match key {
+                "opening" => &mut opening,
+                _ => {
+                    let mut letters = key.chars().map(Color::from_letter);
+                    match (letters.next().flatten(), letters.next().flatten(), letters.next()) {
+                        (Some(own), Some(opponent), None) => &mut responses[own.index()][opponent.index()],
+                        _ => return Err(error("Expected opening or a move, e.g. RG")),
+                    }
+                }
+            };
+
+            if slot.replace(mix).is_some() {
+                return Err(error(&format!("Duplicate entry: {key}")));
+            }
+        }
+
+        let opening = opening.ok_or_else(|| ParseStrategyError::new(0, "Missing opening"))?;
+        let mut strategy = MemoryOne::new(opening, [[opening; 3]; 3]);
+        for own in Color::ALL {
+            for opponent in Color::ALL {
+                let response = responses[own.index()][opponent.index()].ok_or_else(|| {
+                    ParseStrategyError::new(0, &format!("Missing move: {0}{1}", own.letter(), opponent.letter()))
+                })?;
+                strategy = strategy.set_response((own, opponent), response);
+            }
+        }
+
+        Ok(strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Program;
+    use crate::programs::all::*;
+
+    /// Every possible history of at most one move.
+    fn histories() -> Vec<Vec<Move>> {
+        let mut histories = vec![vec![]];
+        for own in Color::ALL {
+            for opponent in Color::ALL {
+                histories.push(vec![(own, opponent)]);
+            }
+        }
+
+        histories
+    }
+
+    #[test]
+    fn preset_test() {
+        let programs: [(&str, Program); 8] = [
+            ("friendly", friendly),
+            ("evil", evil),
+            ("blue", blue),
+            ("random", random),
+            ("tit_for_tat", tit_for_tat_prisoner),
+            ("take_back_once", take_back_once_prisoner),
+            ("copy", copy),
+            ("chat_gpt_adaptive", chat_gpt_adaptive),
+        ];
+
+        for (name, program) in programs {
+            let strategy = MemoryOne::preset(name).unwrap();
+            for history in histories() {
+                let expected = Mix::pure(program(&history));
+                let mix = match history.last() {
+                    Some(previous) => strategy.response(*previous),
+                    None => strategy.opening(),
+                };
+
+                // Randomised programs are compared on the moves they play deterministically
+                if mix.as_pure().is_some() {
+                    assert_eq!(mix, expected, "{name} after {history:?}");
+                } else {
+                    assert!(mix.probability(program(&history)) > 0.0, "{name} after {history:?}");
+                }
+            }
+        }
+
+        assert!(MemoryOne::preset("tit_for_tat").unwrap().is_pure());
+        assert!(!MemoryOne::preset("copy").unwrap().is_pure());
+        assert!(MemoryOne::preset("unknown").is_none());
+    }
+
+    #[test]
+    fn nth_pure_test() {
+        let last = MemoryOne::nth_pure(PURE_STRATEGIES - 1).unwrap();
+        assert_eq!(Some(last), MemoryOne::preset("blue"));
+
+        let strategy = MemoryOne::random_pure(&mut rand::thread_rng());
+        assert!(strategy.is_pure());
+    }
+
+    #[test]
+    fn text_format_test() {
+        let strategy = MemoryOne::random(&mut rand::thread_rng());
+        assert_eq!(strategy.to_string().parse::<MemoryOne>(), Ok(strategy));
+
+        let tit_for_tat = MemoryOne::preset("tit_for_tat").unwrap();
+        let text = tit_for_tat.to_string().replace("BB G", "BB 0 1 0  # green");
+        assert_eq!(text.parse::<MemoryOne>(), Ok(tit_for_tat));
+
+        let error = "opening G\nRR R\nRR G\n".parse::<MemoryOne>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 3: Duplicate entry: RR");
+
+        let error = "opening G\nRX R\n".parse::<MemoryOne>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Expected opening or a move, e.g. RG");
+
+        let error = "opening G\nRR R\n".parse::<MemoryOne>().unwrap_err();
+        assert_eq!(error.to_string(), "Missing move: RG");
+    }
+}
@@ -0,0 +1,460 @@
+//! `mlp` contains strategies played by a small neural network, a multilayer perceptron, and an evolution strategy
+//! to train them.
+//!
+//! The [network](Mlp) is given features of the moves so far:
+//!
+//! * The last `memory` moves, the most recent first: the own and the opponent's color, one-hot encoded, or zeros
+//!   before the first rounds
+//! * How often each player played red, green and blue
+//! * The difference of the scores by the rules in effect, as `tanh(difference / 10)`
+//! * The difference of the amount of blue colors played, as `tanh(difference / 3)`
+//!
+//! The hidden layers use `tanh`, and the network plays the color of the highest of its three outputs.
+//!
+//! A network is saved in a simple text format: the memory, the sizes of the hidden layers and a line of weights
+//! for every neuron, layer by layer, with the bias last:
+//!
+//! ```text
+//! # Evolved against the programs
+//! memory 1
+//! hidden 2
+//! weights 0.5 -1.2 0 0.1 0.3 -0.4 0 0 0 0 0 0 0 0 0.2
+//! weights ...
+//! ```
+
+use std::{fmt, str::FromStr};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use crate::config::{Config, run};
+use crate::game::{Color, Move, Score, Context, Player, Strategy};
+use crate::history::{History, Side};
+use crate::rules::Rules;
+use crate::strategies::ParseStrategyError;
+
+/// The maximum amount of last moves given to a network.
+pub const MAX_MEMORY: usize = 8;
+/// The maximum amount of hidden layers.
+pub const MAX_LAYERS: usize = 4;
+/// The maximum amount of neurons in a hidden layer.
+pub const MAX_NEURONS: usize = 64;
+
+/// A multilayer perceptron that picks a color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mlp {
+    memory: usize,
+    hidden: Vec<usize>,
+    weights: Vec<f64>,
+}
+
+impl Mlp {
+    /// Create a new network with every weight 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The amount of last moves given to the network
+    /// * `hidden` - The amount of neurons in every hidden layer
+    ///
+    /// # Returns
+    ///
+    /// The network, or `None` if `memory`, the amount of hidden layers or the size of a hidden layer is larger
+    /// than its maximum, or a hidden layer is empty.
+    pub fn new(memory: usize, hidden: &[usize]) -> Option<Mlp> {
+        if memory > MAX_MEMORY || hidden.len() > MAX_LAYERS || hidden.iter().any(|size| !(1..=MAX_NEURONS).contains(size)) {
+            return None;
+        }
+
+        let mut mlp = Mlp {
+            memory,
+            hidden: hidden.to_vec(),
+            weights: Vec::new(),
+        };
+        mlp.weights = vec![0.0; mlp.layers().map(|(inputs, outputs)| (inputs + 1) * outputs).sum()];
+
+        Some(mlp)
+    }
+
+    /// Get the amount of last moves given to the network.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Get the amount of neurons in every hidden layer.
+    pub fn hidden(&self) -> &[usize] {
+        &self.hidden
+    }
+
+    /// Get every weight, layer by layer and neuron by neuron, with the bias of a neuron after its weights.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Set every weight, in the order of [`Mlp::weights`].
+    ///
+    /// # Panics
+    ///
+    /// If the amount of weights does not match the network.
+    pub fn set_weights(mut self, weights: Vec<f64>) -> Mlp {
+        if weights.len() != self.weights.len() {
+            panic!("Expected {0} weights, got {1}", self.weights.len(), weights.len());
+        }
+
+        self.weights = weights;
+        self
+    }
+
+    /// Get the amount of inputs of the network.
+    fn inputs(&self) -> usize {
+        6 * self.memory + 8
+    }
+
+    /// Get the amount of inputs and outputs of every layer.
+    fn layers(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let sizes = [self.inputs()].into_iter().chain(self.hidden.iter().copied()).chain([3]);
+        sizes.clone().zip(sizes.skip(1))
+    }
+
+    /// Get the features of the moves.
+    fn features(&self, last_moves: &[Move], rules: &Rules) -> Vec<f64> {
+        let mut features = Vec::with_capacity(self.inputs());
+
+        for m in last_moves.iter().rev().map(Some).chain(std::iter::repeat(None)).take(self.memory) {
+            let mut one_hot = [0.0; 6];
+            if let Some(m) = m {
+                one_hot[m.0.index()] = 1.0;
+                one_hot[3 + m.1.index()] = 1.0;
+            }
+
+            features.extend(one_hot);
+        }
+
+        let (counts, blue) = History::cached(last_moves, |history| {
+            let played = history.len().max(1) as f64;
+            let counts = [Side::Own, Side::Opponent].map(|side| Color::ALL.map(|color| history.count(side, color) as f64 / played));
+            let blue = history.count(Side::Own, Color::Blue) as f64 - history.count(Side::Opponent, Color::Blue) as f64;
+
+            (counts, blue)
+        });
+        features.extend(counts.concat());
+
+        let scores = rules.calculate_scores(last_moves);
+        features.push(((scores.0 - scores.1) as f64 / 10.0).tanh());
+        features.push((blue / 3.0).tanh());
+
+        features
+    }
+
+    /// Get the outputs of the network for red, green and blue.
+    fn outputs(&self, features: Vec<f64>) -> Vec<f64> {
+        let mut values = features;
+        let mut weights = self.weights.as_slice();
+        let layers = self.hidden.len() + 1;
+
+        for (layer, (inputs, outputs)) in self.layers().enumerate() {
+            let (current, rest) = weights.split_at((inputs + 1) * outputs);
+            values = current.chunks(inputs + 1)
+                .map(|neuron| {
+                    let sum = neuron[inputs] + neuron.iter().zip(&values).map(|(weight, value)| weight * value).sum::<f64>();
+                    if layer + 1 < layers { sum.tanh() } else { sum }
+                })
+                .collect();
+            weights = rest;
+        }
+
+        values
+    }
+
+    /// Get the color the network plays after the moves. A tie goes to the earlier color of red, green and blue.
+    pub fn color(&self, last_moves: &[Move], rules: &Rules) -> Color {
+        let outputs = self.outputs(self.features(last_moves, rules));
+
+        Color::ALL.into_iter()
+            .fold(Color::Red, |best, color| if outputs[color.index()] > outputs[best.index()] { color } else { best })
+    }
+}
+
+impl Strategy for Mlp {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        self.color(last_moves, context.rules())
+    }
+}
+
+impl fmt::Display for Mlp {
+    /// Write the network in the text format it is [parsed](FromStr) from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "memory {0}", self.memory)?;
+        writeln!(f, "hidden {0}", self.hidden.iter().map(usize::to_string).collect::<Vec<_>>().join(" "))?;
+
+        let mut weights = self.weights.as_slice();
+        for (inputs, outputs) in self.layers() {
+            for _ in 0..outputs {
+                let (neuron, rest) = weights.split_at(inputs + 1);
+                writeln!(f, "weights {0}", neuron.iter().map(f64::to_string).collect::<Vec<_>>().join(" "))?;
+                weights = rest;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Mlp {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<Mlp, ParseStrategyError> {
+        let mut memory = None;
+        let mut hidden = None;
+        let mut weights = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => {}
+                ["memory", value] => {
+                    let value = value.parse::<usize>().map_err(|_| error("Incorrect value for memory"))?;
+                    if memory.replace(value).is_some() {
+                        return Err(error("Duplicate entry: memory"));
+                    }
+                }
+                ["hidden", sizes @ ..] => {
+                    let sizes = sizes.iter().map(|size| size.parse::<usize>().ok()).collect::<Option<Vec<_>>>();
+                    let sizes = sizes.ok_or_else(|| error("Incorrect value for hidden"))?;
+                    if hidden.replace(sizes).is_some() {
+                        return Err(error("Duplicate entry: hidden"));
+                    }
+                }
+                ["weights", values @ ..] => {
+                    let values = values.iter()
+                        .map(|value| value.parse::<f64>().ok().filter(|value| value.is_finite()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("Invalid weight"))?;
+                    weights.extend(values);
+                }
+                _ => return Err(error("Unknown entry")),
+            }
+        }
+
+        let memory = memory.ok_or_else(|| ParseStrategyError::new(0, "Missing memory"))?;
+        let mlp = Mlp::new(memory, &hidden.unwrap_or_default())
+            .ok_or_else(|| ParseStrategyError::new(0, "Network larger than the maximum"))?;
+
+        if weights.len() != mlp.weights.len() {
+            let reason = format!("Expected {0} weights, found {1}", mlp.weights.len(), weights.len());
+            return Err(ParseStrategyError::new(0, &reason));
+        }
+
+        Ok(mlp.set_weights(weights))
+    }
+}
+
+/// Get the total score of a network in a tournament against a roster, played by [`run`].
+///
+/// # Errors
+///
+/// If the tournament cannot be run.
+pub fn fitness(mlp: &Mlp, config: &Config, roster: &[Player]) -> Result<Score, String> {
+    let name = "Evolving network";
+    let players = [Player::from_strategy(mlp.clone()).set_name(name)].into_iter()
+        .chain(roster.iter().cloned())
+        .collect::<Vec<_>>();
+
+    let scores = run(config, &players)?;
+    scores.iter()
+        .find(|score| score.2 == name)
+        .map(|score| score.0)
+        .ok_or_else(|| String::from("Missing network in the tournament"))
+}
+
+/// An evolution strategy over the weights of a network.
+///
+/// Every generation samples mirrored pairs of networks around a mean, with normally distributed changes to every
+/// weight, and measures their [fitness]. The mean moves to a weighted mean of the better half, where better
+/// networks weigh more, like the recombination of CMA-ES.
+#[derive(Debug, Clone)]
+pub struct Evolution {
+    mean: Mlp,
+    best: Option<(Mlp, Score)>,
+    sigma: f64,
+    population: usize,
+    rng: StdRng,
+}
+
+impl Evolution {
+    /// Create a new evolution strategy, with 16 networks per generation and a step size of 0.5.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The network to start from
+    /// * `seed` - The seed of the changes to the weights
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tourney::config::Config;
+    /// use tourney::game::Player;
+    /// use tourney::programs::all::*;
+    /// use tourney::strategies::mlp::{Evolution, Mlp};
+    ///
+    /// let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("50"), String::from("--seed"), String::from("1")]).unwrap();
+    /// let roster = [Player::new(tit_for_tat_prisoner), Player::new(evil), Player::new(friendly)];
+    ///
+    /// let mut evolution = Evolution::new(Mlp::new(2, &[8]).unwrap(), 7);
+    /// for _ in 0..20 {
+    ///     evolution.step(&config, &roster).unwrap();
+    /// }
+    ///
+    /// let (best, score) = evolution.best().unwrap();
+    /// ```
+    pub fn new(start: Mlp, seed: u64) -> Evolution {
+        Evolution {
+            mean: start,
+            best: None,
+            sigma: 0.5,
+            population: 16,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Set the standard deviation of the changes to every weight.
+    pub fn set_sigma(mut self, sigma: f64) -> Evolution {
+        self.sigma = sigma;
+        self
+    }
+
+    /// Set the amount of networks per generation, rounded up to an even amount.
+    ///
+    /// # Panics
+    ///
+    /// If `population` is 0.
+    pub fn set_population(mut self, population: usize) -> Evolution {
+        if population == 0 {
+            panic!("Cannot evolve without a population");
+        }
+
+        self.population = population.div_ceil(2) * 2;
+        self
+    }
+
+    /// Get the mean of the networks.
+    pub fn mean(&self) -> &Mlp {
+        &self.mean
+    }
+
+    /// Get the best network so far and its fitness, `None` before the first generation.
+    pub fn best(&self) -> Option<(&Mlp, Score)> {
+        self.best.as_ref().map(|(mlp, score)| (mlp, *score))
+    }
+
+    /// Run a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the tournaments
+    /// * `roster` - The players every network plays against
+    ///
+    /// # Returns
+    ///
+    /// The best fitness of the generation.
+    ///
+    /// # Errors
+    ///
+    /// If a tournament cannot be run.
+    pub fn step(&mut self, config: &Config, roster: &[Player]) -> Result<Score, String> {
+        let mut candidates = Vec::with_capacity(self.population);
+        for _ in 0..self.population / 2 {
+            let noise = self.mean.weights.iter().map(|_| self.sigma * normal(&mut self.rng)).collect::<Vec<_>>();
+            for sign in [1.0, -1.0] {
+                let weights = self.mean.weights.iter().zip(&noise).map(|(weight, noise)| weight + sign * noise).collect();
+                let mlp = self.mean.clone().set_weights(weights);
+                let score = fitness(&mlp, config, roster)?;
+                candidates.push((mlp, score));
+            }
+        }
+
+        candidates.sort_by_key(|candidate| -candidate.1);
+
+        let parents = candidates.len() / 2;
+        let ranks = (0..parents).map(|i| ((parents as f64 + 0.5).ln() - ((i + 1) as f64).ln()).max(0.0)).collect::<Vec<_>>();
+        let total = ranks.iter().sum::<f64>();
+        let mut mean = vec![0.0; self.mean.weights.len()];
+        for ((mlp, _), rank) in candidates.iter().zip(&ranks) {
+            for (sum, weight) in mean.iter_mut().zip(&mlp.weights) {
+                *sum += rank / total * weight;
+            }
+        }
+        self.mean = self.mean.clone().set_weights(mean);
+
+        let best = candidates.swap_remove(0);
+        let score = best.1;
+        if self.best.as_ref().is_none_or(|(_, current)| score > *current) {
+            self.best = Some(best);
+        }
+
+        Ok(score)
+    }
+}
+
+/// Draw from the standard normal distribution.
+fn normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let (u, v) = (1.0 - rng.gen::<f64>(), rng.gen::<f64>());
+    (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::programs::all::*;
+
+    #[test]
+    fn parse_test() {
+        let mlp = Mlp::new(1, &[2]).unwrap();
+        let weights = (0..mlp.weights().len()).map(|i| i as f64 / 10.0 - 1.0).collect::<Vec<_>>();
+        let mlp = mlp.set_weights(weights);
+
+        assert_eq!(mlp.to_string().parse::<Mlp>(), Ok(mlp));
+        assert_eq!("memory 1\nhidden\nweights 1 2\n".parse::<Mlp>(), Err(ParseStrategyError::new(0, "Expected 45 weights, found 2")));
+        assert_eq!("memory 9\n".parse::<Mlp>(), Err(ParseStrategyError::new(0, "Network larger than the maximum")));
+        assert_eq!("memory 1\nweights 1 x\n".parse::<Mlp>(), Err(ParseStrategyError::new(2, "Invalid weight")));
+    }
+
+    #[test]
+    fn features_test() {
+        // Without hidden layers, the output of red copies the opponent's last red and green copies its last green
+        let mut weights = vec![0.0; 3 * 15];
+        weights[3] = 1.0;
+        weights[15 + 4] = 1.0;
+        weights[15 + 14] = 0.5;
+        let mlp = Mlp::new(1, &[]).unwrap().set_weights(weights);
+
+        for opponent in [random, evil, friendly] {
+            let game = Game::new(30).set_seed(3);
+            let expected = game.record(&Player::new(tit_for_tat_prisoner), &Player::new(opponent)).unwrap();
+            let actual = game.record(&Player::from_strategy(mlp.clone()), &Player::new(opponent)).unwrap();
+
+            assert_eq!(actual.moves(), expected.moves());
+        }
+
+        let features = mlp.features(&[(Color::Blue, Color::Red), (Color::Blue, Color::Blue)], &Rules::default());
+        assert_eq!(features[..6], [0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(features[6..12], [0.0, 0.0, 1.0, 0.5, 0.0, 0.5]);
+        assert_eq!(features[12..], [(-0.2f64).tanh(), (1.0f64 / 3.0).tanh()]);
+    }
+
+    #[test]
+    fn evolution_test() {
+        let config = Config::new(&[String::from("tourney"), String::from("--max"), String::from("20"), String::from("--seed"), String::from("1")]).unwrap();
+        let roster = [Player::new(friendly), Player::new(tit_for_tat_prisoner)];
+
+        let mut evolution = Evolution::new(Mlp::new(1, &[4]).unwrap(), 3).set_population(8);
+        let first = evolution.step(&config, &roster).unwrap();
+        for _ in 0..5 {
+            evolution.step(&config, &roster).unwrap();
+        }
+
+        let (best, score) = evolution.best().unwrap();
+        assert!(score >= first);
+        assert_eq!(fitness(best, &config, &roster), Ok(score));
+    }
+}
@@ -0,0 +1,267 @@
+//! `plugin` loads strategies from native plugins, dynamic libraries with a stable C ABI, so that strategies can be
+//! shipped without changing the crate.
+//!
+//! A plugin exports a function `tourney_plugin`, which describes every strategy in the plugin:
+//!
+//! ```c
+//! #include <stddef.h>
+//! #include <stdint.h>
+//!
+//! typedef struct {
+//!     const char *id;          // Required, e.g. "tit_for_tat"
+//!     const char *name;        // The id if NULL
+//!     const char *author;      // May be NULL
+//!     const char *description; // May be NULL
+//!     uint8_t deterministic;   // 1 if the same moves always give the same color
+//!     int32_t memory;          // How many of the last moves are looked at, -1 for the whole history
+//!     int32_t (*next_move)(const uint8_t *moves, size_t len, uint64_t seed);
+//! } TourneyStrategy;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;    // TOURNEY_ABI_VERSION
+//!     uint32_t count;
+//!     const TourneyStrategy *strategies;
+//! } TourneyPlugin;
+//!
+//! const TourneyPlugin *tourney_plugin(void);
+//! ```
+//!
+//! The description must stay valid while the library is loaded. `next_move` is given the moves packed as two bytes
+//! per move, the own color first, with 0 for red, 1 for green and 2 for blue, and returns a color in the same
+//! encoding. The seed is drawn from the [game's random number generator](rng), so a plugin that only draws from it
+//! plays the same game for the same seed. Any other return value forfeits the move, and red is played instead.
+//! `next_move` may be called from several threads at once.
+//!
+//! A plugin is trusted like the rest of the tournament: it runs in the tournament process, without the limits of a
+//! [WebAssembly module](super::WasmStrategy).
+
+use std::{ffi::{CStr, c_char}, fmt, path::Path, slice, sync::Arc};
+use libloading::Library;
+use rand::Rng;
+use crate::game::{Color, Move, Context, Player, Strategy, rng};
+use crate::registry::Entry;
+
+/// The version of the ABI, which is increased on every change to the layout of [`RawPlugin`] or [`RawStrategy`].
+pub const ABI_VERSION: u32 = 1;
+
+/// The type definition for the function playing a strategy of a plugin.
+pub type NextMove = unsafe extern "C" fn(moves: *const u8, len: usize, seed: u64) -> i32;
+
+/// The description of a strategy, `TourneyStrategy` in C.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawStrategy {
+    pub id: *const c_char,
+    pub name: *const c_char,
+    pub author: *const c_char,
+    pub description: *const c_char,
+    pub deterministic: u8,
+    pub memory: i32,
+    pub next_move: Option<NextMove>,
+}
+
+/// The description of a plugin, `TourneyPlugin` in C.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawPlugin {
+    pub abi_version: u32,
+    pub count: u32,
+    pub strategies: *const RawStrategy,
+}
+
+// The descriptions are only ever read, which lets a plugin written in Rust keep them in a static
+unsafe impl Sync for RawStrategy {}
+unsafe impl Sync for RawPlugin {}
+
+/// A strategy played by a plugin, which keeps the library loaded.
+#[derive(Clone)]
+struct PluginStrategy {
+    _library: Arc<Library>,
+    next_move: NextMove,
+}
+
+impl Strategy for PluginStrategy {
+    fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+        let bytes = last_moves.iter()
+            .flat_map(|m| [m.0.index() as u8, m.1.index() as u8])
+            .collect::<Vec<_>>();
+
+        // SAFETY: The library is kept loaded, and the function is given a buffer of the length it is told
+        let color = unsafe { (self.next_move)(bytes.as_ptr(), bytes.len(), rng().gen()) };
+        usize::try_from(color).ok().and_then(|index| Color::ALL.get(index).copied()).unwrap_or(Color::Red)
+    }
+}
+
+impl fmt::Debug for PluginStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginStrategy").finish_non_exhaustive()
+    }
+}
+
+/// Load every strategy of a plugin.
+///
+/// # Arguments
+///
+/// * `path` - The path of the dynamic library
+///
+/// # Errors
+///
+/// If the library cannot be loaded, if it does not export `tourney_plugin`, if it was built for another
+/// [version of the ABI](ABI_VERSION), or if a description is invalid.
+pub fn load(path: &Path) -> Result<Vec<Entry>, String> {
+    let invalid = |reason: &str| format!("Invalid plugin {0} ({1})", path.display(), reason);
+
+    // SAFETY: Loading the library runs its initialisers, which are trusted like the rest of the plugin
+    let library = unsafe { Library::new(path) }
+        .map_err(|error| format!("Could not load {0} ({1})", path.display(), error))?;
+    let library = Arc::new(library);
+
+    // SAFETY: The description is only read while the library is loaded, and the version is checked before
+    // anything else is read
+    let plugin = unsafe {
+        let describe = library.get::<unsafe extern "C" fn() -> *const RawPlugin>(b"tourney_plugin\0")
+            .map_err(|_| invalid("Missing export: tourney_plugin"))?;
+        describe().as_ref().ok_or_else(|| invalid("Missing description"))?
+    };
+
+    if plugin.abi_version != ABI_VERSION {
+        return Err(format!(
+            "Incompatible plugin {0} (built for ABI version {1}, expected version {2})",
+            path.display(),
+            plugin.abi_version,
+            ABI_VERSION,
+        ));
+    }
+
+    let strategies = match (plugin.count, plugin.strategies.is_null()) {
+        (0, _) => &[][..],
+        (_, true) => return Err(invalid("Missing strategies")),
+        // SAFETY: The plugin promises `count` descriptions
+        (count, false) => unsafe { slice::from_raw_parts(plugin.strategies, count as usize) },
+    };
+
+    let text = |ptr: *const c_char| match ptr.is_null() {
+        true => Ok(None),
+        // SAFETY: The plugin promises strings terminated by a 0
+        false => unsafe { CStr::from_ptr(ptr) }.to_str()
+            .map(|text| Some(String::from(text)))
+            .map_err(|_| invalid("Strings must be UTF-8")),
+    };
+
+    strategies.iter()
+        .map(|raw| {
+            let id = text(raw.id)?.filter(|id| !id.is_empty()).ok_or_else(|| invalid("Missing id"))?;
+            let next_move = raw.next_move.ok_or_else(|| invalid(&format!("Missing next_move for {id}")))?;
+            let name = text(raw.name)?.unwrap_or_else(|| id.clone());
+
+            let strategy = PluginStrategy { _library: Arc::clone(&library), next_move };
+            Ok(Entry::new(&id, &name, Arc::new(move || Player::from_strategy(strategy.clone())))
+                .set_author(&text(raw.author)?.unwrap_or_default())
+                .set_description(&text(raw.description)?.unwrap_or_default())
+                .set_deterministic(raw.deterministic != 0)
+                .set_memory(usize::try_from(raw.memory).ok()))
+        })
+        .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, path::PathBuf, process::Command};
+    use super::*;
+    use crate::game::Game;
+    use crate::programs::all::*;
+
+    /// Tit for tat and a seeded random strategy, with the ABI version as `VERSION`.
+    const PLUGIN: &str = r#"
+        #include <stddef.h>
+        #include <stdint.h>
+
+        typedef struct {
+            const char *id;
+            const char *name;
+            const char *author;
+            const char *description;
+            uint8_t deterministic;
+            int32_t memory;
+            int32_t (*next_move)(const uint8_t *moves, size_t len, uint64_t seed);
+        } TourneyStrategy;
+
+        typedef struct {
+            uint32_t abi_version;
+            uint32_t count;
+            const TourneyStrategy *strategies;
+        } TourneyPlugin;
+
+        static int32_t tit_for_tat(const uint8_t *moves, size_t len, uint64_t seed) {
+            return len == 0 ? 1 : (moves[len - 1] == 0 ? 0 : 1);
+        }
+
+        static int32_t seeded(const uint8_t *moves, size_t len, uint64_t seed) {
+            return (int32_t)(seed % 3);
+        }
+
+        static const TourneyStrategy strategies[] = {
+            { "c_tit_for_tat", "C tit for tat", "Plugin", "Copies red", 1, 1, tit_for_tat },
+            { "c_seeded", NULL, NULL, NULL, 0, -1, seeded },
+        };
+
+        static const TourneyPlugin plugin = { VERSION, 2, strategies };
+
+        const TourneyPlugin *tourney_plugin(void) {
+            return &plugin;
+        }
+    "#;
+
+    /// Build the plugin for an ABI version into a temporary directory.
+    fn build(name: &str, version: u32) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tourney_plugin_{name}"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("plugin.c"), PLUGIN).unwrap();
+
+        let path = dir.join(format!("plugin.{0}", std::env::consts::DLL_EXTENSION));
+        let status = Command::new("cc")
+            .args(["-shared", "-fPIC", &format!("-DVERSION={version}"), "-o"])
+            .arg(&path)
+            .arg(dir.join("plugin.c"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        path
+    }
+
+    #[test]
+    fn load_test() {
+        let path = build("load", ABI_VERSION);
+        let entries = load(&path).unwrap();
+
+        assert_eq!(entries.iter().map(Entry::id).collect::<Vec<_>>(), ["c_tit_for_tat", "c_seeded"]);
+        assert_eq!((entries[0].name(), entries[0].author(), entries[0].memory()), ("C tit for tat", "Plugin", Some(1)));
+        assert_eq!((entries[1].name(), entries[1].deterministic(), entries[1].memory()), ("c_seeded", false, None));
+
+        for opponent in [random, evil, blue] {
+            let game = Game::new(50).set_seed(3);
+            let expected = game.record(&Player::new(tit_for_tat_prisoner), &Player::new(opponent)).unwrap();
+            let actual = game.record(&entries[0].player(), &Player::new(opponent)).unwrap();
+
+            assert_eq!(actual.moves(), expected.moves());
+        }
+
+        let game = Game::new(20).set_seed(9);
+        let seeded = entries[1].player();
+        assert_eq!(game.record(&seeded, &Player::new(evil)).unwrap().moves(), game.record(&seeded, &Player::new(evil)).unwrap().moves());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn version_test() {
+        let path = build("version", ABI_VERSION + 1);
+        let error = load(&path).unwrap_err();
+
+        assert!(error.starts_with("Incompatible plugin"));
+        assert!(error.ends_with(&format!("(built for ABI version {0}, expected version {1})", ABI_VERSION + 1, ABI_VERSION)));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,463 @@
+//! `process` contains strategies that run as a child process, so they can be written in any language.
+//!
+//! The process is started for every game and speaks a line protocol over its standard input and output:
+//!
+//! 1. The engine sends `start payoffs <9 payoffs> doubling <on|off>` with the initial rules (the payoffs as in a
+//!    [schedule](crate::rules)), and the process replies `ready`.
+//! 2. Every turn the engine sends `move <last move>`, where the last move is the own and the opponent's color of
+//!    the previous round (e.g. `GR`), or `-` in the first round. The process replies with its color, `R`, `G`
+//!    or `B`. When announced rules change, `rules payoffs <9 payoffs> doubling <on|off>` is sent before the move.
+//! 3. After the last round the engine sends `end <last move> <own score> <opponent score>` and closes the input.
+//!
+//! A process that does not start, crashes, replies something else or misses the timeout is stopped, and the
+//! fallback color is played for the rest of the game. A strategy file has the command (split on whitespace and
+//! run from the directory of the file), and optionally the timeouts in milliseconds and the fallback color:
+//!
+//! ```text
+//! # Copy the opponent, in Python
+//! command python3 copy.py
+//! timeout 100
+//! startup 5000
+//! fallback R
+//! ```
+
+use std::{fmt, str::FromStr, thread};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Mutex, mpsc::{self, Receiver}};
+use std::time::{Duration, Instant};
+use crate::game::{Color, Move, Score, Context, Strategy};
+use crate::rules::Rules;
+use crate::strategies::ParseStrategyError;
+
+/// A running process of a game.
+struct Session {
+    child: Option<Child>,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<String>,
+    rules: Rules,
+    history: Vec<Move>,
+}
+
+impl Session {
+    /// Start a process and shake hands, giving a stopped session if that fails.
+    fn start(strategy: &ProcessStrategy, rules: &Rules) -> Session {
+        let (sender, lines) = mpsc::channel();
+        let mut session = Session {
+            child: None,
+            stdin: None,
+            lines,
+            rules: *rules,
+            history: Vec::new(),
+        };
+
+        let mut command = Command::new(&strategy.program);
+        command.args(&strategy.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+
+        if let Some(dir) = &strategy.dir {
+            command.current_dir(dir);
+        }
+
+        let Ok(mut child) = command.spawn() else {
+            return session;
+        };
+
+        // Lines are read on their own thread, which ends when the process closes its output
+        if let Some(stdout) = child.stdout.take() {
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        session.stdin = child.stdin.take();
+        session.child = Some(child);
+
+        let ready = session.send(&format!("start {0}", rules_line(rules)))
+            && session.receive(strategy.startup).is_some_and(|line| line == "ready");
+
+        if !ready {
+            session.stop();
+        }
+
+        session
+    }
+
+    /// Send a line, stopping the process if it cannot be written.
+    fn send(&mut self, line: &str) -> bool {
+        let sent = self.stdin.as_mut()
+            .is_some_and(|stdin| writeln!(stdin, "{line}").and_then(|_| stdin.flush()).is_ok());
+
+        if !sent {
+            self.stop();
+        }
+
+        sent
+    }
+
+    /// Receive a line within a timeout.
+    fn receive(&mut self, timeout: Duration) -> Option<String> {
+        self.child.as_ref()?;
+        self.lines.recv_timeout(timeout).ok().map(|line| String::from(line.trim()))
+    }
+
+    /// Ask the process for its color after some moves, of which all but the last were sent before.
+    fn turn(&mut self, last_moves: &[Move], rules: &Rules, timeout: Duration) -> Option<Color> {
+        if let Some(m) = last_moves.last() {
+            self.history.push(*m);
+        }
+
+        self.child.as_ref()?;
+
+        if *rules != self.rules {
+            self.rules = *rules;
+            if !self.send(&format!("rules {0}", rules_line(rules))) {
+                return None;
+            }
+        }
+
+        if !self.send(&format!("move {0}", last_move(last_moves))) {
+            return None;
+        }
+
+        let color = self.receive(timeout).and_then(|line| {
+            let mut letters = line.chars();
+            match (letters.next().and_then(Color::from_letter), letters.next()) {
+                (Some(color), None) => Some(color),
+                _ => None,
+            }
+        });
+
+        if color.is_none() {
+            self.stop();
+        }
+
+        color
+    }
+
+    /// Tell the process the game is over and let it exit within a timeout.
+    fn finish(mut self, moves: &[Move], scores: (Score, Score), timeout: Duration) {
+        if self.send(&format!("end {0} {1} {2}", last_move(moves), scores.0, scores.1)) {
+            self.stdin = None;
+
+            let deadline = Instant::now() + timeout;
+            while let Some(child) = &mut self.child {
+                if !matches!(child.try_wait(), Ok(None)) {
+                    self.child = None;
+                } else if Instant::now() >= deadline {
+                    break;
+                } else {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+
+        self.stop();
+    }
+
+    /// Stop the process, after which the session only plays the fallback color.
+    fn stop(&mut self) {
+        self.stdin = None;
+
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Write rules as they are sent to the process.
+fn rules_line(rules: &Rules) -> String {
+    let payoffs = Color::ALL.iter()
+        .flat_map(|own| Color::ALL.map(|opponent| rules.payoffs().score((*own, opponent)).0.to_string()))
+        .collect::<Vec<_>>();
+
+    format!("payoffs {0} doubling {1}", payoffs.join(" "), if rules.doubling() { "on" } else { "off" })
+}
+
+/// Write the last move as it is sent to the process.
+fn last_move(moves: &[Move]) -> String {
+    match moves.last() {
+        Some(m) => format!("{0}{1}", m.0.letter(), m.1.letter()),
+        None => String::from("-"),
+    }
+}
+
+/// A strategy played by a child process, started for every game.
+pub struct ProcessStrategy {
+    program: String,
+    args: Vec<String>,
+    dir: Option<PathBuf>,
+    timeout: Duration,
+    startup: Duration,
+    fallback: Color,
+    sessions: Mutex<Vec<Session>>,
+}
+
+impl ProcessStrategy {
+    /// Create a new strategy with a timeout of 100 ms per move, 5 s to start, and red as the fallback color.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The program to run
+    /// * `args` - The arguments of the program
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tourney::game::{Game, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::strategies::process::ProcessStrategy;
+    ///
+    /// let strategy = ProcessStrategy::new("python3", &["copy.py"]);
+    /// let scores = Game::new(100).play(&Player::from_strategy(strategy), &Player::new(tit_for_tat_prisoner));
+    /// ```
+    pub fn new(program: &str, args: &[&str]) -> ProcessStrategy {
+        ProcessStrategy {
+            program: String::from(program),
+            args: args.iter().map(|arg| String::from(*arg)).collect(),
+            dir: None,
+            timeout: Duration::from_millis(100),
+            startup: Duration::from_secs(5),
+            fallback: Color::Red,
+            sessions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Set the directory the program is run from.
+    pub fn set_dir(mut self, dir: &Path) -> ProcessStrategy {
+        self.dir = Some(dir.to_path_buf());
+        self
+    }
+
+    /// Set the time the process has to reply to a move.
+    pub fn set_timeout(mut self, timeout: Duration) -> ProcessStrategy {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the time the process has to start and reply to the handshake.
+    pub fn set_startup(mut self, startup: Duration) -> ProcessStrategy {
+        self.startup = startup;
+        self
+    }
+
+    /// Set the color played once the process has failed.
+    pub fn set_fallback(mut self, fallback: Color) -> ProcessStrategy {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Take the session that has been sent every move but the last.
+    fn take(&self, last_moves: &[Move]) -> Option<Session> {
+        let (_, previous) = last_moves.split_last()?;
+        let mut sessions = self.sessions.lock().unwrap_or_else(|error| error.into_inner());
+        let position = sessions.iter().position(|session| session.history == previous)?;
+        Some(sessions.swap_remove(position))
+    }
+}
+
+impl Strategy for ProcessStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        // A simulation gets a process of its own, which is caught up on the hypothetical moves
+        let session = if context.depth() == 0 { self.take(last_moves) } else { None };
+        let mut session = session.unwrap_or_else(|| {
+            let mut session = Session::start(self, context.rules());
+            for round in 0..last_moves.len() {
+                session.turn(&last_moves[..round], context.rules(), self.timeout);
+            }
+            session
+        });
+
+        let color = session.turn(last_moves, context.rules(), self.timeout).unwrap_or(self.fallback);
+
+        if context.depth() == 0 {
+            self.sessions.lock().unwrap_or_else(|error| error.into_inner()).push(session);
+        }
+
+        color
+    }
+
+    fn game_over(&self, moves: &[Move], scores: (Score, Score)) {
+        if let Some(session) = self.take(moves) {
+            session.finish(moves, scores, self.timeout);
+        }
+    }
+}
+
+impl Clone for ProcessStrategy {
+    /// Clone the configuration, without the processes of games being played.
+    fn clone(&self) -> ProcessStrategy {
+        ProcessStrategy {
+            program: self.program.clone(),
+            args: self.args.clone(),
+            dir: self.dir.clone(),
+            timeout: self.timeout,
+            startup: self.startup,
+            fallback: self.fallback,
+            sessions: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl fmt::Debug for ProcessStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProcessStrategy")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .field("dir", &self.dir)
+            .field("timeout", &self.timeout)
+            .field("startup", &self.startup)
+            .field("fallback", &self.fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ProcessStrategy {
+    fn eq(&self, other: &ProcessStrategy) -> bool {
+        (&self.program, &self.args, &self.dir, self.timeout, self.startup, self.fallback)
+            == (&other.program, &other.args, &other.dir, other.timeout, other.startup, other.fallback)
+    }
+}
+
+impl fmt::Display for ProcessStrategy {
+    /// Write the strategy in the text format it is [parsed](FromStr) from. The directory is not included.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut command = vec![self.program.as_str()];
+        command.extend(self.args.iter().map(String::as_str));
+
+        writeln!(f, "command {0}", command.join(" "))?;
+        writeln!(f, "timeout {0}", self.timeout.as_millis())?;
+        writeln!(f, "startup {0}", self.startup.as_millis())?;
+        writeln!(f, "fallback {0}", self.fallback.letter())
+    }
+}
+
+impl FromStr for ProcessStrategy {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<ProcessStrategy, ParseStrategyError> {
+        let mut strategy = None;
+        let mut timeout = None;
+        let mut startup = None;
+        let mut fallback = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let millis = |value: &str| value.parse::<u64>()
+                .ok()
+                .filter(|millis| *millis > 0)
+                .map(Duration::from_millis)
+                .ok_or_else(|| error(&format!("Invalid milliseconds: {value}")));
+
+            match words.as_slice() {
+                [] => {}
+                ["command", program, args @ ..] if strategy.is_none() => strategy = Some(ProcessStrategy::new(program, args)),
+                ["timeout", value] if timeout.is_none() => timeout = Some(millis(value)?),
+                ["startup", value] if startup.is_none() => startup = Some(millis(value)?),
+                ["fallback", color] if fallback.is_none() => {
+                    let mut letters = color.chars();
+                    match (letters.next().and_then(Color::from_letter), letters.next()) {
+                        (Some(color), None) => fallback = Some(color),
+                        _ => return Err(error(&format!("Invalid color: {color}"))),
+                    }
+                }
+                [key, ..] => return Err(error(&format!("Unexpected or repeated line: {key}"))),
+            }
+        }
+
+        let mut strategy = strategy.ok_or_else(|| ParseStrategyError::new(0, "Missing command"))?;
+        if let Some(timeout) = timeout {
+            strategy = strategy.set_timeout(timeout);
+        }
+        if let Some(startup) = startup {
+            strategy = strategy.set_startup(startup);
+        }
+        if let Some(fallback) = fallback {
+            strategy = strategy.set_fallback(fallback);
+        }
+
+        Ok(strategy)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Player};
+    use crate::programs::all::*;
+
+    /// A process that plays the opponent's last color, after green.
+    const COPY: &str = "read start; echo ready; while read kind last rest; do \
+        case $kind in move) case $last in -) echo G;; ?R) echo R;; ?G) echo G;; ?B) echo B;; esac;; end) exit 0;; esac; done";
+
+    fn shell(script: &str) -> Player {
+        Player::from_strategy(ProcessStrategy::new("sh", &["-c", script]).set_timeout(Duration::from_secs(5)))
+    }
+
+    #[test]
+    fn protocol_test() {
+        let player = shell(COPY);
+
+        for opponent in [random, evil, blue] {
+            let transcript = Game::new(30).record(&player, &Player::new(opponent)).unwrap();
+            let moves = transcript.moves();
+
+            assert_eq!(moves[0].0, Color::Green);
+            assert!(moves.windows(2).all(|m| m[1].0 == m[0].1));
+        }
+
+        // Both players run a process of their own
+        assert_eq!(Game::new(10).play(&player, &player), Ok((20, 20)));
+    }
+
+    #[test]
+    fn failure_test() {
+        let silent = Player::from_strategy(ProcessStrategy::new("sh", &["-c", "read start; echo ready; sleep 5"])
+            .set_timeout(Duration::from_millis(50))
+            .set_fallback(Color::Blue));
+        let crashing = shell("read start; echo ready; read move; echo X");
+        let missing = Player::from_strategy(ProcessStrategy::new("./does_not_exist", &[]));
+
+        let transcript = Game::new(5).record(&silent, &Player::new(friendly)).unwrap();
+        assert!(transcript.moves().iter().all(|m| m.0 == Color::Blue));
+
+        let transcript = Game::new(5).record(&crashing, &Player::new(friendly)).unwrap();
+        assert!(transcript.moves().iter().all(|m| m.0 == Color::Red));
+
+        let transcript = Game::new(5).record(&missing, &Player::new(friendly)).unwrap();
+        assert!(transcript.moves().iter().all(|m| m.0 == Color::Red));
+    }
+
+    #[test]
+    fn text_format_test() {
+        let strategy = "# Copy\ncommand python3 copy.py --fast\ntimeout 20\nfallback G\n".parse::<ProcessStrategy>().unwrap();
+        let expected = ProcessStrategy::new("python3", &["copy.py", "--fast"])
+            .set_timeout(Duration::from_millis(20))
+            .set_fallback(Color::Green);
+
+        assert_eq!(strategy, expected);
+        assert_eq!(strategy.to_string().parse::<ProcessStrategy>(), Ok(expected));
+
+        let error = "timeout 0\n".parse::<ProcessStrategy>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Invalid milliseconds: 0");
+
+        let error = "fallback G\n".parse::<ProcessStrategy>().unwrap_err();
+        assert_eq!(error.to_string(), "Missing command");
+    }
+}
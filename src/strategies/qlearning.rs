@@ -0,0 +1,369 @@
+//! `qlearning` contains an agent that learns which color to play by tabular Q-learning.
+//!
+//! The state of the agent is a window over the last moves, and a [table](QTable) holds the expected value of every
+//! color in every state. A [learner](QLearner) updates the table after every move it plays, with the payoff of the
+//! move as the reward, and [`train`] plays it against a roster of opponents and itself. The trained table is then
+//! played frozen: it picks the color of the highest value.
+//!
+//! A table is saved in a simple text format. It has the size of the window, and an entry for every state that was
+//! visited: the own colors and the opponent's colors (oldest first, `-` for an empty window) and the values of
+//! red, green and blue. Until the window is filled, the state holds every move so far:
+//!
+//! ```text
+//! # Trained against the programs
+//! memory 1
+//! - -  4.2 5.1 3.9
+//! R R  3.3 2.8 2.5
+//! G G  5.0 6.1 4.7
+//! ```
+
+use std::{collections::HashMap, fmt, str::FromStr, sync::{Arc, Mutex}};
+use rand::Rng;
+use crate::game::{Color, Move, Score, Context, Game, Player, Strategy, rng};
+use crate::rules::Rules;
+use crate::strategies::ParseStrategyError;
+
+/// The maximum amount of moves in a state.
+pub const MAX_MEMORY: usize = 4;
+
+/// A table of the expected value of every color, by the last moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QTable {
+    memory: usize,
+    values: HashMap<Vec<Move>, [f64; 3]>,
+}
+
+impl QTable {
+    /// Create a new table without any visited states.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The amount of last moves in a state
+    ///
+    /// # Returns
+    ///
+    /// The table, or `None` if `memory` is larger than [`MAX_MEMORY`].
+    pub fn new(memory: usize) -> Option<QTable> {
+        if memory > MAX_MEMORY {
+            return None;
+        }
+
+        Some(QTable {
+            memory,
+            values: HashMap::new(),
+        })
+    }
+
+    /// Get the amount of last moves in a state.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Get the amount of visited states.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no state was visited.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the state after the moves.
+    fn state<'a>(&self, last_moves: &'a [Move]) -> &'a [Move] {
+        &last_moves[last_moves.len().saturating_sub(self.memory)..]
+    }
+
+    /// Get the values of red, green and blue after the moves, 0 in a state that was never visited.
+    pub fn values(&self, last_moves: &[Move]) -> [f64; 3] {
+        self.values.get(self.state(last_moves)).copied().unwrap_or_default()
+    }
+
+    /// Get the color of the highest value after the moves. A tie goes to the earlier color of red, green and blue,
+    /// and a state that was never visited plays green.
+    pub fn best(&self, last_moves: &[Move]) -> Color {
+        let Some(values) = self.values.get(self.state(last_moves)) else {
+            return Color::Green;
+        };
+
+        Color::ALL.into_iter()
+            .fold(None, |best: Option<Color>, color| match best {
+                Some(best) if values[best.index()] >= values[color.index()] => Some(best),
+                _ => Some(color),
+            })
+            .unwrap_or(Color::Green)
+    }
+
+    /// Move the value of a color after the moves towards a target.
+    fn learn(&mut self, last_moves: &[Move], color: Color, target: f64, rate: f64) {
+        let values = self.values.entry(self.state(last_moves).to_vec()).or_default();
+        values[color.index()] += rate * (target - values[color.index()]);
+    }
+}
+
+impl Strategy for QTable {
+    fn next_move(&self, last_moves: &[Move], _context: &mut Context) -> Color {
+        self.best(last_moves)
+    }
+}
+
+/// The letters of colors, `-` if there are none.
+fn letters(colors: impl Iterator<Item = Color>) -> String {
+    let letters = colors.map(|color| color.letter()).collect::<String>();
+    if letters.is_empty() {
+        return String::from("-");
+    }
+
+    letters
+}
+
+impl fmt::Display for QTable {
+    /// Write the table in the text format it is [parsed](FromStr) from, ordered by state.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "memory {0}", self.memory)?;
+
+        let mut states = self.values.iter().collect::<Vec<_>>();
+        states.sort_by_key(|(state, _)| (state.len(), state.iter().map(|m| m.0.index() * 3 + m.1.index()).collect::<Vec<_>>()));
+
+        for (state, values) in states {
+            let own = letters(state.iter().map(|m| m.0));
+            let opponent = letters(state.iter().map(|m| m.1));
+            writeln!(f, "{0} {1}  {2} {3} {4}", own, opponent, values[0], values[1], values[2])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for QTable {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<QTable, ParseStrategyError> {
+        let mut table = None;
+        let colors = |letters: &str| match letters {
+            "-" => Some(Vec::new()),
+            _ => letters.chars().map(Color::from_letter).collect::<Option<Vec<_>>>(),
+        };
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |reason: &str| ParseStrategyError::new(i + 1, reason);
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match (words.as_slice(), &mut table) {
+                ([], _) => {}
+                (["memory", value], None) => {
+                    let memory = value.parse::<usize>().map_err(|_| error("Incorrect value for memory"))?;
+                    table = Some(QTable::new(memory).ok_or_else(|| error(&format!("Memory larger than {MAX_MEMORY} moves")))?);
+                }
+                (["memory", _], Some(_)) => return Err(error("Duplicate entry: memory")),
+                ([_, _, _, _, _], None) => return Err(error("Missing memory before the states")),
+                ([own, opponent, values @ ..], Some(table)) if values.len() == 3 => {
+                    let own = colors(own).ok_or_else(|| error("Invalid own colors"))?;
+                    let opponent = colors(opponent).ok_or_else(|| error("Invalid opponent colors"))?;
+                    if own.len() != opponent.len() || own.len() > table.memory {
+                        return Err(error("State does not match the memory of the table"));
+                    }
+
+                    let values = values.iter()
+                        .map(|value| value.parse::<f64>().ok().filter(|value| value.is_finite()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("Invalid value"))?;
+
+                    let state = own.into_iter().zip(opponent).collect::<Vec<_>>();
+                    if table.values.insert(state, [values[0], values[1], values[2]]).is_some() {
+                        return Err(error("Duplicate entry"));
+                    }
+                }
+                _ => return Err(error("Unknown entry")),
+            }
+        }
+
+        table.ok_or_else(|| ParseStrategyError::new(0, "Missing memory"))
+    }
+}
+
+/// The table of a learner, shared by its clones, and the rules of the last move it played.
+#[derive(Debug)]
+struct Shared {
+    table: QTable,
+    rules: Rules,
+}
+
+/// A strategy that updates a [table](QTable) after every move it plays.
+///
+/// The value of the last color moves towards its payoff by the rules in effect plus the discounted highest value
+/// of the new state, and the learner explores: it plays a random color with some probability, drawn from the
+/// [game's random number generator](rng), and otherwise the color of the highest value. The doubling at the end of
+/// the game is not learned. Clones share the table, which lets a learner play itself.
+#[derive(Debug, Clone)]
+pub struct QLearner {
+    shared: Arc<Mutex<Shared>>,
+    rate: f64,
+    discount: f64,
+    exploration: f64,
+}
+
+impl QLearner {
+    /// Create a new learner, with a learning rate of 0.1, a discount of 0.9 and an exploration of 0.1.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to start from
+    pub fn new(table: QTable) -> QLearner {
+        QLearner {
+            shared: Arc::new(Mutex::new(Shared { table, rules: Rules::default() })),
+            rate: 0.1,
+            discount: 0.9,
+            exploration: 0.1,
+        }
+    }
+
+    /// Set how far a value moves towards its target every update.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is not within `0.0..=1.0`.
+    pub fn set_rate(mut self, rate: f64) -> QLearner {
+        if !(0.0..=1.0).contains(&rate) {
+            panic!("Cannot learn at rate {rate}");
+        }
+
+        self.rate = rate;
+        self
+    }
+
+    /// Set how much the value of the next state counts towards the target of a value.
+    ///
+    /// # Panics
+    ///
+    /// If `discount` is not within `0.0..=1.0`.
+    pub fn set_discount(mut self, discount: f64) -> QLearner {
+        if !(0.0..=1.0).contains(&discount) {
+            panic!("Cannot discount by {discount}");
+        }
+
+        self.discount = discount;
+        self
+    }
+
+    /// Set the probability of playing a random color.
+    ///
+    /// # Panics
+    ///
+    /// If `exploration` is not within `0.0..=1.0`.
+    pub fn set_exploration(mut self, exploration: f64) -> QLearner {
+        if !(0.0..=1.0).contains(&exploration) {
+            panic!("Cannot explore with probability {exploration}");
+        }
+
+        self.exploration = exploration;
+        self
+    }
+
+    /// Get a copy of the table learned so far.
+    pub fn table(&self) -> QTable {
+        self.shared.lock().unwrap_or_else(|error| error.into_inner()).table.clone()
+    }
+}
+
+impl Strategy for QLearner {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let mut shared = self.shared.lock().unwrap_or_else(|error| error.into_inner());
+        shared.rules = *context.rules();
+
+        if let Some((last, before)) = last_moves.split_last() {
+            let reward = f64::from(shared.rules.payoffs().score(*last).0);
+            let future = shared.table.values(last_moves).into_iter().fold(f64::MIN, f64::max);
+            shared.table.learn(before, last.0, reward + self.discount * future, self.rate);
+        }
+
+        if rng().gen_bool(self.exploration) {
+            return Color::ALL[rng().gen_range(0..3)];
+        }
+
+        shared.table.best(last_moves)
+    }
+
+    fn game_over(&self, moves: &[Move], _scores: (Score, Score)) {
+        let mut shared = self.shared.lock().unwrap_or_else(|error| error.into_inner());
+
+        // The last move has no next state
+        if let Some((last, before)) = moves.split_last() {
+            let reward = f64::from(shared.rules.payoffs().score(*last).0);
+            shared.table.learn(before, last.0, reward, self.rate);
+        }
+    }
+}
+
+/// Train a learner by playing every opponent and itself, in every episode.
+///
+/// # Arguments
+///
+/// * `learner` - The learner to train
+/// * `opponents` - The opponents to play
+/// * `game` - The settings of the games
+/// * `episodes` - How many times every game is played
+/// * `seed` - The seed from which the seed of every game is derived
+///
+/// # Examples
+///
+/// ```
+/// use tourney::game::{Game, Player};
+/// use tourney::programs::all::*;
+/// use tourney::strategies::qlearning::{QLearner, QTable, train};
+///
+/// let learner = QLearner::new(QTable::new(1).unwrap());
+/// train(&learner, &[Player::new(tit_for_tat_prisoner), Player::new(evil)], &Game::new(20), 10, 7);
+///
+/// // Play the learned table frozen
+/// let player = Player::from_strategy(learner.table());
+/// ```
+pub fn train(learner: &QLearner, opponents: &[Player], game: &Game, episodes: u32, seed: u64) {
+    let player = Player::from_strategy(learner.clone());
+    let mut games = 0u64;
+
+    for _ in 0..episodes {
+        for opponent in opponents.iter().chain([&player]) {
+            // A game can only overflow with extreme payoffs, in which case the learner has learned from its moves
+            let _ = game.clone().set_seed(seed.wrapping_add(games)).play(&player, opponent);
+            games += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::all::*;
+
+    #[test]
+    fn parse_test() {
+        let table = "# Comment\nmemory 1\n- -  1 2 0\nR G  3 3 -1.5\n".parse::<QTable>().unwrap();
+
+        assert_eq!(table.best(&[]), Color::Green);
+        assert_eq!(table.best(&[(Color::Blue, Color::Blue), (Color::Red, Color::Green)]), Color::Red);
+        assert_eq!(table.best(&[(Color::Blue, Color::Blue)]), Color::Green);
+        assert_eq!(table.to_string().parse::<QTable>(), Ok(table));
+
+        assert_eq!("memory 1\nRR GG 0 0 0\n".parse::<QTable>(), Err(ParseStrategyError::new(2, "State does not match the memory of the table")));
+        assert_eq!("memory 9\n".parse::<QTable>(), Err(ParseStrategyError::new(1, "Memory larger than 4 moves")));
+        assert_eq!("- - 0 0 0\n".parse::<QTable>(), Err(ParseStrategyError::new(1, "Missing memory before the states")));
+        assert_eq!("memory 0\n- - 0 x 0\n".parse::<QTable>(), Err(ParseStrategyError::new(2, "Invalid value")));
+    }
+
+    #[test]
+    fn train_test() {
+        let learner = QLearner::new(QTable::new(1).unwrap()).set_exploration(0.2);
+        train(&learner, &[Player::new(friendly)], &Game::new(30), 50, 1);
+
+        // Red is the best response to green, and the learner looks at the last move only
+        let table = learner.table();
+        assert!(!table.is_empty() && table.len() <= 1 + 9);
+        assert_eq!(table.best(&[(Color::Red, Color::Green)]), Color::Red);
+
+        let frozen = Player::from_strategy(table);
+        let transcript = Game::new(20).record(&frozen, &Player::new(friendly)).unwrap();
+        assert!(transcript.moves()[1..].iter().all(|m| m.0 == Color::Red));
+    }
+}
@@ -0,0 +1,312 @@
+//! `script` contains strategies written in [Rhai](https://rhai.rs), an embedded scripting language, which can be
+//! changed without recompiling the crate.
+//!
+//! A [script](ScriptStrategy) is evaluated every move, and its last expression is the color to play: `Red`, `Green`
+//! or `Blue`. The moves are given as the constant `history`:
+//!
+//! * `history.round` - The index of the current round, counting from 0
+//! * `history.my`, `history.opp` - The moves of the player or its opponent, which have:
+//!     * `last` - The last color, `()` in round 0
+//!     * `moves` - An array of the colors, from the first round
+//!     * `red`, `green`, `blue` and `count(color)` - How many times a color was played
+//!     * `score` - The score so far by the rules in effect, without the doubling
+//!
+//! `random()` draws a number within `0.0..1.0` and `random(Red, Green)` (or of three colors) draws a color, both
+//! from the [game's random number generator](rng):
+//!
+//! ```text
+//! // Tit for tat that forgives red one time out of ten
+//! if history.opp.last == Red && random() >= 0.1 { Red } else { Green }
+//! ```
+//!
+//! Scripts cannot access files, and every move is limited in the amount of operations, the depth of calls and the
+//! size of strings and arrays. A move that fails, e.g. by running out of operations, or that does not give a color,
+//! is forfeited: the fallback color is played instead.
+
+use std::{fmt, fs, path::{Path, PathBuf}, sync::{Arc, RwLock}, time::SystemTime};
+use rand::Rng;
+use rhai::{AST, Array, Dynamic, Engine, Scope, module_resolvers::DummyModuleResolver};
+use crate::game::{Color, Move, Score, Context, Strategy, rng};
+use crate::rules::Rules;
+
+/// The moves of one of the players, as seen by a script.
+#[derive(Debug, Clone)]
+struct Side {
+    moves: Vec<Color>,
+    score: Score,
+}
+
+impl Side {
+    fn count(&self, color: Color) -> i64 {
+        self.moves.iter().filter(|other| **other == color).count() as i64
+    }
+}
+
+/// The moves of both players, as seen by a script.
+#[derive(Debug, Clone)]
+struct History {
+    my: Side,
+    opp: Side,
+}
+
+impl History {
+    fn new(last_moves: &[Move], rules: &Rules) -> History {
+        let scores = rules.calculate_scores(last_moves);
+
+        History {
+            my: Side { moves: last_moves.iter().map(|m| m.0).collect(), score: scores.0 },
+            opp: Side { moves: last_moves.iter().map(|m| m.1).collect(), score: scores.1 },
+        }
+    }
+}
+
+/// A compiled script, and when its file was last modified.
+struct Compiled {
+    ast: AST,
+    modified: Option<SystemTime>,
+}
+
+/// A strategy played by a script.
+#[derive(Clone)]
+pub struct ScriptStrategy {
+    engine: Arc<Engine>,
+    compiled: Arc<RwLock<Compiled>>,
+    path: Option<PathBuf>,
+    operations: u64,
+    fallback: Color,
+}
+
+impl ScriptStrategy {
+    /// Compile a script, with 100,000 operations per move and red as the fallback color.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The script
+    ///
+    /// # Errors
+    ///
+    /// If the script does not compile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tourney::game::{Game, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::strategies::ScriptStrategy;
+    ///
+    /// let strategy = ScriptStrategy::new("if history.opp.red > history.opp.green { Red } else { Green }").unwrap();
+    ///
+    /// let scores = Game::new(10).play(&Player::from_strategy(strategy), &Player::new(evil)).unwrap();
+    /// ```
+    pub fn new(source: &str) -> Result<ScriptStrategy, String> {
+        let operations = 100_000;
+        let engine = engine(operations);
+        let ast = engine.compile(source).map_err(|error| error.to_string())?;
+
+        Ok(ScriptStrategy {
+            engine: Arc::new(engine),
+            compiled: Arc::new(RwLock::new(Compiled { ast, modified: None })),
+            path: None,
+            operations,
+            fallback: Color::Red,
+        })
+    }
+
+    /// Set the file the script is [reloaded](ScriptStrategy::reload) from.
+    pub fn set_path(mut self, path: &Path) -> ScriptStrategy {
+        self.compiled.write().unwrap_or_else(|error| error.into_inner()).modified = modified(path);
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Set the amount of operations per move.
+    pub fn set_operations(mut self, operations: u64) -> ScriptStrategy {
+        self.engine = Arc::new(engine(operations));
+        self.operations = operations;
+        self
+    }
+
+    /// Set the color played when a move is forfeited.
+    pub fn set_fallback(mut self, fallback: Color) -> ScriptStrategy {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Compile the script again if its file was modified since it was last compiled. The script is shared with
+    /// every clone, which play the new script from their next move.
+    ///
+    /// # Returns
+    ///
+    /// Whether the script was compiled again, always `false` without a [file](ScriptStrategy::set_path).
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or the script does not compile, in which case the previous script is kept.
+    pub fn reload(&self) -> Result<bool, String> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+
+        let modified = modified(path);
+        if modified.is_some() && modified == self.compiled.read().unwrap_or_else(|error| error.into_inner()).modified {
+            return Ok(false);
+        }
+
+        let source = fs::read_to_string(path).map_err(|error| format!("Could not read {0} ({1})", path.display(), error))?;
+        let ast = self.engine.compile(source).map_err(|error| error.to_string())?;
+        *self.compiled.write().unwrap_or_else(|error| error.into_inner()) = Compiled { ast, modified };
+
+        Ok(true)
+    }
+}
+
+impl Strategy for ScriptStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let mut scope = Scope::new();
+        scope.push_constant("history", History::new(last_moves, context.rules()));
+        for color in Color::ALL {
+            scope.push_constant(format!("{color:?}"), color);
+        }
+
+        let compiled = self.compiled.read().unwrap_or_else(|error| error.into_inner());
+        self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &compiled.ast)
+            .ok()
+            .and_then(|value| value.try_cast::<Color>())
+            .unwrap_or(self.fallback)
+    }
+}
+
+impl fmt::Debug for ScriptStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptStrategy")
+            .field("path", &self.path)
+            .field("operations", &self.operations)
+            .field("fallback", &self.fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Get when a file was last modified, `None` if unknown.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Create an engine without access to files or output, limited to an amount of operations per evaluation.
+fn engine(operations: u64) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {})
+        .set_max_operations(operations)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(10_000)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000);
+
+    engine.register_type_with_name::<Color>("Color")
+        .register_fn("==", |a: Color, b: Color| a == b)
+        .register_fn("!=", |a: Color, b: Color| a != b)
+        .register_fn("to_string", |color: &mut Color| format!("{color:?}"))
+        .register_fn("to_debug", |color: &mut Color| format!("{color:?}"))
+        .register_fn("random", || rng().gen::<f64>())
+        .register_fn("random", |a: Color, b: Color| [a, b][rng().gen_range(0..2)])
+        .register_fn("random", |a: Color, b: Color, c: Color| [a, b, c][rng().gen_range(0..3)]);
+
+    engine.register_type_with_name::<History>("History")
+        .register_get("round", |history: &mut History| history.my.moves.len() as i64)
+        .register_get("my", |history: &mut History| history.my.clone())
+        .register_get("opp", |history: &mut History| history.opp.clone());
+
+    engine.register_type_with_name::<Side>("Side")
+        .register_get("last", |side: &mut Side| side.moves.last().map_or(Dynamic::UNIT, |color| Dynamic::from(*color)))
+        .register_get("moves", |side: &mut Side| side.moves.iter().map(|color| Dynamic::from(*color)).collect::<Array>())
+        .register_get("red", |side: &mut Side| side.count(Color::Red))
+        .register_get("green", |side: &mut Side| side.count(Color::Green))
+        .register_get("blue", |side: &mut Side| side.count(Color::Blue))
+        .register_get("score", |side: &mut Side| side.score)
+        .register_fn("count", |side: &mut Side, color: Color| side.count(color));
+
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::Duration;
+    use super::*;
+    use crate::game::{Game, Player, Program};
+    use crate::programs::all::*;
+
+    fn colors(player: &Player, opponent: Program, rounds: u32) -> Vec<Color> {
+        let transcript = Game::new(rounds).set_seed(3).record(player, &Player::new(opponent)).unwrap();
+        transcript.moves().iter().map(|m| m.0).collect()
+    }
+
+    #[test]
+    fn history_test() {
+        let tit_for_tat = ScriptStrategy::new("if history.opp.last == Red { Red } else { Green }").unwrap();
+        let player = Player::from_strategy(tit_for_tat);
+        for opponent in [random, evil, blue] {
+            let expected = colors(&Player::new(tit_for_tat_prisoner), opponent, 30);
+            assert_eq!(colors(&player, opponent, 30), expected);
+        }
+
+        // Blue gives away a point every round, which the score of the player follows
+        let script = "let h = history; if h.round == 2 && h.my.score == 2 && h.opp.count(Blue) == 2 && h.my.moves.len() == 2 { Blue } else { Green }";
+        let player = Player::from_strategy(ScriptStrategy::new(script).unwrap());
+        assert_eq!(colors(&player, blue, 3), [Color::Green, Color::Green, Color::Blue]);
+
+        let player = Player::from_strategy(ScriptStrategy::new("random(Red, Blue)").unwrap());
+        assert!(colors(&player, evil, 50).iter().all(|color| *color != Color::Green));
+    }
+
+    #[test]
+    fn limits_test() {
+        let forfeits = [
+            "loop {}",
+            "history = 1; Green",
+            "\"Green\"",
+            "let s = \"x\"; loop { s += s; }",
+            "import \"other\" as other; Green",
+            "fn f(n) { f(n + 1) } f(0)",
+        ];
+
+        for script in forfeits {
+            let player = Player::from_strategy(ScriptStrategy::new(script).unwrap().set_fallback(Color::Blue));
+            assert_eq!(colors(&player, friendly, 3), vec![Color::Blue; 3], "{script}");
+        }
+
+        let counting = "let n = 0; for i in 0..1000 { n += i; } Green";
+        let player = Player::from_strategy(ScriptStrategy::new(counting).unwrap().set_operations(100));
+        assert_eq!(colors(&player, friendly, 3), vec![Color::Red; 3]);
+        assert!(ScriptStrategy::new("if {").is_err());
+    }
+
+    #[test]
+    fn reload_test() {
+        let path = std::env::temp_dir().join("tourney_reload_test.rhai");
+        fs::write(&path, "Green").unwrap();
+        let strategy = ScriptStrategy::new("Green").unwrap().set_path(&path);
+        let player = Player::from_strategy(strategy.clone());
+
+        assert_eq!(strategy.reload(), Ok(false));
+
+        let touch = |source: &str, seconds: u64| {
+            fs::write(&path, source).unwrap();
+            File::options().write(true).open(&path).unwrap().set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)).unwrap();
+        };
+
+        touch("Red", 1);
+        assert_eq!(strategy.reload(), Ok(true));
+        assert_eq!(colors(&player, friendly, 2), vec![Color::Red; 2]);
+
+        // A broken script keeps the previous version
+        touch("if {", 2);
+        assert!(strategy.reload().is_err());
+        assert_eq!(colors(&player, friendly, 2), vec![Color::Red; 2]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
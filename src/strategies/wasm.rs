@@ -0,0 +1,249 @@
+//! `wasm` contains strategies compiled to WebAssembly, which run in an interpreter without access to the
+//! tournament process.
+//!
+//! A module exports its `memory` and two functions:
+//!
+//! * `alloc(len: i32) -> i32` - Get the address of `len` bytes to which the moves are written
+//! * `next_move(ptr: i32, len: i32) -> i32` - Choose a color (0 for red, 1 for green, 2 for blue) after the moves
+//!   at `ptr`, two bytes per move with the own color first, encoded the same way
+//!
+//! It may import `env.random() -> i32`, which draws from the [game's random number generator](rng).
+//!
+//! Every move runs in a fresh instance with a limited amount of fuel and memory, and the fuel used is
+//! [charged](Context::charge) to the player. A move that traps, e.g. by running out of fuel or memory, or that
+//! returns something other than a color, is forfeited: the fallback color is played instead.
+
+use std::{fmt, sync::Arc};
+use rand::Rng;
+use wasmi::{Config, Engine, ExternType, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, core::ValType};
+use crate::game::{Color, Move, Context, Strategy, rng};
+
+/// A strategy played by a WebAssembly module.
+#[derive(Clone)]
+pub struct WasmStrategy {
+    engine: Engine,
+    module: Arc<Module>,
+    linker: Arc<Linker<StoreLimits>>,
+    fuel: u64,
+    memory: usize,
+    fallback: Color,
+}
+
+impl WasmStrategy {
+    /// Compile a module, with 1,000,000 fuel per move, 1 MiB of memory and red as the fallback color.
+    ///
+    /// # Arguments
+    ///
+    /// * `wasm` - The module in the binary format
+    ///
+    /// # Errors
+    ///
+    /// If the module is invalid, or if it does not export the memory and functions described in the
+    /// [module documentation](self).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::fs;
+    /// use tourney::game::{Game, Player};
+    /// use tourney::programs::all::*;
+    /// use tourney::strategies::wasm::WasmStrategy;
+    ///
+    /// let strategy = WasmStrategy::new(&fs::read("tit_for_tat.wasm").unwrap()).unwrap();
+    /// let game = Game::new(100).set_penalty(1000);
+    ///
+    /// let scores = game.play(&Player::from_strategy(strategy), &Player::new(evil));
+    /// ```
+    pub fn new(wasm: &[u8]) -> Result<WasmStrategy, String> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|error| error.to_string())?;
+
+        let export = |name: &str| module.exports().find(|export| export.name() == name).map(|export| export.ty().clone());
+        let function = |name: &str, params: &[ValType]| match export(name) {
+            Some(ExternType::Func(ty)) if ty.params() == params && ty.results() == [ValType::I32] => Ok(()),
+            _ => Err(format!("Missing export: {name}")),
+        };
+
+        function("alloc", &[ValType::I32])?;
+        function("next_move", &[ValType::I32, ValType::I32])?;
+        if !matches!(export("memory"), Some(ExternType::Memory(_))) {
+            return Err(String::from("Missing export: memory"));
+        }
+
+        let mut linker = Linker::new(&engine);
+        linker.func_wrap("env", "random", || -> i32 { rng().gen() })
+            .map_err(|error| error.to_string())?;
+
+        Ok(WasmStrategy {
+            engine,
+            module: Arc::new(module),
+            linker: Arc::new(linker),
+            fuel: 1_000_000,
+            memory: 1 << 20,
+            fallback: Color::Red,
+        })
+    }
+
+    /// Set the fuel per move, roughly the amount of instructions that may be executed.
+    pub fn set_fuel(mut self, fuel: u64) -> WasmStrategy {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Set the largest size in bytes a memory may grow to.
+    pub fn set_memory(mut self, memory: usize) -> WasmStrategy {
+        self.memory = memory;
+        self
+    }
+
+    /// Set the color played when a move is forfeited.
+    pub fn set_fallback(mut self, fallback: Color) -> WasmStrategy {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Run the module in a fresh instance.
+    ///
+    /// # Returns
+    ///
+    /// The color, or `None` if the move is forfeited, and the fuel used.
+    fn run(&self, last_moves: &[Move]) -> (Option<Color>, u64) {
+        let mut store = Store::new(&self.engine, StoreLimitsBuilder::new().memory_size(self.memory).build());
+        store.limiter(|limits| limits);
+
+        if store.set_fuel(self.fuel).is_err() {
+            return (None, 0);
+        }
+
+        let color = (|| {
+            let instance = self.linker.instantiate(&mut store, &self.module).ok()?.start(&mut store).ok()?;
+            let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc").ok()?;
+            let next_move = instance.get_typed_func::<(i32, i32), i32>(&store, "next_move").ok()?;
+            let memory = instance.get_memory(&store, "memory")?;
+
+            let bytes = last_moves.iter()
+                .flat_map(|m| [m.0.index() as u8, m.1.index() as u8])
+                .collect::<Vec<_>>();
+            let len = i32::try_from(bytes.len()).ok()?;
+
+            let ptr = alloc.call(&mut store, len).ok()?;
+            memory.write(&mut store, usize::try_from(ptr).ok()?, &bytes).ok()?;
+
+            let color = next_move.call(&mut store, (ptr, len)).ok()?;
+            usize::try_from(color).ok().and_then(|index| Color::ALL.get(index).copied())
+        })();
+
+        (color, self.fuel - store.get_fuel().unwrap_or(0))
+    }
+}
+
+impl Strategy for WasmStrategy {
+    fn next_move(&self, last_moves: &[Move], context: &mut Context) -> Color {
+        let (color, fuel) = self.run(last_moves);
+        context.charge(fuel);
+
+        color.unwrap_or(self.fallback)
+    }
+}
+
+impl fmt::Debug for WasmStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmStrategy")
+            .field("fuel", &self.fuel)
+            .field("memory", &self.memory)
+            .field("fallback", &self.fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Player};
+    use crate::programs::all::*;
+
+    /// Tit for tat, reading the opponent's last color from the moves.
+    const TIT_FOR_TAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param $len i32) (result i32) (i32.const 0))
+            (func (export "next_move") (param $ptr i32) (param $len i32) (result i32)
+                (if (result i32) (i32.eqz (local.get $len))
+                    (then (i32.const 1))
+                    (else
+                        (if (result i32) (i32.eqz (i32.load8_u (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 1))))
+                            (then (i32.const 0))
+                            (else (i32.const 1)))))))
+    "#;
+
+    /// A module with `body` as the body of `next_move`.
+    fn module(body: &str) -> Vec<u8> {
+        let text = format!(r#"
+            (module
+                (import "env" "random" (func $random (result i32)))
+                (memory (export "memory") 1)
+                (func (export "alloc") (param $len i32) (result i32) (i32.const 0))
+                (func (export "next_move") (param $ptr i32) (param $len i32) (result i32) {body}))
+        "#);
+
+        wat::parse_str(text).unwrap()
+    }
+
+    #[test]
+    fn tit_for_tat_test() {
+        let strategy = WasmStrategy::new(&wat::parse_str(TIT_FOR_TAT).unwrap()).unwrap();
+        let player = Player::from_strategy(strategy);
+
+        for opponent in [random, evil, blue, tit_for_tat_prisoner] {
+            let game = Game::new(50).set_seed(3);
+            let expected = game.record(&Player::new(tit_for_tat_prisoner), &Player::new(opponent)).unwrap();
+            let actual = game.record(&player, &Player::new(opponent)).unwrap();
+
+            assert_eq!(actual.moves(), expected.moves());
+        }
+    }
+
+    #[test]
+    fn forfeit_test() {
+        let forfeits = [
+            module("(loop $forever (br $forever)) (i32.const 1)"),
+            module("(drop (memory.grow (i32.const 100))) (unreachable)"),
+            module("(i32.const 3)"),
+        ];
+
+        for wasm in forfeits {
+            let player = Player::from_strategy(WasmStrategy::new(&wasm).unwrap().set_fallback(Color::Blue));
+            let transcript = Game::new(3).record(&player, &Player::new(friendly)).unwrap();
+
+            assert!(transcript.moves().iter().all(|m| m.0 == Color::Blue));
+        }
+
+        // Memory beyond the limit cannot be grown
+        let wasm = module("(if (result i32) (i32.eq (memory.grow (i32.const 100)) (i32.const -1)) (then (i32.const 1)) (else (i32.const 0)))");
+        let player = Player::from_strategy(WasmStrategy::new(&wasm).unwrap());
+        assert!(Game::new(3).record(&player, &Player::new(friendly)).unwrap().moves().iter().all(|m| m.0 == Color::Green));
+
+        let error = WasmStrategy::new(&wat::parse_str("(module (memory (export \"memory\") 1))").unwrap()).unwrap_err();
+        assert_eq!(error, "Missing export: alloc");
+    }
+
+    #[test]
+    fn fuel_penalty_test() {
+        // Counting down from 10,000 uses a lot more fuel than the other player
+        let costly = module("(local $i i32) (local.set $i (i32.const 10000)) \
+            (loop $count (local.set $i (i32.sub (local.get $i) (i32.const 1))) (br_if $count (local.get $i))) (i32.const 1)");
+        let player = Player::from_strategy(WasmStrategy::new(&costly).unwrap());
+        let cheap = Player::from_strategy(WasmStrategy::new(&wat::parse_str(TIT_FOR_TAT).unwrap()).unwrap());
+
+        let scores = Game::new(10).set_penalty(1000).play(&player, &cheap).unwrap();
+        assert!(scores.0 < 0 && scores.1 == 20);
+
+        // The random number generator of the game is available
+        let random = Player::from_strategy(WasmStrategy::new(&module("(i32.rem_u (call $random) (i32.const 3))")).unwrap());
+        let game = Game::new(20).set_seed(9);
+        assert_eq!(game.record(&random, &cheap).unwrap().moves(), game.record(&random, &cheap).unwrap().moves());
+    }
+}
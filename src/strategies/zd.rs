@@ -0,0 +1,192 @@
+//! `zd` generates zero-determinant strategies: [memory-one strategies](MemoryOne) that enforce a linear
+//! [relation](Relation) between the average scores per round of both players, whatever the opponent plays.
+//!
+//! With two colors the strategies of Press and Dyson apply directly. With three colors the strategy mixes
+//! between two of them, a low and a high color. Writing `f = α·S_X + β·S_Y + γ` for the value of the relation
+//! after each move, the relation can be enforced if `f ≥ 0` after every move in which the strategy played the
+//! low color and `f ≤ 0` after every move in which it played the high color, against any color of the opponent.
+//! The strategy then plays the high color with probability `[played high] + f / d`, where `d` is the largest
+//! `|f|` of those moves.
+//!
+//! The blue column often rules this out: an opponent that plays blue gives away a point whatever the strategy
+//! plays, so e.g. extortion with the default payoffs cannot be enforced. The relation also concerns the scores
+//! before the doubling at the end of the game, and only holds on average over long games.
+
+use std::fmt;
+use crate::game::Color;
+use crate::rules::Payoffs;
+use crate::strategies::{Mix, MemoryOne};
+
+/// A linear relation `α·s_X + β·s_Y + γ = 0` between the average scores per round of the strategy (`s_X`) and
+/// its opponent (`s_Y`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relation {
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+}
+
+impl Relation {
+    /// Create a new relation `α·s_X + β·s_Y + γ = 0`.
+    pub fn new(alpha: f64, beta: f64, gamma: f64) -> Relation {
+        Relation {
+            alpha,
+            beta,
+            gamma,
+        }
+    }
+
+    /// Create an extortionate relation `s_X - P = χ·(s_Y - P)`, where `P` is the payoff of mutual red.
+    ///
+    /// # Arguments
+    ///
+    /// * `payoffs` - The [payoff matrix](Payoffs)
+    /// * `chi` - The extortion factor `χ`, at least 1
+    pub fn extortion(payoffs: &Payoffs, chi: f64) -> Relation {
+        let baseline = f64::from(payoffs.score((Color::Red, Color::Red)).0);
+        Relation::new(1.0, -chi, (chi - 1.0) * baseline)
+    }
+
+    /// Create a generous relation `s_X - R = χ·(s_Y - R)`, where `R` is the payoff of mutual green.
+    ///
+    /// # Arguments
+    ///
+    /// * `payoffs` - The [payoff matrix](Payoffs)
+    /// * `chi` - The generosity factor `χ`, at least 1
+    pub fn generous(payoffs: &Payoffs, chi: f64) -> Relation {
+        let baseline = f64::from(payoffs.score((Color::Green, Color::Green)).0);
+        Relation::new(1.0, -chi, (chi - 1.0) * baseline)
+    }
+
+    /// Create an equalizing relation `s_Y = score`, which fixes the score of the opponent.
+    pub fn equalizer(score: f64) -> Relation {
+        Relation::new(0.0, 1.0, -score)
+    }
+
+    /// Get `α·s_X + β·s_Y + γ`, which is 0 if the relation holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `own` - The average score per round of the strategy
+    /// * `opponent` - The average score per round of the opponent
+    pub fn value(&self, own: f64, opponent: f64) -> f64 {
+        self.alpha * own + self.beta * opponent + self.gamma
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{0}·s_X + {1}·s_Y + {2} = 0", self.alpha, self.beta, self.gamma)
+    }
+}
+
+/// Find a memory-one strategy that enforces a relation.
+///
+/// Pairs of colors without blue are tried first. The strategy opens with its high color.
+///
+/// # Arguments
+///
+/// * `payoffs` - The [payoff matrix](Payoffs)
+/// * `relation` - The [relation](Relation) to enforce
+///
+/// # Returns
+///
+/// The strategy, or `None` if no memory-one strategy can enforce the relation by mixing two colors.
+///
+/// # Examples
+///
+/// ```
+/// use tourney::rules::Payoffs;
+/// use tourney::strategies::zd::{Relation, solve};
+///
+/// let payoffs = Payoffs::default();
+///
+/// // The opponent's score is the opposite of the own score
+/// assert!(solve(&payoffs, &Relation::new(1.0, 1.0, 0.0)).is_some());
+///
+/// // Blue gives the opponent a way out of extortion
+/// assert!(solve(&payoffs, &Relation::extortion(&payoffs, 2.0)).is_none());
+/// ```
+pub fn solve(payoffs: &Payoffs, relation: &Relation) -> Option<MemoryOne> {
+    use Color::*;
+
+    let value = |own: Color, opponent: Color| {
+        let (x, y) = payoffs.score((own, opponent));
+        relation.value(f64::from(x), f64::from(y))
+    };
+
+    for (low, high) in [(Red, Green), (Green, Red), (Red, Blue), (Blue, Red), (Green, Blue), (Blue, Green)] {
+        let feasible = Color::ALL.iter().all(|opponent| value(low, *opponent) >= 0.0 && value(high, *opponent) <= 0.0);
+        let scale = Color::ALL.iter()
+            .map(|opponent| value(low, *opponent).max(-value(high, *opponent)))
+            .fold(0.0, f64::max);
+
+        if !feasible || scale <= 0.0 {
+            continue;
+        }
+
+        let mix = |probability: f64| {
+            let probability = probability.clamp(0.0, 1.0);
+            let mut weights = [0.0; 3];
+            weights[low.index()] = 1.0 - probability;
+            weights[high.index()] += probability;
+            Mix::new(weights[0], weights[1], weights[2]).unwrap_or(Mix::pure(high))
+        };
+
+        let mut strategy = MemoryOne::new(Mix::pure(high), [[Mix::pure(high); 3]; 3]);
+        for opponent in Color::ALL {
+            strategy = strategy
+                .set_response((low, opponent), mix(value(low, opponent) / scale))
+                .set_response((high, opponent), mix(1.0 + value(high, opponent) / scale));
+        }
+
+        return Some(strategy);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Player};
+    use crate::programs::all::*;
+
+    #[test]
+    fn press_dyson_test() {
+        // When blue plays like red, the two-color extortionate strategies of Press and Dyson are found
+        let payoffs = Payoffs::new([[1, 3, 1], [0, 2, 0], [1, 3, 1]]);
+        let strategy = solve(&payoffs, &Relation::extortion(&payoffs, 2.0)).unwrap();
+
+        assert_eq!(strategy.opening(), Mix::pure(Color::Green));
+        assert_eq!(strategy.response((Color::Red, Color::Red)), Mix::pure(Color::Red));
+        assert_eq!(strategy.response((Color::Green, Color::Green)).probability(Color::Green), 0.8);
+    }
+
+    #[test]
+    fn enforce_test() {
+        let payoffs = Payoffs::default();
+        let relation = Relation::new(1.0, 1.0, 0.0);
+        let player = Player::from_strategy(solve(&payoffs, &relation).unwrap());
+
+        for opponent in [Player::new(random), Player::new(tit_for_tat_prisoner), Player::new(blue)] {
+            let transcript = Game::new(10000).set_seed(7).record(&player, &opponent).unwrap();
+            let (own, other) = transcript.moves().iter().fold((0, 0), |acc, m| {
+                let score = payoffs.score(*m);
+                (acc.0 + score.0, acc.1 + score.1)
+            });
+
+            let rounds = transcript.moves().len() as f64;
+            assert!(relation.value(f64::from(own) / rounds, f64::from(other) / rounds).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn infeasible_test() {
+        let payoffs = Payoffs::default();
+
+        assert!(solve(&payoffs, &Relation::generous(&payoffs, 2.0)).is_none());
+        assert!(solve(&payoffs, &Relation::equalizer(1.0)).is_none());
+        assert!(solve(&payoffs, &Relation::new(0.0, 0.0, 0.0)).is_none());
+    }
+}